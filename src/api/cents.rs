@@ -0,0 +1,44 @@
+use std::str::FromStr; // Use of #from_str.
+
+use api::client::{ApiServiceResult, Transaction};
+use api::error::TellerClientError;
+
+/// Reads an amount such as `-2.70` as a whole number of cents, so that adding
+/// amounts up is exact.
+pub fn to_cents(amount: &str) -> ApiServiceResult<i64> {
+    f64::from_str(amount.trim())
+        .ok()
+        .and_then(|a| if a.is_finite() { Some((a * 100f64).round() as i64) } else { None })
+        .ok_or(TellerClientError::InputError(format!("Invalid amount `{}`", amount)))
+}
+
+/// Writes a whole number of cents as an amount such as `-2.70`.
+pub fn from_cents(cents: i64) -> String {
+    format!("{:.2}", cents as f64 / 100f64)
+}
+
+/// The amount of each of some transactions in cents, in the same order.
+pub fn to_transaction_cents(transactions: &[Transaction]) -> ApiServiceResult<Vec<i64>> {
+    transactions.iter().map(|t| to_cents(&t.amount)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{to_cents, from_cents};
+
+    #[test]
+    fn can_convert_amounts_to_and_from_cents() {
+        assert_eq!(-270, to_cents("-2.70").unwrap());
+        assert_eq!(150000, to_cents("1500.00").unwrap());
+        assert_eq!(1, to_cents("0.005").unwrap());
+        assert_eq!("-2.70", from_cents(-270));
+        assert_eq!("0.00", from_cents(0));
+    }
+
+    #[test]
+    fn can_reject_invalid_amounts() {
+        assert!(to_cents("").is_err());
+        assert!(to_cents("1,234.56").is_err());
+        assert!(to_cents("NaN").is_err());
+    }
+}
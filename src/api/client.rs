@@ -6,7 +6,9 @@ use chrono::{Date, DateTime, UTC};
 use std::io::prelude::*; // Required for read_to_string use later.
 
 use api::error::TellerClientError;
+use redact::{SecretKind, add_secret};
 
+/// The result of anything which talks to the Teller API or reads a statement.
 pub type ApiServiceResult<T> = Result<T, TellerClientError>;
 
 type AccountResponse = Account;
@@ -15,7 +17,8 @@ type AccountsResponse = Vec<Account>;
 
 type TransactionsResponse = Vec<Transaction>;
 
-#[derive(Debug, RustcDecodable, Clone)]
+/// A bank account as returned by the Teller API. Amounts are decimal strings.
+#[derive(Debug, RustcEncodable, RustcDecodable, Clone)]
 pub struct Account {
     pub account_number: String,
     pub balance: String,
@@ -27,21 +30,39 @@ pub struct Account {
     pub name: String,
 }
 
-#[derive(Debug, RustcDecodable, Clone)]
+/// A transaction as returned by the Teller API, dated `YYYY-MM-DD`. Outgoing
+/// amounts are negative.
+#[derive(Debug, RustcEncodable, RustcDecodable, Clone)]
 pub struct Transaction {
     pub amount: String,
     pub counterparty: String,
     pub date: String,
     pub description: String,
-    // pub id: String,
+    pub id: String,
     // pub running_balance: String,
     // pub type: String,
 }
 
+/// Masks the id, number and bank code of an account from now on.
+pub fn add_account_secrets(account: &Account) {
+    add_secret(SecretKind::AccountId, &account.id);
+    add_secret(SecretKind::AccountNumber, &account.account_number);
+    add_secret(SecretKind::BankCode, &account.bank_code);
+}
+
+/// Masks the counterparties of some transactions from now on.
+pub fn add_transaction_secrets(transactions: &[Transaction]) {
+    for transaction in transactions.iter() {
+        add_secret(SecretKind::Counterparty, &transaction.counterparty);
+    }
+}
+
+/// The date of a transaction, at midnight UTC.
 pub fn parse_utc_date_from_transaction(t: &Transaction) -> Date<UTC> {
     generate_utc_date_from_date_str(&t.date)
 }
 
+/// Parses a `YYYY-MM-DD` date as midnight UTC, panicking if it is invalid.
 pub fn generate_utc_date_from_date_str(d: &str) -> Date<UTC> {
     let full_date = &(d.to_owned() + "T00:00:00-00:00");
     let date_without_tz = DateTime::parse_from_rfc3339(full_date).unwrap().date();
@@ -51,28 +72,60 @@ pub fn generate_utc_date_from_date_str(d: &str) -> Date<UTC> {
 
 const TELLER_API_SERVER_URL: &'static str = "https://api.teller.io";
 
-pub struct TellerClient<'a> {
+/// A client for the Teller API, which owns the `auth_token` it sends.
+///
+/// ```no_run
+/// use teller::api::TellerClient;
+///
+/// let teller = TellerClient::new("auth-token");
+/// let accounts = teller.get_accounts().unwrap();
+/// ```
+pub struct TellerClient {
     client: Client,
-    auth_token: &'a str,
+    auth_token: String,
+}
+
+/// Builds a `TellerClient`, e.g. to give it a preconfigured `hyper::Client`.
+pub struct TellerClientBuilder {
+    client: Option<Client>,
+    auth_token: String,
 }
 
-impl<'a> TellerClient<'a> {
-    pub fn new(auth_token: &'a str) -> TellerClient {
-        let client = Client::new();
+impl TellerClientBuilder {
+    /// Sends requests through this client instead of a default one.
+    pub fn hyper_client(mut self, client: Client) -> TellerClientBuilder {
+        self.client = Some(client);
+        self
+    }
+
+    /// Builds the client, with a default `hyper::Client` unless one was given.
+    pub fn build(self) -> TellerClient {
         TellerClient {
-            client: client,
-            auth_token: auth_token,
+            client: self.client.unwrap_or_else(Client::new),
+            auth_token: self.auth_token,
         }
     }
+}
 
-    #[allow(dead_code)]
-    pub fn new_with_hyper_client(auth_token: &'a str, client: Client) -> TellerClient {
-        TellerClient {
-            client: client,
-            auth_token: auth_token,
+impl TellerClient {
+    /// Starts building a client which sends `auth_token`.
+    pub fn builder<S: Into<String>>(auth_token: S) -> TellerClientBuilder {
+        TellerClientBuilder {
+            client: None,
+            auth_token: auth_token.into(),
         }
     }
 
+    /// A client which sends `auth_token` through a default `hyper::Client`.
+    pub fn new<S: Into<String>>(auth_token: S) -> TellerClient {
+        TellerClient::builder(auth_token).build()
+    }
+
+    /// A client which sends `auth_token` through `client`.
+    pub fn new_with_hyper_client<S: Into<String>>(auth_token: S, client: Client) -> TellerClient {
+        TellerClient::builder(auth_token).hyper_client(client).build()
+    }
+
     fn get_body(&self, url: &str) -> ApiServiceResult<String> {
         let mut res = try!(self.client.get(url)
                                .header(Authorization(
@@ -91,20 +144,27 @@ impl<'a> TellerClient<'a> {
         Ok(body)
     }
 
+    /// Lists every account which the `auth_token` can see.
     pub fn get_accounts(&self) -> ApiServiceResult<Vec<Account>> {
         let body = try!(self.get_body(&format!("{}/accounts", TELLER_API_SERVER_URL)));
         let accounts_response: AccountsResponse = try!(json::decode(&body));
+        for account in accounts_response.iter() {
+            add_account_secrets(&account);
+        }
 
         Ok(accounts_response)
     }
 
+    /// Gets a single account by its id.
     pub fn get_account(&self, account_id: &str) -> ApiServiceResult<Account> {
         let body = try!(self.get_body(&format!("{}/accounts/{}", TELLER_API_SERVER_URL, account_id)));
         let account_response: AccountResponse = try!(json::decode(&body));
+        add_account_secrets(&account_response);
 
         Ok(account_response)
     }
 
+    /// Gets one page of an account's transactions, newest first.
     pub fn raw_transactions(&self,
                             account_id: &str,
                             page_size: u32,
@@ -121,10 +181,12 @@ impl<'a> TellerClient<'a> {
 
         let body = try!(self.get_body(&url.as_str()));
         let transactions_response: TransactionsResponse = try!(json::decode(&body));
+        add_transaction_secrets(&transactions_response);
 
         Ok(transactions_response)
     }
 
+    /// Gets an account's transactions between two dates, oldest first.
     #[allow(unused_variables)]
     pub fn get_transactions(&self,
                             account_id: &str,
@@ -223,6 +285,7 @@ mod tests {
             date: "2016-01-21".to_string(),
             counterparty: "NANNA'S".to_string(),
             amount: "-10.00".to_string(),
+            id: "some-long-uuid".to_string(),
         };
         assert!(true);
     }
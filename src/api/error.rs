@@ -7,12 +7,14 @@ use hyper::error::Error as HttpError;
 use std::io::Error as StdIoError;
 use rustc_serialize::json::DecoderError;
 
+/// Why the Teller API or a statement could not be read.
 #[derive(Debug)]
 pub enum TellerClientError {
     AuthenticationError,
     HttpClientError(HttpError),
     IoError(StdIoError),
     JsonParseError(DecoderError),
+    InputError(String),
 }
 
 impl Display for TellerClientError {
@@ -46,6 +48,7 @@ impl StdError for TellerClientError {
             TellerClientError::HttpClientError(ref err) => err.description(),
             TellerClientError::IoError(ref err) => err.description(),
             TellerClientError::JsonParseError(ref err) => err.description(),
+            TellerClientError::InputError(ref message) => message,
         }
     }
 
@@ -1,11 +1,14 @@
-use api::client::{TellerClient, ApiServiceResult, Account};
+use api::client::{ApiServiceResult, Account};
+use api::source::Source;
 use api::inform::Money;
 
+/// The balance of an account.
 pub trait GetAccountBalance {
+    /// Gets the current balance of an account in its currency.
     fn get_account_balance(&self, account_id: &str) -> ApiServiceResult<Money>;
 }
 
-impl<'a> GetAccountBalance for TellerClient<'a> {
+impl<S: Source + ?Sized> GetAccountBalance for S {
     fn get_account_balance(&self, account_id: &str) -> ApiServiceResult<Money> {
         let to_money = |a: Account| Money::new(a.balance, a.currency);
         self.get_account(&account_id).map(to_money)
@@ -16,6 +19,7 @@ impl<'a> GetAccountBalance for TellerClient<'a> {
 mod tests {
 
     use api::client::TellerClient;
+    use locale::DisplayFormat;
     use super::GetAccountBalance;
 
     use hyper;
@@ -30,7 +34,7 @@ mod tests {
 
         let money = teller.get_account_balance("123").unwrap();
 
-        assert_eq!("1000.00 GBP", money.get_balance_for_display(&false));
+        assert_eq!("1000.00 GBP", money.get_balance_for_display(&DisplayFormat::new(), &false));
     }
 
 }
@@ -1,28 +1,34 @@
 use cli::arg_types::Interval;
 
-use std::str::FromStr; // Use of #from_str.
-
 use itertools::Itertools;
 
-use api::client::{TellerClient, ApiServiceResult, Transaction};
+use api::cents::{to_cents, from_cents, to_transaction_cents};
+use api::client::{ApiServiceResult, Transaction};
 use api::client::parse_utc_date_from_transaction;
+use api::source::Source;
 use chrono::{Date, Datelike, UTC};
 
+/// The balance of an account at the start of each interval.
 pub type Balances = HistoricalAmountsWithCurrency;
+/// The total outgoing of an account within each interval, as positive amounts.
 pub type Outgoings = HistoricalAmountsWithCurrency;
+/// The total incoming of an account within each interval.
 pub type Incomings = HistoricalAmountsWithCurrency;
 
+/// An interval, such as `01-2016`, and the amount for it.
 pub type IntervalAmount = (String, String);
 
 type DateStringToTransactions = (String, Vec<Transaction>);
 
-#[derive(Debug)]
+/// An amount for each interval, oldest first, alongside the currency of the amounts.
+#[derive(Debug, RustcEncodable)]
 pub struct HistoricalAmountsWithCurrency {
     pub historical_amounts: Vec<IntervalAmount>,
     pub currency: String,
 }
 
 impl HistoricalAmountsWithCurrency {
+    /// Pairs the amounts of each interval with their currency.
     pub fn new<S: Into<String>>(historical_amounts: Vec<IntervalAmount>,
                                 currency: S)
                                 -> HistoricalAmountsWithCurrency {
@@ -33,7 +39,10 @@ impl HistoricalAmountsWithCurrency {
     }
 }
 
+/// The balances of an account over time.
 pub trait GetBalances {
+    /// Gets the balance at the start of each interval between two dates, followed by the
+    /// current balance.
     fn get_balances(&self,
                     account_id: &str,
                     interval: &Interval,
@@ -41,7 +50,9 @@ pub trait GetBalances {
                     to: &Date<UTC>) -> ApiServiceResult<Balances>;
 }
 
+/// The outgoings of an account over time.
 pub trait GetOutgoings {
+    /// Gets the total outgoing within each interval between two dates.
     fn get_outgoings(&self,
                      account_id: &str,
                      interval: &Interval,
@@ -49,7 +60,9 @@ pub trait GetOutgoings {
                      to: &Date<UTC>) -> ApiServiceResult<Outgoings>;
 }
 
+/// The incomings of an account over time.
 pub trait GetIncomings {
+    /// Gets the total incoming within each interval between two dates.
     fn get_incomings(&self,
                      account_id: &str,
                      interval: &Interval,
@@ -57,12 +70,15 @@ pub trait GetIncomings {
                      to: &Date<UTC>) -> ApiServiceResult<Incomings>;
 }
 
-fn to_grouped_transaction_aggregates(transactions: Vec<Transaction>,
-                                     from: &Date<UTC>,
-                                     to: &Date<UTC>,
-                                     interval: &Interval,
-                                     aggregate_txs: &Fn(DateStringToTransactions) -> (String, i64))
-                                     -> Vec<(String, i64)> {
+/// Groups transactions into intervals between two dates, newest first, and totals each
+/// group in cents with `aggregate_txs`.
+pub fn to_grouped_transaction_aggregates(transactions: Vec<Transaction>,
+                                         from: &Date<UTC>,
+                                         to: &Date<UTC>,
+                                         interval: &Interval,
+                                         aggregate_txs: &Fn(DateStringToTransactions)
+                                                            -> ApiServiceResult<(String, i64)>)
+                                         -> ApiServiceResult<Vec<(String, i64)>> {
     let group_format = |date: Date<UTC>| -> String {
         date.format("%m-%Y").to_string()
     };
@@ -101,149 +117,157 @@ fn to_grouped_transaction_aggregates(transactions: Vec<Transaction>,
         current_date = next_date;
     }
 
-    let mut month_year_aggregates = month_year_transactions.into_iter()
-                                                           .map(aggregate_txs)
-                                                           .collect::<Vec<(String, i64)>>();
+    let mut month_year_aggregates: Vec<(String, i64)> =
+        try!(month_year_transactions.into_iter().map(aggregate_txs).collect());
     month_year_aggregates.reverse();
 
-    month_year_aggregates
+    Ok(month_year_aggregates)
+}
+
+fn sum_all(myt: DateStringToTransactions) -> ApiServiceResult<(String, i64)> {
+    let group_name = myt.0;
+    let amount = try!(to_transaction_cents(&myt.1)).into_iter().fold(0i64, |sum, v| sum + v);
+    Ok((group_name, amount))
+}
+
+/// Totals the outgoing transactions of an interval in cents, as a negative amount.
+pub fn sum_outgoings(myt: DateStringToTransactions) -> ApiServiceResult<(String, i64)> {
+    let group_name = myt.0;
+    let amount = try!(to_transaction_cents(&myt.1))
+                     .into_iter()
+                     .filter(|ci| *ci < 0)
+                     .fold(0i64, |sum, v| sum + v);
+    Ok((group_name, amount))
+}
+
+fn sum_incomings(myt: DateStringToTransactions) -> ApiServiceResult<(String, i64)> {
+    let group_name = myt.0;
+    let amount = try!(to_transaction_cents(&myt.1))
+                     .into_iter()
+                     .filter(|ci| *ci > 0)
+                     .fold(0i64, |sum, v| sum + v);
+    Ok((group_name, amount))
+}
+
+/// The amount shown is for the beginning of a month before
+/// any transactions have come in or out.
+///
+/// NOTE: Balances will not work correctly if based off a different month
+///       than the current balance.
+pub fn to_balances(current_balance: &str,
+                   transactions: Vec<Transaction>,
+                   interval: &Interval,
+                   from: &Date<UTC>,
+                   to: &Date<UTC>,
+                   currency: &str)
+                   -> ApiServiceResult<Balances> {
+    let current_balance = try!(to_cents(&current_balance));
+
+    let month_year_total_transactions = try!(to_grouped_transaction_aggregates(transactions,
+                                                                               &from,
+                                                                               &to,
+                                                                               &interval,
+                                                                               &sum_all));
+
+    let mut historical_amounts: Vec<IntervalAmount> = vec![];
+    historical_amounts.push(("current".to_string(),
+                             from_cents(current_balance)));
+
+    let mut last_balance = current_balance;
+    for mytt in month_year_total_transactions {
+        last_balance = last_balance - mytt.1;
+        historical_amounts.push((mytt.0.to_string(),
+                                 from_cents(last_balance)));
+    }
+    historical_amounts.reverse();
+
+    Ok(HistoricalAmountsWithCurrency::new(historical_amounts, currency))
 }
 
-impl<'a> GetBalances for TellerClient<'a> {
+/// The total outgoing of the transactions within each interval.
+pub fn to_outgoings(transactions: Vec<Transaction>,
+                    interval: &Interval,
+                    from: &Date<UTC>,
+                    to: &Date<UTC>,
+                    currency: &str)
+                    -> ApiServiceResult<Outgoings> {
+    let month_year_total_outgoing = try!(to_grouped_transaction_aggregates(transactions,
+                                                                           &from,
+                                                                           &to,
+                                                                           &interval,
+                                                                           &sum_outgoings));
+
+    let mut historical_amounts: Vec<IntervalAmount> = vec![];
+    for mytt in month_year_total_outgoing {
+        historical_amounts.push((mytt.0.to_string(),
+                                 from_cents(mytt.1.abs())));
+    }
+    historical_amounts.reverse();
+
+    Ok(HistoricalAmountsWithCurrency::new(historical_amounts, currency))
+}
+
+/// The total incoming of the transactions within each interval.
+pub fn to_incomings(transactions: Vec<Transaction>,
+                    interval: &Interval,
+                    from: &Date<UTC>,
+                    to: &Date<UTC>,
+                    currency: &str)
+                    -> ApiServiceResult<Incomings> {
+    let month_year_total_incoming = try!(to_grouped_transaction_aggregates(transactions,
+                                                                           &from,
+                                                                           &to,
+                                                                           &interval,
+                                                                           &sum_incomings));
+
+    let mut historical_amounts: Vec<IntervalAmount> = vec![];
+    for mytt in month_year_total_incoming {
+        historical_amounts.push((mytt.0.to_string(), from_cents(mytt.1)));
+    }
+    historical_amounts.reverse();
+
+    Ok(HistoricalAmountsWithCurrency::new(historical_amounts, currency))
+}
 
-    // The amount shown is for the beginning of a month before
-    // any transactions have come in or out.
-    //
-    // NOTE: Balances will not work correctly if based off a different month
-    //       than the current account balance returned by get_accounts.
+impl<S: Source + ?Sized> GetBalances for S {
     fn get_balances(&self,
                     account_id: &str,
                     interval: &Interval,
                     from: &Date<UTC>,
                     to: &Date<UTC>)
                     -> ApiServiceResult<Balances> {
-        let sum_all = |myt: (String, Vec<Transaction>)| {
-            let to_cent_integer = |t: &Transaction| {
-                (f64::from_str(&t.amount).unwrap() * 100f64).round() as i64
-            };
-
-            let group_name = myt.0;
-            let amount = myt.1.iter().map(to_cent_integer).fold(0i64, |sum, v| sum + v);
-            (group_name, amount)
-        };
-
         let account = try!(self.get_account(&account_id));
-        let current_balance = (f64::from_str(&account.balance).unwrap() * 100f64).round() as i64;
-        let currency = account.currency;
-
         let transactions = self.get_transactions(&account_id, &from, &to).unwrap_or(vec![]);
-        let month_year_total_transactions = to_grouped_transaction_aggregates(transactions,
-                                                                              &from,
-                                                                              &to,
-                                                                              &interval,
-                                                                              &sum_all);
-
-        let mut historical_amounts: Vec<IntervalAmount> = vec![];
-        historical_amounts.push(("current".to_string(),
-                                 format!("{:.2}", current_balance as f64 / 100f64)));
-
-        let mut last_balance = current_balance;
-        for mytt in month_year_total_transactions {
-            last_balance = last_balance - mytt.1;
-            historical_amounts.push((mytt.0.to_string(),
-                                     format!("{:.2}", last_balance as f64 / 100f64)));
-        }
-        historical_amounts.reverse();
 
-        Ok(HistoricalAmountsWithCurrency::new(historical_amounts, currency))
+        to_balances(&account.balance, transactions, &interval, &from, &to, &account.currency)
     }
 }
 
-impl<'a> GetOutgoings for TellerClient<'a> {
+impl<S: Source + ?Sized> GetOutgoings for S {
     fn get_outgoings(&self,
                      account_id: &str,
                      interval: &Interval,
                      from: &Date<UTC>,
                      to: &Date<UTC>)
                      -> ApiServiceResult<Outgoings> {
-        let sum_outgoings = |myt: (String, Vec<Transaction>)| {
-            let to_cent_integer = |t: &Transaction| {
-                (f64::from_str(&t.amount).unwrap() * 100f64).round() as i64
-            };
-
-            let group_name = myt.0;
-            let amount = myt.1
-                            .iter()
-                            .map(to_cent_integer)
-                            .filter(|ci| *ci < 0)
-                            .fold(0i64, |sum, v| sum + v);
-            (group_name, amount)
-        };
-
         let account = try!(self.get_account(&account_id));
-        let currency = account.currency;
-
         let transactions = self.get_transactions(&account_id, &from, &to).unwrap_or(vec![]);
-        let month_year_total_outgoing = to_grouped_transaction_aggregates(transactions,
-                                                                          &from,
-                                                                          &to,
-                                                                          &interval,
-                                                                          &sum_outgoings);
-
-        let from_cent_integer_to_float_string = |amount: i64| format!("{:.2}", amount as f64 / 100f64);
-
-        let mut historical_amounts: Vec<IntervalAmount> = vec![];
-        for mytt in month_year_total_outgoing {
-            historical_amounts.push((mytt.0.to_string(),
-                                     from_cent_integer_to_float_string(mytt.1.abs())));
-        }
-        historical_amounts.reverse();
 
-        Ok(HistoricalAmountsWithCurrency::new(historical_amounts, currency))
+        to_outgoings(transactions, &interval, &from, &to, &account.currency)
     }
 }
 
-impl<'a> GetIncomings for TellerClient<'a> {
+impl<S: Source + ?Sized> GetIncomings for S {
     fn get_incomings(&self,
                      account_id: &str,
                      interval: &Interval,
                      from: &Date<UTC>,
                      to: &Date<UTC>)
                      -> ApiServiceResult<Incomings> {
-        let sum_incomings = |myt: (String, Vec<Transaction>)| {
-            let to_cent_integer = |t: &Transaction| {
-                (f64::from_str(&t.amount).unwrap() * 100f64).round() as i64
-            };
-
-            let group_name = myt.0;
-            let amount = myt.1
-                            .iter()
-                            .map(to_cent_integer)
-                            .filter(|ci| *ci > 0)
-                            .fold(0i64, |sum, v| sum + v);
-            (group_name, amount)
-        };
-
         let account = try!(self.get_account(&account_id));
-        let currency = account.currency;
-
         let transactions = self.get_transactions(&account_id, &from, &to).unwrap_or(vec![]);
-        let month_year_total_incoming = to_grouped_transaction_aggregates(transactions,
-                                                                          &from,
-                                                                          &to,
-                                                                          &interval,
-                                                                          &sum_incomings);
-
-        let from_cent_integer_to_float_string = |amount: i64| format!("{:.2}", amount as f64 / 100f64);
-
-        let mut historical_amounts: Vec<IntervalAmount> = vec![];
-        for mytt in month_year_total_incoming {
-            historical_amounts.push((mytt.0.to_string(),
-                                     from_cent_integer_to_float_string(mytt.1)));
-        }
-        historical_amounts.reverse();
 
-        Ok(HistoricalAmountsWithCurrency::new(historical_amounts, currency))
+        to_incomings(transactions, &interval, &from, &to, &account.currency)
     }
 }
 
@@ -251,8 +275,8 @@ impl<'a> GetIncomings for TellerClient<'a> {
 mod tests {
     use cli::arg_types::Interval;
 
-    use api::client::{TellerClient, generate_utc_date_from_date_str};
-    use super::{GetBalances, GetOutgoings, GetIncomings};
+    use api::client::{TellerClient, Transaction, generate_utc_date_from_date_str};
+    use super::{GetBalances, GetOutgoings, GetIncomings, to_outgoings};
 
     use hyper;
     mock_connector_in_order!(GetAccountFollowedByGetTransactions {
@@ -370,4 +394,29 @@ mod tests {
         assert_eq!("50.00", agg.historical_amounts[11].1);
     }
 
+    #[test]
+    fn can_get_outgoings_without_api() {
+        let to_transaction = |date: &str, amount: &str| {
+            Transaction {
+                amount: amount.to_string(),
+                counterparty: "NANNA'S".to_string(),
+                date: date.to_string(),
+                description: "".to_string(),
+                id: "".to_string(),
+            }
+        };
+        let transactions = vec![to_transaction("2015-11-02", "-10.00"),
+                                to_transaction("2015-11-20", "-2.50"),
+                                to_transaction("2015-12-01", "100.00")];
+
+        let from = generate_utc_date_from_date_str("2015-11-01");
+        let to = generate_utc_date_from_date_str("2015-12-31");
+        let agg = to_outgoings(transactions, &Interval::Monthly, &from, &to, "GBP").unwrap();
+
+        assert_eq!("GBP", agg.currency);
+        assert_eq!(vec![("11-2015".to_string(), "12.50".to_string()),
+                        ("12-2015".to_string(), "0.00".to_string())],
+                   agg.historical_amounts);
+    }
+
 }
@@ -0,0 +1,302 @@
+use cli::arg_types::Interval;
+
+use std::collections::HashMap;
+
+use api::cents::{from_cents, to_transaction_cents};
+use api::client::{ApiServiceResult, Transaction};
+use api::client::parse_utc_date_from_transaction;
+use api::inform::{to_grouped_transaction_aggregates, sum_outgoings};
+use api::source::Source;
+use chrono::{Date, UTC};
+
+// Transactions in the first days of the history are never treated as
+// payments to new counterparties, since we have not seen enough to know.
+const LEARNING_PERIOD_IN_DAYS: i64 = 30;
+// A counterparty needs this many earlier transactions before an amount
+// can be said to be outside of its usual distribution.
+const MIN_HISTORY_FOR_DISTRIBUTION: usize = 3;
+const STANDARD_DEVIATIONS: f64 = 3f64;
+const MIN_RELATIVE_DEVIATION: f64 = 0.25f64;
+const TRAILING_MONTHS: usize = 3;
+const MAX_RELATIVE_MONTH_DEVIATION: f64 = 0.5f64;
+
+/// Why a transaction or month stands out.
+#[derive(Debug, PartialEq)]
+pub enum AnomalyKind {
+    UnusualAmount,
+    NewCounterparty,
+    DuplicateCharge,
+    UnusualMonth,
+}
+
+/// A transaction or month which stands out, with the reason it does. A month is
+/// dated `MM-YYYY` and has no `counterparty`.
+#[derive(Debug)]
+pub struct Anomaly {
+    pub kind: AnomalyKind,
+    pub date: String,
+    pub counterparty: String,
+    pub amount: String,
+    pub reason: String,
+}
+
+/// The anomalies of an account, those of its transactions before those of its
+/// months, alongside its currency.
+#[derive(Debug)]
+pub struct AnomaliesWithCurrency {
+    pub anomalies: Vec<Anomaly>,
+    pub currency: String,
+}
+
+/// The transactions and months of an account which stand out.
+pub trait GetAnomalies {
+    /// Gets the unusual amounts, new counterparties above `new_counterparty_threshold`,
+    /// charges repeated within `duplicate_within_days` and unusual months between two dates.
+    fn get_anomalies(&self,
+                     account_id: &str,
+                     new_counterparty_threshold: &f64,
+                     duplicate_within_days: &i64,
+                     from: &Date<UTC>,
+                     to: &Date<UTC>)
+                     -> ApiServiceResult<AnomaliesWithCurrency>;
+}
+
+fn mean_and_standard_deviation(amounts: &Vec<i64>) -> (f64, f64) {
+    let count = amounts.len() as f64;
+    let mean = amounts.iter().fold(0f64, |sum, v| sum + *v as f64) / count;
+    let variance = amounts.iter().fold(0f64, |sum, v| sum + (*v as f64 - mean).powi(2)) / count;
+    (mean, variance.sqrt())
+}
+
+fn to_transaction_anomaly(kind: AnomalyKind, t: &Transaction, reason: String) -> Anomaly {
+    Anomaly {
+        kind: kind,
+        date: t.date.to_owned(),
+        counterparty: t.counterparty.to_owned(),
+        amount: t.amount.to_owned(),
+        reason: reason,
+    }
+}
+
+/// The unusual amounts, new counterparties and duplicate charges among transactions,
+/// which are oldest first.
+pub fn find_transaction_anomalies(transactions: &Vec<Transaction>,
+                                  new_counterparty_threshold: &f64,
+                                  duplicate_within_days: &i64)
+                                  -> ApiServiceResult<Vec<Anomaly>> {
+    let threshold = (new_counterparty_threshold * 100f64).round() as i64;
+
+    let start_date = match transactions.first() {
+        Some(t) => parse_utc_date_from_transaction(&t),
+        None => return Ok(vec![]),
+    };
+
+    let mut anomalies = vec![];
+    let amounts = try!(to_transaction_cents(&transactions));
+    let mut history: HashMap<String, Vec<(&Transaction, i64)>> = HashMap::new();
+    for (t, &amount) in transactions.iter().zip(amounts.iter()) {
+        let date = parse_utc_date_from_transaction(&t);
+
+        match history.get(&t.counterparty) {
+            None => {
+                let is_after_learning_period = (date - start_date).num_days() >= LEARNING_PERIOD_IN_DAYS;
+                if is_after_learning_period && amount < 0 && amount.abs() > threshold {
+                    let reason = format!("First payment to this counterparty is above {}",
+                                         from_cents(threshold));
+                    anomalies.push(to_transaction_anomaly(AnomalyKind::NewCounterparty, &t, reason));
+                }
+            }
+            Some(earlier_transactions) => {
+                let duplicate = earlier_transactions.iter().rev().find(|&&(et, earlier_amount)| {
+                    let days_apart = (date - parse_utc_date_from_transaction(&et)).num_days();
+                    amount < 0 && earlier_amount == amount && days_apart <= *duplicate_within_days
+                });
+                if let Some(&(duplicate, _)) = duplicate {
+                    let reason = format!("The same amount was charged on {}", duplicate.date);
+                    anomalies.push(to_transaction_anomaly(AnomalyKind::DuplicateCharge, &t, reason));
+                }
+
+                // Payments and refunds are compared against their own kind.
+                let earlier_amounts: Vec<i64> = earlier_transactions.iter()
+                                                                    .map(|&(_, ea)| ea)
+                                                                    .filter(|ea| (*ea < 0) == (amount < 0))
+                                                                    .collect();
+                if earlier_amounts.len() >= MIN_HISTORY_FOR_DISTRIBUTION {
+                    let (mean, standard_deviation) = mean_and_standard_deviation(&earlier_amounts);
+                    let deviation = (amount as f64 - mean).abs();
+                    if deviation > STANDARD_DEVIATIONS * standard_deviation &&
+                       deviation > MIN_RELATIVE_DEVIATION * mean.abs() {
+                        let reason = format!("Usually {} (±{}) across {} earlier transactions",
+                                             from_cents(mean.round() as i64),
+                                             from_cents(standard_deviation.round() as i64),
+                                             earlier_amounts.len());
+                        anomalies.push(to_transaction_anomaly(AnomalyKind::UnusualAmount, &t, reason));
+                    }
+                }
+            }
+        }
+
+        history.entry(t.counterparty.to_owned()).or_insert(vec![]).push((t, amount));
+    }
+
+    Ok(anomalies)
+}
+
+/// The months between two dates whose outgoings are far from those of the months
+/// before them.
+pub fn find_month_anomalies(transactions: &Vec<Transaction>,
+                            from: &Date<UTC>,
+                            to: &Date<UTC>)
+                            -> ApiServiceResult<Vec<Anomaly>> {
+    let mut month_year_total_outgoing = try!(to_grouped_transaction_aggregates(transactions.clone(),
+                                                                               &from,
+                                                                               &to,
+                                                                               &Interval::Monthly,
+                                                                               &sum_outgoings))
+                                            .into_iter()
+                                            .map(|(group_name, amount)| (group_name, amount.abs()))
+                                            .collect::<Vec<(String, i64)>>();
+    month_year_total_outgoing.reverse();
+
+    let mut anomalies = vec![];
+    let last_idx = month_year_total_outgoing.len().saturating_sub(1);
+    for (idx, mytt) in month_year_total_outgoing.iter().enumerate().skip(TRAILING_MONTHS) {
+        let trailing_total = month_year_total_outgoing[(idx - TRAILING_MONTHS)..idx]
+                                 .iter()
+                                 .fold(0i64, |sum, v| sum + v.1);
+        let trailing_average = trailing_total as f64 / TRAILING_MONTHS as f64;
+        if trailing_average == 0f64 {
+            continue;
+        }
+
+        // The last month is still in progress, so it can only have spent
+        // more than usual and not less.
+        let relative_deviation = (mytt.1 as f64 - trailing_average) / trailing_average;
+        let is_unusually_high = relative_deviation > MAX_RELATIVE_MONTH_DEVIATION;
+        let is_unusually_low = relative_deviation < -MAX_RELATIVE_MONTH_DEVIATION && idx != last_idx;
+        if is_unusually_high || is_unusually_low {
+            let reason = format!("Outgoings are {:.0}% {} the trailing {}-month average of {}",
+                                 relative_deviation.abs() * 100f64,
+                                 if is_unusually_high { "above" } else { "below" },
+                                 TRAILING_MONTHS,
+                                 from_cents(trailing_average.round() as i64));
+            anomalies.push(Anomaly {
+                kind: AnomalyKind::UnusualMonth,
+                date: mytt.0.to_owned(),
+                counterparty: "".to_string(),
+                amount: from_cents(mytt.1),
+                reason: reason,
+            });
+        }
+    }
+
+    Ok(anomalies)
+}
+
+impl<S: Source + ?Sized> GetAnomalies for S {
+    fn get_anomalies(&self,
+                     account_id: &str,
+                     new_counterparty_threshold: &f64,
+                     duplicate_within_days: &i64,
+                     from: &Date<UTC>,
+                     to: &Date<UTC>)
+                     -> ApiServiceResult<AnomaliesWithCurrency> {
+        let account = try!(self.get_account(&account_id));
+        let transactions = try!(self.get_transactions(&account_id, &from, &to));
+
+        let mut anomalies = try!(find_transaction_anomalies(&transactions,
+                                                            &new_counterparty_threshold,
+                                                            &duplicate_within_days));
+        anomalies.append(&mut try!(find_month_anomalies(&transactions, &from, &to)));
+
+        Ok(AnomaliesWithCurrency {
+            anomalies: anomalies,
+            currency: account.currency,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use api::client::{TellerClient, Transaction, generate_utc_date_from_date_str};
+    use super::{GetAnomalies, AnomalyKind, find_transaction_anomalies};
+
+    use hyper;
+    mock_connector_in_order!(GetAccountFollowedByGetTransactions {
+        include_str!("../mocks/get-account.http")
+        include_str!("../mocks/get-transactions.http")
+    });
+
+    #[test]
+    fn can_get_anomalies() {
+        let c = hyper::client::Client::with_connector(GetAccountFollowedByGetTransactions::default());
+        let teller = TellerClient::new_with_hyper_client("fake-auth-token", c);
+
+        let from = generate_utc_date_from_date_str("2015-01-01");
+        let to = generate_utc_date_from_date_str("2016-01-01");
+        let awc = teller.get_anomalies("123", &50f64, &3i64, &from, &to).unwrap();
+
+        assert_eq!("GBP", awc.currency);
+
+        let new_counterparties = awc.anomalies
+                                    .iter()
+                                    .filter(|a| a.kind == AnomalyKind::NewCounterparty)
+                                    .collect::<Vec<_>>();
+        assert_eq!(2, new_counterparties.len());
+        assert_eq!("COUNTERPARTY-2", new_counterparties[0].counterparty);
+        assert_eq!("-60.00", new_counterparties[0].amount);
+        assert_eq!("COUNTERPARTY-1", new_counterparties[1].counterparty);
+        assert_eq!("-55.00", new_counterparties[1].amount);
+
+        let unusual_months = awc.anomalies
+                                .iter()
+                                .filter(|a| a.kind == AnomalyKind::UnusualMonth)
+                                .collect::<Vec<_>>();
+        assert_eq!(9, unusual_months.len());
+        assert_eq!("04-2015", unusual_months[0].date);
+        assert_eq!("0.00", unusual_months[0].amount);
+        assert_eq!("Outgoings are 100% below the trailing 3-month average of 7.99",
+                   unusual_months[0].reason);
+        assert_eq!("06-2015", unusual_months[1].date);
+        assert_eq!("50.00", unusual_months[1].amount);
+        assert_eq!("Outgoings are 500% above the trailing 3-month average of 8.33",
+                   unusual_months[1].reason);
+    }
+
+    fn to_transaction(date: &str, amount: &str) -> Transaction {
+        Transaction {
+            id: date.to_string(),
+            date: date.to_string(),
+            counterparty: "ACME".to_string(),
+            amount: amount.to_string(),
+            description: "".to_string(),
+        }
+    }
+
+    #[test]
+    fn can_find_duplicate_charges_and_unusual_amounts() {
+        let transactions = vec![to_transaction("2015-03-01", "-9.99"),
+                                to_transaction("2015-03-04", "-9.99"),
+                                to_transaction("2015-03-08", "-9.99"),
+                                to_transaction("2015-03-20", "-10.49"),
+                                to_transaction("2015-04-01", "-50.00")];
+        let anomalies = find_transaction_anomalies(&transactions, &50f64, &3i64).unwrap();
+
+        // The third charge is one day further apart than `--within` allows.
+        assert_eq!(2, anomalies.len());
+        assert_eq!(AnomalyKind::DuplicateCharge, anomalies[0].kind);
+        assert_eq!("2015-03-04", anomalies[0].date);
+        assert_eq!("The same amount was charged on 2015-03-01", anomalies[0].reason);
+        assert_eq!(AnomalyKind::UnusualAmount, anomalies[1].kind);
+        assert_eq!("2015-04-01", anomalies[1].date);
+        assert_eq!("-50.00", anomalies[1].amount);
+        assert_eq!("Usually -10.12 (±0.22) across 4 earlier transactions", anomalies[1].reason);
+
+        let anomalies = find_transaction_anomalies(&transactions, &50f64, &4i64).unwrap();
+        assert_eq!(3, anomalies.len());
+        assert_eq!("2015-03-08", anomalies[1].date);
+        assert_eq!("The same amount was charged on 2015-03-04", anomalies[1].reason);
+    }
+
+}
@@ -1,18 +1,19 @@
-use std::str::FromStr; // Use of #from_str.
-
 use std::collections::HashMap;
-use itertools::Itertools;
 
-use api::client::{TellerClient, ApiServiceResult, Transaction};
+use api::cents::{to_cents, from_cents, to_transaction_cents};
+use api::client::{ApiServiceResult, Transaction};
+use api::source::Source;
 use chrono::{Date, UTC};
 
-#[derive(Debug)]
+/// The total paid to each counterparty of an account, alongside its currency.
+#[derive(Debug, RustcEncodable)]
 pub struct CounterpartiesWithCurrrency {
     pub counterparties: Vec<(String, String)>,
     pub currency: String,
 }
 
 impl CounterpartiesWithCurrrency {
+    /// Pairs `(counterparty, total)`s with the currency of their totals.
     pub fn new<S: Into<String>>(counterparties: Vec<(String, String)>,
                                 currency: S)
                                 -> CounterpartiesWithCurrrency {
@@ -23,7 +24,9 @@ impl CounterpartiesWithCurrrency {
     }
 }
 
+/// The counterparties which an account has paid.
 pub trait GetCounterparties {
+    /// Gets the total paid to each counterparty between two dates.
     fn get_counterparties(&self,
                           account_id: &str,
                           from: &Date<UTC>,
@@ -59,7 +62,41 @@ fn convert_to_counterparty_to_date_amount_list<'a>(transactions: &'a Vec<Transac
     })
 }
 
-impl<'a> GetCounterparties for TellerClient<'a> {
+fn sum_date_amounts(date_amount_tuples: &Vec<(String, String)>) -> ApiServiceResult<i64> {
+    let amounts: Vec<i64> = try!(date_amount_tuples.iter().map(|dat| to_cents(&dat.1)).collect());
+    Ok(amounts.into_iter().fold(0i64, |acc, v| acc + v))
+}
+
+/// The outgoings of the transactions grouped by counterparty, smallest first.
+pub fn to_counterparties(transactions: Vec<Transaction>,
+                         currency: &str)
+                         -> ApiServiceResult<CounterpartiesWithCurrrency> {
+    let amounts = try!(to_transaction_cents(&transactions));
+    let outgoing_transactions: Vec<Transaction> = transactions.into_iter()
+                                                              .zip(amounts.into_iter())
+                                                              .filter(|&(_, amount)| amount < 0)
+                                                              .map(|(tx, _)| tx)
+                                                              .collect();
+
+    let counterparty_to_date_amount_list =
+        convert_to_counterparty_to_date_amount_list(&outgoing_transactions);
+    let mut sorted_counterparties: Vec<(String, i64)> =
+        try!(counterparty_to_date_amount_list.into_iter()
+                                             .map(|(counterparty, date_amount_tuples)| {
+                                                 sum_date_amounts(&date_amount_tuples)
+                                                     .map(|amount| (counterparty, amount.abs()))
+                                             })
+                                             .collect());
+    sorted_counterparties.sort_by(|&(_, amount_a), &(_, amount_b)| amount_a.cmp(&amount_b));
+    let counterparties = sorted_counterparties.into_iter()
+                                              .map(|(counterparty, amount)| {
+                                                  (counterparty, from_cents(amount))
+                                              })
+                                              .collect();
+    Ok(CounterpartiesWithCurrrency::new(counterparties, currency.to_string()))
+}
+
+impl<S: Source + ?Sized> GetCounterparties for S {
     fn get_counterparties(&self,
                           account_id: &str,
                           from: &Date<UTC>,
@@ -68,37 +105,7 @@ impl<'a> GetCounterparties for TellerClient<'a> {
         let account = try!(self.get_account(&account_id));
         let transactions = try!(self.get_transactions(&account_id, &from, &to));
 
-        let to_cent_integer = |amount: &str| (f64::from_str(&amount).unwrap() * 100f64).round() as i64;
-        let from_cent_integer_to_float_string = |amount: &i64| {
-            format!("{:.2}", *amount as f64 / 100f64)
-        };
-
-        let outgoing_transactions: Vec<Transaction> = transactions.into_iter().filter(|tx| {
-            to_cent_integer(&tx.amount) < 0
-        }).collect();
-
-        let counterparty_to_date_amount_list =
-            convert_to_counterparty_to_date_amount_list(&outgoing_transactions);
-        let sorted_counterparties =
-            counterparty_to_date_amount_list.into_iter()
-                                            .map(|(counterparty, date_amount_tuples)| {
-                                                let amount =
-                                                    date_amount_tuples.iter().fold(0i64, |acc, dat| {
-                                                        acc + to_cent_integer(&dat.1)
-                                                    });
-                                                (counterparty, amount.abs())
-                                            })
-                                            .sort_by(|&(_, amount_a), &(_, amount_b)| {
-                                                amount_a.cmp(&amount_b)
-                                            });
-        let counterparties = sorted_counterparties.into_iter()
-                                                  .map(|(counterparty, amount)| {
-                                                      (counterparty,
-                                                       from_cent_integer_to_float_string(&amount))
-                                                  })
-                                                  .collect();
-        let currency = account.currency;
-        Ok(CounterpartiesWithCurrrency::new(counterparties, currency))
+        to_counterparties(transactions, &account.currency)
     }
 }
 
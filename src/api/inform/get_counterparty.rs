@@ -0,0 +1,182 @@
+use cli::arg_types::Interval;
+
+use api::cents::{from_cents, to_transaction_cents};
+use api::client::{ApiServiceResult, Transaction};
+use api::client::parse_utc_date_from_transaction;
+use api::inform::{Outgoings, HistoricalAmountsWithCurrency, IntervalAmount};
+use api::inform::{to_grouped_transaction_aggregates, sum_outgoings};
+use api::source::Source;
+use chrono::{Date, UTC};
+
+/// The payments to a counterparty, alongside the currency of their amounts.
+#[derive(Debug)]
+pub struct CounterpartyWithCurrency {
+    pub counterparty: String,
+    pub transactions: Vec<Transaction>,
+    pub outgoings: Outgoings,
+    /// The number of outgoing transactions, as incoming ones are not payments.
+    pub payment_count: usize,
+    pub total: String,
+    pub average_payment: String,
+    pub cadence_in_days: Option<i64>,
+    pub share_of_outgoings: String,
+    pub currency: String,
+}
+
+/// The history of a single counterparty of an account.
+pub trait GetCounterparty {
+    /// Gets the transactions with a counterparty between two dates, with its outgoings
+    /// within each interval and how often it is paid.
+    fn get_counterparty(&self,
+                        account_id: &str,
+                        counterparty: &str,
+                        interval: &Interval,
+                        from: &Date<UTC>,
+                        to: &Date<UTC>)
+                        -> ApiServiceResult<CounterpartyWithCurrency>;
+}
+
+/// Summarises the transactions with a counterparty, which are oldest first.
+pub fn to_counterparty_with_currency(counterparty: &str,
+                                     transactions: Vec<Transaction>,
+                                     interval: &Interval,
+                                     from: &Date<UTC>,
+                                     to: &Date<UTC>,
+                                     currency: &str)
+                                     -> ApiServiceResult<CounterpartyWithCurrency> {
+    let amounts = try!(to_transaction_cents(&transactions));
+    let all_outgoing = amounts.iter()
+                              .filter(|ci| **ci < 0)
+                              .fold(0i64, |sum, v| sum + v)
+                              .abs();
+
+    // Counterparty names are matched case-insensitively so that `nanna's`
+    // finds the transactions of `NANNA'S`.
+    let (counterparty_transactions, counterparty_amounts): (Vec<Transaction>, Vec<i64>) =
+        transactions.into_iter()
+                    .zip(amounts.into_iter())
+                    .filter(|&(ref t, _)| {
+                        t.counterparty.to_lowercase() == counterparty.to_lowercase()
+                    })
+                    .unzip();
+
+    let payments: Vec<(&Transaction, i64)> = counterparty_transactions.iter()
+                                                                      .zip(counterparty_amounts)
+                                                                      .filter(|&(_, ci)| ci < 0)
+                                                                      .collect();
+    let payment_count = payments.len();
+    let total = payments.iter().fold(0i64, |sum, &(_, v)| sum + v).abs();
+    let average_payment = if payments.is_empty() {
+        0i64
+    } else {
+        total / payment_count as i64
+    };
+
+    // The cadence is the average number of days between payments, and is
+    // only meaningful once at least two payments have been made.
+    let cadence_in_days = match (payments.first(), payments.last()) {
+        (Some(&(first, _)), Some(&(last, _))) if payments.len() > 1 => {
+            let days = parse_utc_date_from_transaction(&last) - parse_utc_date_from_transaction(&first);
+            Some(days.num_days() / (payments.len() - 1) as i64)
+        }
+        _ => None,
+    };
+
+    let share_of_outgoings = if all_outgoing == 0 {
+        0f64
+    } else {
+        total as f64 / all_outgoing as f64 * 100f64
+    };
+
+    let interval_total_outgoing =
+        try!(to_grouped_transaction_aggregates(counterparty_transactions.clone(),
+                                               &from,
+                                               &to,
+                                               &interval,
+                                               &sum_outgoings));
+
+    let mut historical_amounts: Vec<IntervalAmount> = vec![];
+    for itt in interval_total_outgoing {
+        historical_amounts.push((itt.0.to_string(), from_cents(itt.1.abs())));
+    }
+    historical_amounts.reverse();
+
+    // Prefer the name as it appears on the statement over the one typed in.
+    let counterparty_name = counterparty_transactions.first()
+                                                     .map(|t| t.counterparty.to_owned())
+                                                     .unwrap_or(counterparty.to_string());
+
+    Ok(CounterpartyWithCurrency {
+        counterparty: counterparty_name,
+        transactions: counterparty_transactions,
+        outgoings: HistoricalAmountsWithCurrency::new(historical_amounts, currency),
+        payment_count: payment_count,
+        total: from_cents(total),
+        average_payment: from_cents(average_payment),
+        cadence_in_days: cadence_in_days,
+        share_of_outgoings: format!("{:.2}", share_of_outgoings),
+        currency: currency.to_string(),
+    })
+}
+
+impl<S: Source + ?Sized> GetCounterparty for S {
+    fn get_counterparty(&self,
+                        account_id: &str,
+                        counterparty: &str,
+                        interval: &Interval,
+                        from: &Date<UTC>,
+                        to: &Date<UTC>)
+                        -> ApiServiceResult<CounterpartyWithCurrency> {
+        let account = try!(self.get_account(&account_id));
+        let transactions = try!(self.get_transactions(&account_id, &from, &to));
+
+        to_counterparty_with_currency(&counterparty,
+                                      transactions,
+                                      &interval,
+                                      &from,
+                                      &to,
+                                      &account.currency)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cli::arg_types::Interval;
+
+    use api::client::{TellerClient, generate_utc_date_from_date_str};
+    use super::GetCounterparty;
+
+    use hyper;
+    mock_connector_in_order!(GetAccountFollowedByGetTransactions {
+        include_str!("../mocks/get-account.http")
+        include_str!("../mocks/get-transactions.http")
+    });
+
+    #[test]
+    fn can_get_counterparty() {
+        let c = hyper::client::Client::with_connector(GetAccountFollowedByGetTransactions::default());
+        let teller = TellerClient::new_with_hyper_client("fake-auth-token", c);
+
+        let from = generate_utc_date_from_date_str("2015-01-01");
+        let to = generate_utc_date_from_date_str("2016-01-01");
+        let cpty = teller.get_counterparty("123", "counterparty-4", &Interval::Monthly, &from, &to)
+                         .unwrap();
+
+        assert_eq!("GBP", cpty.currency);
+        assert_eq!("COUNTERPARTY-4", cpty.counterparty);
+        assert_eq!(4, cpty.transactions.len());
+        assert_eq!(3, cpty.payment_count);
+        assert_eq!("2015-01-01", cpty.transactions[0].date);
+        assert_eq!("98.97", cpty.total);
+        assert_eq!("32.99", cpty.average_payment);
+        assert_eq!(Some(75), cpty.cadence_in_days);
+        assert_eq!("37.49", cpty.share_of_outgoings);
+        assert_eq!("01-2015", cpty.outgoings.historical_amounts[0].0);
+        assert_eq!("23.97", cpty.outgoings.historical_amounts[0].1);
+        assert_eq!("06-2015", cpty.outgoings.historical_amounts[5].0);
+        assert_eq!("50.00", cpty.outgoings.historical_amounts[5].1);
+        assert_eq!("07-2015", cpty.outgoings.historical_amounts[6].0);
+        assert_eq!("0.00", cpty.outgoings.historical_amounts[6].1);
+    }
+
+}
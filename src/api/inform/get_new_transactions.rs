@@ -0,0 +1,97 @@
+use api::client::{ApiServiceResult, Transaction};
+use api::source::Source;
+use config::watch_cursor::WatchCursor;
+
+const PAGE_SIZE: u32 = 100;
+
+/// The transactions since a `WatchCursor`, oldest first, and where the cursor has moved to.
+#[derive(Debug)]
+pub struct NewTransactions {
+    pub transactions: Vec<Transaction>,
+    pub cursor: Option<WatchCursor>,
+}
+
+/// The transactions of an account which a `WatchCursor` has not seen yet.
+pub trait GetNewTransactions {
+    /// Gets the transactions after `cursor`. Without a cursor nothing is new, and a cursor
+    /// is only placed after the newest transaction.
+    fn get_new_transactions(&self,
+                            account_id: &str,
+                            cursor: &Option<WatchCursor>)
+                            -> ApiServiceResult<NewTransactions>;
+}
+
+/// Without a cursor nothing is new yet, and the cursor is placed after the
+/// newest of the transactions, which are newest first.
+pub fn to_new_transactions(transactions: Vec<Transaction>,
+                           cursor: &Option<WatchCursor>)
+                           -> NewTransactions {
+    match *cursor {
+        None => {
+            NewTransactions {
+                transactions: vec![],
+                cursor: WatchCursor::from_transactions(&transactions),
+            }
+        }
+        Some(ref cursor) => {
+            let mut new_transactions: Vec<Transaction> =
+                transactions.into_iter().filter(|t| !cursor.is_seen(&t)).collect();
+            new_transactions.reverse();
+
+            NewTransactions {
+                cursor: Some(cursor.advance(&new_transactions)),
+                transactions: new_transactions,
+            }
+        }
+    }
+}
+
+impl<S: Source + ?Sized> GetNewTransactions for S {
+    fn get_new_transactions(&self,
+                            account_id: &str,
+                            cursor: &Option<WatchCursor>)
+                            -> ApiServiceResult<NewTransactions> {
+        let transactions = try!(self.get_recent_transactions(&account_id, PAGE_SIZE));
+
+        Ok(to_new_transactions(transactions, &cursor))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use api::client::TellerClient;
+    use config::watch_cursor::WatchCursor;
+    use super::GetNewTransactions;
+
+    use hyper;
+    mock_connector!(GetTransactionsRequest {
+        "https://api.teller.io" => include_str!("../mocks/get-transactions.http")
+    });
+
+    #[test]
+    fn can_get_new_transactions_without_cursor() {
+        let c = hyper::client::Client::with_connector(GetTransactionsRequest::default());
+        let teller = TellerClient::new_with_hyper_client("fake-auth-token", c);
+
+        let new_transactions = teller.get_new_transactions("123", &None).unwrap();
+
+        assert_eq!(0, new_transactions.transactions.len());
+        assert_eq!("2016-01-01", new_transactions.cursor.unwrap().date);
+    }
+
+    #[test]
+    fn can_get_new_transactions_with_cursor() {
+        let c = hyper::client::Client::with_connector(GetTransactionsRequest::default());
+        let teller = TellerClient::new_with_hyper_client("fake-auth-token", c);
+
+        let cursor = Some(WatchCursor::new("2015-10-01", vec![]));
+        let new_transactions = teller.get_new_transactions("123", &cursor).unwrap();
+
+        assert_eq!(4, new_transactions.transactions.len());
+        assert_eq!("COUNTERPARTY-3", new_transactions.transactions[0].counterparty);
+        assert_eq!("COUNTERPARTY-1", new_transactions.transactions[3].counterparty);
+        assert_eq!("2016-01-01", new_transactions.cursor.unwrap().date);
+    }
+
+}
@@ -1,47 +1,48 @@
 use chrono::{UTC, Date, Datelike};
 
-use std::str::FromStr; // Use of #from_str.
-
-use api::client::{TellerClient, ApiServiceResult, Transaction};
+use api::cents::{to_cents, from_cents};
+use api::client::{ApiServiceResult, Transaction};
 use api::client::parse_utc_date_from_transaction;
 use api::inform::Money;
+use api::source::Source;
 
+/// The money which went out of an account within a month.
 pub trait GetOutgoing {
+    /// Gets the total of the outgoing transactions within the month of `for_month`, as a
+    /// positive amount.
     fn get_outgoing(&self, account_id: &str, for_month: &Date<UTC>) -> ApiServiceResult<Money>;
 }
 
-impl<'a> GetOutgoing for TellerClient<'a> {
-    fn get_outgoing(&self, account_id: &str, for_month: &Date<UTC>) -> ApiServiceResult<Money> {
-        let account = try!(self.get_account(&account_id));
-        let currency = account.currency;
+/// The total outgoing of the transactions within the month of `for_month`.
+pub fn to_outgoing(transactions: &Vec<Transaction>,
+                   for_month: &Date<UTC>,
+                   currency: &str)
+                   -> ApiServiceResult<Money> {
+    let from = for_month.with_day(1).unwrap();
+    let to = if from.month() < 12 {
+        from.with_month(from.month() + 1).unwrap()
+    } else {
+        from.with_year(from.year() + 1).unwrap().with_month(1).unwrap()
+    };
 
-        let from = for_month.with_day(1).unwrap();
-        let to = if from.month() < 12 {
-            from.with_month(from.month() + 1).unwrap()
-        } else {
-            from.with_year(from.year() + 1).unwrap().with_month(1).unwrap()
-        };
-        let transactions: Vec<Transaction> = self.raw_transactions(&account_id, 250, 1)
-                                                 .unwrap_or(vec![])
-                                                 .into_iter()
-                                                 .filter(|t| {
-                                                     let transaction_date =
-                                                         parse_utc_date_from_transaction(&t);
-                                                     from <= transaction_date && transaction_date <= to
-                                                 })
-                                                 .collect();
+    let amounts = try!(transactions.iter()
+                                   .filter(|t| {
+                                       let transaction_date = parse_utc_date_from_transaction(&t);
+                                       from <= transaction_date && transaction_date <= to
+                                   })
+                                   .map(|t| to_cents(&t.amount))
+                                   .collect::<ApiServiceResult<Vec<i64>>>());
+    let outgoing = amounts.into_iter().filter(|ci| *ci < 0).fold(0i64, |sum, v| sum + v);
 
-        let from_float_string_to_cent_integer = |t: &Transaction| {
-            (f64::from_str(&t.amount).unwrap() * 100f64).round() as i64
-        };
-        let from_cent_integer_to_float_string = |amount: i64| format!("{:.2}", amount as f64 / 100f64);
+    Ok(Money::new(from_cents(outgoing.abs()), currency.to_string()))
+}
 
-        let outgoing = transactions.iter()
-                                   .map(from_float_string_to_cent_integer)
-                                   .filter(|ci| *ci < 0)
-                                   .fold(0i64, |sum, v| sum + v);
+impl<S: Source + ?Sized> GetOutgoing for S {
+    fn get_outgoing(&self, account_id: &str, for_month: &Date<UTC>) -> ApiServiceResult<Money> {
+        let account = try!(self.get_account(&account_id));
+        let transactions = self.get_recent_transactions(&account_id, 250).unwrap_or(vec![]);
 
-        Ok(Money::new(from_cent_integer_to_float_string(outgoing.abs()), currency))
+        to_outgoing(&transactions, &for_month, &account.currency)
     }
 }
 
@@ -49,6 +50,7 @@ impl<'a> GetOutgoing for TellerClient<'a> {
 mod tests {
 
     use api::client::{TellerClient, generate_utc_date_from_date_str};
+    use locale::DisplayFormat;
     use super::GetOutgoing;
 
     use hyper;
@@ -65,7 +67,7 @@ mod tests {
         let current_month = generate_utc_date_from_date_str("2016-01-01");
         let money = teller.get_outgoing("123", &current_month).unwrap();
 
-        assert_eq!("55.00 GBP", money.get_balance_for_display(&false));
+        assert_eq!("55.00 GBP", money.get_balance_for_display(&DisplayFormat::new(), &false));
     }
 
 }
@@ -0,0 +1,197 @@
+use api::cents::{to_cents, from_cents, to_transaction_cents};
+use api::client::{ApiServiceResult, Transaction};
+use api::client::parse_utc_date_from_transaction;
+use api::inform::{TransactionsWithCurrrency, GetTransactionsWithCurrency};
+use api::source::Source;
+use chrono::{Date, Datelike, UTC};
+
+const WEEKDAYS: [&'static str; 7] = ["Monday",
+                                     "Tuesday",
+                                     "Wednesday",
+                                     "Thursday",
+                                     "Friday",
+                                     "Saturday",
+                                     "Sunday"];
+
+/// The distribution of the amounts of some transactions, as positive amounts.
+#[derive(Debug)]
+pub struct Stats {
+    pub count: usize,
+    pub mean: String,
+    pub median: String,
+    pub p90: String,
+    pub max: String,
+    pub average_daily: String,
+    pub busiest_weekday: Option<String>,
+    pub busiest_day_of_month: Option<u32>,
+}
+
+/// The stats of the outgoing and incoming transactions of an account, alongside its
+/// currency.
+#[derive(Debug)]
+pub struct StatsWithCurrency {
+    pub outgoing: Stats,
+    pub incoming: Stats,
+    pub currency: String,
+}
+
+/// How an account spends and receives money.
+pub trait GetStats {
+    /// Gets the stats of the transactions between two dates.
+    fn get_stats(&self,
+                 account_id: &str,
+                 from: &Date<UTC>,
+                 to: &Date<UTC>)
+                 -> ApiServiceResult<StatsWithCurrency>;
+}
+
+// The index of the most frequent element, preferring the earliest index
+// whenever there is a tie.
+fn busiest(counts: &[usize]) -> Option<usize> {
+    counts.iter()
+          .enumerate()
+          .fold(None, |busiest: Option<(usize, usize)>, (idx, &count)| {
+              match busiest {
+                  Some((_, busiest_count)) if busiest_count >= count => busiest,
+                  _ if count > 0 => Some((idx, count)),
+                  _ => busiest,
+              }
+          })
+          .map(|(idx, _)| idx)
+}
+
+/// The stats of some transactions across a period of `days`.
+pub fn to_stats(transactions: &Vec<&Transaction>, days: i64) -> ApiServiceResult<Stats> {
+    let mut amounts: Vec<i64> = try!(transactions.iter()
+                                                 .map(|t| to_cents(&t.amount).map(i64::abs))
+                                                 .collect());
+    amounts.sort();
+
+    let count = amounts.len();
+    let total = amounts.iter().fold(0i64, |sum, v| sum + v);
+
+    let mean = if count == 0 {
+        0i64
+    } else {
+        (total as f64 / count as f64).round() as i64
+    };
+    let median = match count {
+        0 => 0i64,
+        n if n % 2 == 0 => ((amounts[n / 2 - 1] + amounts[n / 2]) as f64 / 2f64).round() as i64,
+        n => amounts[n / 2],
+    };
+    // Nearest-rank percentile.
+    let p90 = match count {
+        0 => 0i64,
+        n => amounts[((0.9f64 * n as f64).ceil() as usize).saturating_sub(1)],
+    };
+    let max = amounts.last().cloned().unwrap_or(0i64);
+    let average_daily = if days > 0 {
+        (total as f64 / days as f64).round() as i64
+    } else {
+        total
+    };
+
+    let mut weekday_counts = [0usize; 7];
+    let mut day_of_month_counts = [0usize; 31];
+    for t in transactions.iter() {
+        let date = parse_utc_date_from_transaction(&t);
+        weekday_counts[date.weekday().num_days_from_monday() as usize] += 1;
+        day_of_month_counts[date.day0() as usize] += 1;
+    }
+
+    Ok(Stats {
+        count: count,
+        mean: from_cents(mean),
+        median: from_cents(median),
+        p90: from_cents(p90),
+        max: from_cents(max),
+        average_daily: from_cents(average_daily),
+        busiest_weekday: busiest(&weekday_counts).map(|idx| WEEKDAYS[idx].to_string()),
+        busiest_day_of_month: busiest(&day_of_month_counts).map(|idx| (idx + 1) as u32),
+    })
+}
+
+/// The stats of the outgoing and incoming transactions between two dates.
+pub fn to_stats_with_currency(twc: &TransactionsWithCurrrency,
+                              from: &Date<UTC>,
+                              to: &Date<UTC>)
+                              -> ApiServiceResult<StatsWithCurrency> {
+    let days = (*to - *from).num_days();
+
+    let amounts = try!(to_transaction_cents(&twc.transactions));
+    let outgoing_transactions = twc.transactions
+                                   .iter()
+                                   .zip(amounts.iter())
+                                   .filter(|&(_, amount)| *amount < 0)
+                                   .map(|(t, _)| t)
+                                   .collect();
+    let incoming_transactions = twc.transactions
+                                   .iter()
+                                   .zip(amounts.iter())
+                                   .filter(|&(_, amount)| *amount > 0)
+                                   .map(|(t, _)| t)
+                                   .collect();
+
+    Ok(StatsWithCurrency {
+        outgoing: try!(to_stats(&outgoing_transactions, days)),
+        incoming: try!(to_stats(&incoming_transactions, days)),
+        currency: twc.currency.to_owned(),
+    })
+}
+
+impl<S: Source + ?Sized> GetStats for S {
+    fn get_stats(&self,
+                 account_id: &str,
+                 from: &Date<UTC>,
+                 to: &Date<UTC>)
+                 -> ApiServiceResult<StatsWithCurrency> {
+        let transactions_with_currency = try!(self.get_transactions_with_currency(&account_id,
+                                                                                  &from,
+                                                                                  &to));
+
+        to_stats_with_currency(&transactions_with_currency, &from, &to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use api::client::{TellerClient, generate_utc_date_from_date_str};
+    use super::GetStats;
+
+    use hyper;
+    mock_connector_in_order!(GetTransactionsFollowedByGetAccount {
+        include_str!("../mocks/get-transactions.http")
+        include_str!("../mocks/get-account.http")
+    });
+
+    #[test]
+    fn can_get_stats() {
+        let c = hyper::client::Client::with_connector(GetTransactionsFollowedByGetAccount::default());
+        let teller = TellerClient::new_with_hyper_client("fake-auth-token", c);
+
+        let from = generate_utc_date_from_date_str("2015-01-01");
+        let to = generate_utc_date_from_date_str("2016-01-01");
+        let stats = teller.get_stats("123", &from, &to).unwrap();
+
+        assert_eq!("GBP", stats.currency);
+
+        assert_eq!(6, stats.outgoing.count);
+        assert_eq!("44.00", stats.outgoing.mean);
+        assert_eq!("50.00", stats.outgoing.median);
+        assert_eq!("60.00", stats.outgoing.p90);
+        assert_eq!("60.00", stats.outgoing.max);
+        assert_eq!("0.72", stats.outgoing.average_daily);
+        assert_eq!(Some("Friday".to_string()), stats.outgoing.busiest_weekday);
+        assert_eq!(Some(1), stats.outgoing.busiest_day_of_month);
+
+        assert_eq!(4, stats.incoming.count);
+        assert_eq!("87.50", stats.incoming.mean);
+        assert_eq!("75.00", stats.incoming.median);
+        assert_eq!("150.00", stats.incoming.p90);
+        assert_eq!("150.00", stats.incoming.max);
+        assert_eq!(Some("Tuesday".to_string()), stats.incoming.busiest_weekday);
+    }
+
+}
@@ -1,13 +1,16 @@
-use api::client::{TellerClient, ApiServiceResult, Transaction};
+use api::client::{ApiServiceResult, Transaction};
+use api::source::Source;
 use chrono::{Date, UTC};
 
-#[derive(Debug)]
+/// The transactions of an account alongside the currency of their amounts.
+#[derive(Debug, RustcEncodable)]
 pub struct TransactionsWithCurrrency {
     pub transactions: Vec<Transaction>,
     pub currency: String,
 }
 
 impl TransactionsWithCurrrency {
+    /// Pairs transactions with the currency of their amounts.
     pub fn new<S: Into<String>>(transactions: Vec<Transaction>,
                                 currency: S)
                                 -> TransactionsWithCurrrency {
@@ -18,7 +21,9 @@ impl TransactionsWithCurrrency {
     }
 }
 
+/// The transactions of an account and its currency.
 pub trait GetTransactionsWithCurrency {
+    /// Gets the transactions of an account between two dates, oldest first.
     fn get_transactions_with_currency(&self,
                                       account_id: &str,
                                       from: &Date<UTC>,
@@ -26,7 +31,7 @@ pub trait GetTransactionsWithCurrency {
                                       -> ApiServiceResult<TransactionsWithCurrrency>;
 }
 
-impl<'a> GetTransactionsWithCurrency for TellerClient<'a> {
+impl<S: Source + ?Sized> GetTransactionsWithCurrency for S {
     fn get_transactions_with_currency(&self,
                                       account_id: &str,
                                       from: &Date<UTC>,
@@ -3,24 +3,36 @@ pub mod get_incoming;
 pub mod get_outgoing;
 pub mod get_transactions_with_currency;
 pub mod get_counterparties;
+pub mod get_counterparty;
 pub mod get_aggregates;
+pub mod get_stats;
+pub mod get_anomalies;
+pub mod get_new_transactions;
 
 pub use api::client::{TellerClient, ApiServiceResult, Transaction, Account};
 
+use locale::DisplayFormat;
+
 pub use self::get_account_balance::*;
 pub use self::get_incoming::*;
 pub use self::get_outgoing::*;
 pub use self::get_transactions_with_currency::*;
 pub use self::get_counterparties::*;
+pub use self::get_counterparty::*;
 pub use self::get_aggregates::*;
+pub use self::get_stats::*;
+pub use self::get_anomalies::*;
+pub use self::get_new_transactions::*;
 
+/// An amount and its currency, such as a balance.
 #[derive(Debug)]
 pub struct Money {
-    amount: String,
-    currency: String,
+    pub amount: String,
+    pub currency: String,
 }
 
 impl Money {
+    /// Pairs an amount with its currency.
     pub fn new<S: Into<String>>(amount: S, currency: S) -> Money {
         Money {
             amount: amount.into(),
@@ -28,18 +40,18 @@ impl Money {
         }
     }
 
-    pub fn get_balance_for_display(&self, hide_currency: &bool) -> String {
-        if *hide_currency {
-            self.amount.to_owned()
-        } else {
-            let balance_with_currency = format!("{} {}", self.amount, self.currency);
-            balance_with_currency.to_owned()
-        }
+    /// The amount with its currency, as `display_format` displays them.
+    pub fn get_balance_for_display(&self,
+                                   display_format: &DisplayFormat,
+                                   hide_currency: &bool)
+                                   -> String {
+        display_format.format_money(&self.amount, &self.currency, &hide_currency)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use locale::DisplayFormat;
     use super::Money;
 
     #[test]
@@ -60,8 +72,9 @@ mod tests {
 
         let money = Money::new(amount, currency);
 
-        let money_with_currency = money.get_balance_for_display(&false);
-        let money_without_currency = money.get_balance_for_display(&true);
+        let display_format = DisplayFormat::new();
+        let money_with_currency = money.get_balance_for_display(&display_format, &false);
+        let money_without_currency = money.get_balance_for_display(&display_format, &true);
 
         assert_eq!(format!("{} {}", amount, currency), money_with_currency);
         assert_eq!(amount, money_without_currency);
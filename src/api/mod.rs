@@ -1,5 +1,8 @@
+pub mod cents;
 pub mod client;
 pub mod error;
 pub mod inform;
+pub mod source;
 
 pub use self::client::*;
+pub use self::source::{Source, AccountSource, TransactionSource};
@@ -0,0 +1,265 @@
+use std::env;
+use std::fs::{self, File};
+use std::io::prelude::*; // Required for read_to_string use later.
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use rustc_serialize::{json, Decodable, Encodable};
+use chrono::{Date, UTC};
+
+use api::client::{ApiServiceResult, Account, Transaction, add_account_secrets,
+                  add_transaction_secrets};
+use api::source::{Source, AccountSource, TransactionSource};
+use config::atomic_write::create_private_file;
+
+/// Keeps the responses of another source on disk for `max_age`, and falls
+/// back to older responses whenever that source fails (e.g. when offline).
+///
+/// Responses read from the disk never pass through the other source, so their
+/// secrets are masked here instead.
+pub struct CacheSource<S: Source> {
+    source: S,
+    cache_dir: PathBuf,
+    max_age: Duration,
+}
+
+/// `$XDG_CACHE_HOME/teller` (or `~/.cache/teller`).
+pub fn get_cache_dir() -> PathBuf {
+    let mut cache_dir = match env::var("XDG_CACHE_HOME") {
+        Ok(ref xdg_cache_home) if !xdg_cache_home.is_empty() => PathBuf::from(xdg_cache_home),
+        _ => {
+            let mut home_dir = env::home_dir().unwrap_or(PathBuf::from("."));
+            home_dir.push(".cache");
+            home_dir
+        }
+    };
+    cache_dir.push("teller");
+    cache_dir
+}
+
+fn get_age(path: &PathBuf) -> Option<Duration> {
+    fs::metadata(&path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+}
+
+fn read_cached<T: Decodable>(path: &PathBuf) -> Option<T> {
+    let mut body = String::new();
+    File::open(&path)
+        .and_then(|mut file| file.read_to_string(&mut body))
+        .ok()
+        .and_then(|_| json::decode(&body).ok())
+}
+
+fn write_cached<T: Encodable>(path: &PathBuf, value: &T) {
+    let written = json::encode(&value)
+                      .map_err(|e| format!("{}", e))
+                      .and_then(|body| {
+                          create_private_file(&path)
+                              .and_then(|mut file| file.write_all(body.as_bytes()))
+                              .map_err(|e| format!("{}", e))
+                      });
+    if let Err(e) = written {
+        warn!("Unable to write to the cache at {}: {}", path.display(), e);
+    }
+}
+
+impl<S: Source> CacheSource<S> {
+    /// Caches the responses of `source` within `cache_dir` for `max_age`.
+    pub fn new(source: S, cache_dir: PathBuf, max_age: Duration) -> CacheSource<S> {
+        CacheSource {
+            source: source,
+            cache_dir: cache_dir,
+            max_age: max_age,
+        }
+    }
+
+    fn cached<T, F>(&self, key: &str, fetch: F) -> ApiServiceResult<T>
+        where T: Decodable + Encodable,
+              F: Fn(&S) -> ApiServiceResult<T>
+    {
+        let path = self.cache_dir.join(format!("{}.json", key));
+        let is_fresh = get_age(&path).map(|age| age < self.max_age).unwrap_or(false);
+        if is_fresh {
+            if let Some(value) = read_cached(&path) {
+                debug!("Read {} from the cache", key);
+                return Ok(value);
+            }
+        }
+
+        match fetch(&self.source) {
+            Ok(value) => {
+                if fs::create_dir_all(&self.cache_dir).is_ok() {
+                    write_cached(&path, &value);
+                }
+                Ok(value)
+            }
+            Err(e) => {
+                match read_cached(&path) {
+                    Some(value) => {
+                        warn!("Using an old copy of {} from the cache: {}", key, e);
+                        Ok(value)
+                    }
+                    None => Err(e),
+                }
+            }
+        }
+    }
+}
+
+impl<S: Source> AccountSource for CacheSource<S> {
+    fn get_accounts(&self) -> ApiServiceResult<Vec<Account>> {
+        let accounts = try!(self.cached("accounts", |source| source.get_accounts()));
+        for account in accounts.iter() {
+            add_account_secrets(&account);
+        }
+        Ok(accounts)
+    }
+
+    fn get_account(&self, account_id: &str) -> ApiServiceResult<Account> {
+        let account = try!(self.cached(&format!("account-{}", account_id),
+                                       |source| source.get_account(&account_id)));
+        add_account_secrets(&account);
+        Ok(account)
+    }
+}
+
+impl<S: Source> TransactionSource for CacheSource<S> {
+    fn get_transactions(&self,
+                        account_id: &str,
+                        from: &Date<UTC>,
+                        to: &Date<UTC>)
+                        -> ApiServiceResult<Vec<Transaction>> {
+        let key = format!("transactions-{}-{}-{}",
+                          account_id,
+                          from.format("%Y-%m-%d"),
+                          to.format("%Y-%m-%d"));
+        let transactions = try!(self.cached(&key, |source| {
+            source.get_transactions(&account_id, &from, &to)
+        }));
+        add_transaction_secrets(&transactions);
+        Ok(transactions)
+    }
+
+    fn get_recent_transactions(&self,
+                               account_id: &str,
+                               count: u32)
+                               -> ApiServiceResult<Vec<Transaction>> {
+        let transactions = try!(self.cached(&format!("recent-transactions-{}-{}",
+                                                     account_id,
+                                                     count),
+                                            |source| {
+                                                source.get_recent_transactions(&account_id, count)
+                                            }));
+        add_transaction_secrets(&transactions);
+        Ok(transactions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::env;
+    use std::fs;
+    use std::time::Duration;
+
+    use api::client::{ApiServiceResult, Account, Transaction};
+    use api::error::TellerClientError;
+    use api::source::{AccountSource, TransactionSource};
+    use chrono::{Date, UTC};
+    use redact::{enable_redaction, redact_output};
+    use super::CacheSource;
+
+    struct FakeSource {
+        calls: Cell<usize>,
+        offline: Cell<bool>,
+    }
+
+    impl AccountSource for FakeSource {
+        fn get_accounts(&self) -> ApiServiceResult<Vec<Account>> {
+            self.calls.set(self.calls.get() + 1);
+            if self.offline.get() {
+                return Err(TellerClientError::InputError("offline".to_string()));
+            }
+            Ok(vec![Account {
+                        account_number: "00000000".to_string(),
+                        balance: "1000.00".to_string(),
+                        bank_code: "000000".to_string(),
+                        currency: "GBP".to_string(),
+                        id: "123".to_string(),
+                        institution: "natwest".to_string(),
+                        name: "current".to_string(),
+                    }])
+        }
+
+        fn get_account(&self, _: &str) -> ApiServiceResult<Account> {
+            self.get_accounts().map(|mut accounts| accounts.remove(0))
+        }
+    }
+
+    impl TransactionSource for FakeSource {
+        fn get_transactions(&self,
+                            _: &str,
+                            _: &Date<UTC>,
+                            _: &Date<UTC>)
+                            -> ApiServiceResult<Vec<Transaction>> {
+            Ok(vec![])
+        }
+
+        fn get_recent_transactions(&self, _: &str, _: u32) -> ApiServiceResult<Vec<Transaction>> {
+            Ok(vec![])
+        }
+    }
+
+    fn new_fake_source() -> FakeSource {
+        FakeSource {
+            calls: Cell::new(0),
+            offline: Cell::new(false),
+        }
+    }
+
+    #[test]
+    fn can_read_from_cache() {
+        let cache_dir = env::temp_dir().join("teller-test-cache-fresh");
+        let _ = fs::remove_dir_all(&cache_dir);
+
+        let cache = CacheSource::new(new_fake_source(), cache_dir.clone(), Duration::from_secs(60));
+        assert_eq!("123", cache.get_accounts().unwrap()[0].id);
+        assert_eq!("123", cache.get_accounts().unwrap()[0].id);
+        assert_eq!(1, cache.source.calls.get());
+
+        let _ = fs::remove_dir_all(&cache_dir);
+    }
+
+    #[test]
+    fn can_fall_back_to_old_cache() {
+        let cache_dir = env::temp_dir().join("teller-test-cache-old");
+        let _ = fs::remove_dir_all(&cache_dir);
+
+        let cache = CacheSource::new(new_fake_source(), cache_dir.clone(), Duration::from_secs(0));
+        assert_eq!("123", cache.get_account("123").unwrap().id);
+
+        cache.source.offline.set(true);
+        assert_eq!("123", cache.get_account("123").unwrap().id);
+        assert_eq!(2, cache.source.calls.get());
+
+        let _ = fs::remove_dir_all(&cache_dir);
+    }
+
+    #[test]
+    fn can_mask_cached_accounts() {
+        let cache_dir = env::temp_dir().join("teller-test-cache-redact");
+        let _ = fs::remove_dir_all(&cache_dir);
+
+        enable_redaction();
+        let cache = CacheSource::new(new_fake_source(), cache_dir.clone(), Duration::from_secs(60));
+        let account = cache.get_account("123").unwrap();
+        let output = redact_output(&format!("{} {}", account.account_number, account.bank_code));
+
+        assert!(!output.contains("00000000"));
+        assert!(!output.contains("000000"));
+
+        let _ = fs::remove_dir_all(&cache_dir);
+    }
+}
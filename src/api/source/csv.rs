@@ -0,0 +1,244 @@
+use api::cents::{to_cents, from_cents};
+use api::client::{ApiServiceResult, Transaction};
+use api::error::TellerClientError;
+use api::source::statement::{to_amount, to_input_error, to_transaction};
+
+/// The header of each column of a CSV, where `None` looks for the usual names.
+///
+/// Banks which split money paid in and out into separate columns can give
+/// `debit` and `credit` columns instead of an `amount`.
+#[derive(Debug, Default, Clone)]
+pub struct CsvColumns {
+    pub date: Option<String>,
+    pub amount: Option<String>,
+    pub debit: Option<String>,
+    pub credit: Option<String>,
+    pub counterparty: Option<String>,
+    pub description: Option<String>,
+    pub id: Option<String>,
+}
+
+impl CsvColumns {
+    /// Reads a mapping such as `date=Posted Date,counterparty=Payee`.
+    pub fn parse(mapping: &str) -> Result<CsvColumns, String> {
+        let mut columns = CsvColumns::default();
+        for pair in mapping.split(',').filter(|p| !p.trim().is_empty()) {
+            let mut parts = pair.splitn(2, '=');
+            let field = parts.next().unwrap_or("").trim().to_lowercase();
+            let header = match parts.next().map(|h| h.trim()) {
+                Some(header) if !header.is_empty() => Some(header.to_string()),
+                _ => return Err(format!("Expected a column of the form field=header, not `{}`", pair)),
+            };
+            match &field[..] {
+                "date" => columns.date = header,
+                "amount" => columns.amount = header,
+                "debit" => columns.debit = header,
+                "credit" => columns.credit = header,
+                "counterparty" => columns.counterparty = header,
+                "description" => columns.description = header,
+                "id" => columns.id = header,
+                _ => {
+                    return Err(format!("There is no `{}` field (expected date, amount, debit, \
+                                        credit, counterparty, description or id)",
+                                       field))
+                }
+            }
+        }
+        Ok(columns)
+    }
+}
+
+/// Just enough CSV for bank statements: fields may be quoted, a quote within
+/// a quoted field is doubled, and a quoted field may span lines.
+pub fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let mut records = vec![];
+    let mut record = vec![];
+    let mut field = String::new();
+    let mut in_quotes = false;
+
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' => in_quotes = true,
+            ',' => record.push(field.split_off(0)),
+            '\r' => (),
+            '\n' => {
+                record.push(field.split_off(0));
+                records.push(record.split_off(0));
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+
+    records.into_iter()
+           .filter(|r: &Vec<String>| r.iter().any(|f| !f.trim().is_empty()))
+           .collect()
+}
+
+/// The index of the first header matching any of the names, ignoring case.
+pub fn find_column(headers: &Vec<String>, names: &[&str]) -> Option<usize> {
+    names.iter()
+         .filter_map(|name| headers.iter().position(|h| h.trim().to_lowercase() == *name))
+         .next()
+}
+
+fn to_column(headers: &Vec<String>,
+             column: &Option<String>,
+             usual_names: &[&str])
+             -> ApiServiceResult<Option<usize>> {
+    match *column {
+        Some(ref header) => {
+            find_column(&headers, &[&header.trim().to_lowercase()[..]])
+                .map(Some)
+                .ok_or(TellerClientError::InputError(format!("The CSV has no `{}` column", header)))
+        }
+        None => Ok(find_column(&headers, &usual_names)),
+    }
+}
+
+/// Reads the transactions of a CSV whose first row is its header.
+pub fn to_csv_transactions(text: &str,
+                           columns: &CsvColumns,
+                           date_format: &str,
+                           decimal_mark: Option<char>)
+                           -> ApiServiceResult<Vec<Transaction>> {
+    let mut records = parse_csv(&text).into_iter();
+    let headers = try!(records.next().ok_or(TellerClientError::InputError("The CSV is empty"
+                                                                              .to_string())));
+
+    let missing_column = |column: &str| {
+        TellerClientError::InputError(format!("The CSV has no `{}` column", column))
+    };
+    let date_column = try!(try!(to_column(&headers, &columns.date, &["date"]))
+                               .ok_or(missing_column("date")));
+    let amount_column = try!(to_column(&headers, &columns.amount, &["amount"]));
+    let debit_column = try!(to_column(&headers, &columns.debit, &["debit", "paid out", "money out"]));
+    let credit_column = try!(to_column(&headers, &columns.credit, &["credit", "paid in", "money in"]));
+    if amount_column.is_none() && debit_column.is_none() && credit_column.is_none() {
+        return Err(missing_column("amount"));
+    }
+    let counterparty_column = try!(to_column(&headers,
+                                             &columns.counterparty,
+                                             &["counterparty", "payee", "name"]));
+    let description_column = try!(to_column(&headers,
+                                            &columns.description,
+                                            &["description", "memo", "reference"]));
+    let id_column = try!(to_column(&headers, &columns.id, &["id"]));
+
+    let mut transactions = vec![];
+    for (idx, record) in records.enumerate() {
+        // The header is the first row.
+        let position = format!("row {}", idx + 2);
+        let get_field = |column: Option<usize>| {
+            column.and_then(|c| record.get(c)).map(|f| f.trim()).unwrap_or("")
+        };
+
+        let amount = match amount_column {
+            Some(_) => get_field(amount_column).to_string(),
+            None => {
+                // Money paid out is usually written as a positive amount.
+                let to_unsigned_cents = |field: &str| -> ApiServiceResult<i64> {
+                    if field.is_empty() {
+                        return Ok(0);
+                    }
+                    let amount = try!(to_amount(&field, decimal_mark)
+                                          .map_err(|message| to_input_error(&position, &message)));
+                    to_cents(&amount).map(i64::abs)
+                };
+                let cents = try!(to_unsigned_cents(get_field(credit_column))) -
+                            try!(to_unsigned_cents(get_field(debit_column)));
+                from_cents(cents)
+            }
+        };
+
+        transactions.push(try!(to_transaction(&position,
+                                              &amount,
+                                              decimal_mark,
+                                              get_field(Some(date_column)),
+                                              &date_format,
+                                              get_field(counterparty_column),
+                                              get_field(description_column),
+                                              get_field(id_column))));
+    }
+
+    Ok(transactions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CsvColumns, parse_csv, find_column, to_csv_transactions};
+
+    #[test]
+    fn can_parse_csv() {
+        let records = parse_csv("date,amount,description\r\n2016-01-01,-10.00,\"NANNA'S, \
+                                 LONDON\"\n\n2016-01-02,5,\"say \"\"hi\"\"\nthere\"");
+
+        assert_eq!(3, records.len());
+        assert_eq!(vec!["date", "amount", "description"], records[0]);
+        assert_eq!(vec!["2016-01-01", "-10.00", "NANNA'S, LONDON"], records[1]);
+        assert_eq!(vec!["2016-01-02", "5", "say \"hi\"\nthere"], records[2]);
+    }
+
+    #[test]
+    fn can_find_column() {
+        let headers = vec!["Date".to_string(), " Payee ".to_string(), "Amount".to_string()];
+
+        assert_eq!(Some(1), find_column(&headers, &["counterparty", "payee"]));
+        assert_eq!(Some(2), find_column(&headers, &["amount"]));
+        assert_eq!(None, find_column(&headers, &["id"]));
+    }
+
+    #[test]
+    fn can_read_csv_with_column_mapping() {
+        let columns = CsvColumns::parse("date=Posted, counterparty=Who").unwrap();
+        let text = "Posted,Who,Paid out,Paid in\n21/01/2016,NANNA'S,10.00,\n22/01/2016,ACME,,\
+                    \"1,500.00\"\n";
+        let transactions = to_csv_transactions(&text, &columns, "%d/%m/%Y", None).unwrap();
+
+        assert_eq!(2, transactions.len());
+        assert_eq!("2016-01-21", transactions[0].date);
+        assert_eq!("NANNA'S", transactions[0].counterparty);
+        assert_eq!("-10.00", transactions[0].amount);
+        assert_eq!("1500.00", transactions[1].amount);
+
+        assert_eq!(true, CsvColumns::parse("payee=Who").is_err());
+        assert_eq!(true,
+                   to_csv_transactions(&text,
+                                       &CsvColumns::parse("date=When").unwrap(),
+                                       "%d/%m/%Y",
+                                       None)
+                       .is_err());
+    }
+
+    #[test]
+    fn can_read_csv_with_decimal_comma() {
+        let text = "Datum,Betrag\n21.01.2016,\"-2,70\"\n22.01.2016,\"1.500,00\"\n23.01.2016,\
+                    \"1.000\"\n";
+        let columns = CsvColumns::parse("date=Datum,amount=Betrag").unwrap();
+        let transactions = to_csv_transactions(&text, &columns, "%d.%m.%Y", Some(',')).unwrap();
+
+        assert_eq!(3, transactions.len());
+        assert_eq!("-2.70", transactions[0].amount);
+        assert_eq!("1500.00", transactions[1].amount);
+        assert_eq!("1000.00", transactions[2].amount);
+
+        // Without the decimal mark none of these amounts are guessed.
+        assert_eq!(true, to_csv_transactions(&text, &columns, "%d.%m.%Y", None).is_err());
+    }
+}
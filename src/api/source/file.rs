@@ -0,0 +1,199 @@
+use std::fs::File;
+use std::io;
+use std::io::prelude::*; // Required for read_to_string use later.
+use std::path::Path;
+
+use chrono::{Date, UTC};
+
+use api::cents::{from_cents, to_transaction_cents};
+use api::client::{ApiServiceResult, Account, Transaction, parse_utc_date_from_transaction,
+                  add_account_secrets, add_transaction_secrets};
+use api::source::{AccountSource, TransactionSource};
+use api::source::statement::{Statement, ParseOptions, parse_statement};
+
+/// Statements rarely say which currency they are in, and Teller only
+/// supports banks in the UK.
+pub const DEFAULT_CURRENCY: &'static str = "GBP";
+
+/// A statement of transactions, read from a JSON, CSV, OFX or QIF file or from stdin.
+///
+/// A statement belongs to a single account, so the account id which is asked
+/// for is ignored. Without an `account` within the statement its balance is
+/// the sum of its transactions.
+pub struct FileSource {
+    account: Account,
+    transactions: Vec<Transaction>,
+}
+
+/// Reads the whole of a file, or of stdin when the path is `-`.
+pub fn read_input(path: &str) -> ApiServiceResult<String> {
+    let mut text = String::new();
+    if path == "-" {
+        let stdin = io::stdin();
+        try!(stdin.lock().read_to_string(&mut text));
+    } else {
+        let mut file = try!(File::open(&path));
+        try!(file.read_to_string(&mut text));
+    }
+    Ok(text)
+}
+
+impl FileSource {
+    /// A statement of transactions for an account called `name`.
+    pub fn new(name: &str, mut transactions: Vec<Transaction>) -> ApiServiceResult<FileSource> {
+        transactions.sort_by(|a, b| a.date.cmp(&b.date));
+
+        let balance = try!(to_transaction_cents(&transactions))
+                          .into_iter()
+                          .fold(0i64, |sum, v| sum + v);
+
+        Ok(FileSource {
+            account: Account {
+                account_number: "".to_string(),
+                balance: from_cents(balance),
+                bank_code: "".to_string(),
+                currency: DEFAULT_CURRENCY.to_string(),
+                id: "".to_string(),
+                institution: "".to_string(),
+                name: name.to_string(),
+            },
+            transactions: transactions,
+        })
+    }
+
+    /// An account called `name` with whatever else the statement says about it.
+    pub fn from_statement(name: &str, statement: Statement) -> ApiServiceResult<FileSource> {
+        let mut file_source = try!(FileSource::new(name, statement.transactions));
+        {
+            let account = &mut file_source.account;
+            let fields = vec![(&mut account.id, statement.id),
+                              (&mut account.name, statement.name),
+                              (&mut account.institution, statement.institution),
+                              (&mut account.account_number, statement.account_number),
+                              (&mut account.bank_code, statement.bank_code),
+                              (&mut account.currency, statement.currency),
+                              (&mut account.balance, statement.balance)];
+            for (field, value) in fields {
+                if let Some(value) = value {
+                    *field = value;
+                }
+            }
+        }
+        add_account_secrets(&file_source.account);
+        add_transaction_secrets(&file_source.transactions);
+        Ok(file_source)
+    }
+
+    /// Reads a JSON, CSV, OFX or QIF statement, guessing which it is.
+    pub fn parse(name: &str, text: &str) -> ApiServiceResult<FileSource> {
+        let statement = try!(parse_statement(&text, &ParseOptions::default()));
+        FileSource::from_statement(&name, statement)
+    }
+
+    /// Reads a statement from a path, or from stdin when the path is `-`.
+    pub fn open(path: &str) -> ApiServiceResult<FileSource> {
+        let text = try!(read_input(&path));
+        let name = match path {
+            "-" => "stdin",
+            _ => Path::new(&path).file_stem().and_then(|s| s.to_str()).unwrap_or(&path),
+        };
+        FileSource::parse(&name, &text)
+    }
+
+    /// Every transaction within the statement, whatever its date.
+    pub fn get_all_transactions(&self) -> &Vec<Transaction> {
+        &self.transactions
+    }
+}
+
+impl AccountSource for FileSource {
+    fn get_accounts(&self) -> ApiServiceResult<Vec<Account>> {
+        Ok(vec![self.account.clone()])
+    }
+
+    fn get_account(&self, _: &str) -> ApiServiceResult<Account> {
+        Ok(self.account.clone())
+    }
+}
+
+impl TransactionSource for FileSource {
+    fn get_transactions(&self,
+                        _: &str,
+                        from: &Date<UTC>,
+                        to: &Date<UTC>)
+                        -> ApiServiceResult<Vec<Transaction>> {
+        Ok(self.transactions
+               .iter()
+               .filter(|t| {
+                   let transaction_date = parse_utc_date_from_transaction(&t);
+                   *from <= transaction_date && transaction_date <= *to
+               })
+               .cloned()
+               .collect())
+    }
+
+    fn get_recent_transactions(&self, _: &str, count: u32) -> ApiServiceResult<Vec<Transaction>> {
+        Ok(self.transactions.iter().rev().take(count as usize).cloned().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use api::client::generate_utc_date_from_date_str;
+    use api::source::{AccountSource, TransactionSource};
+    use super::FileSource;
+
+    #[test]
+    fn can_read_json_statement_with_account() {
+        let statement = r#"{
+            "account": {"name": "Joint", "currency": "EUR", "balance": "100"},
+            "transactions": [
+                {"date": "2016-01-21", "amount": -10, "counterparty": "NANNA'S"},
+                {"date": "2015-12-01", "amount": "1000", "description": "SALARY"}
+            ]
+        }"#;
+        let source = FileSource::parse("statement", &statement).unwrap();
+
+        let account = source.get_account("ignored").unwrap();
+        assert_eq!("Joint", account.name);
+        assert_eq!("EUR", account.currency);
+        assert_eq!("100.00", account.balance);
+
+        let from = generate_utc_date_from_date_str("2015-01-01");
+        let to = generate_utc_date_from_date_str("2016-01-01");
+        let transactions = source.get_transactions("", &from, &to).unwrap();
+        assert_eq!(1, transactions.len());
+        assert_eq!("SALARY", transactions[0].description);
+
+        let recent = source.get_recent_transactions("", 1).unwrap();
+        assert_eq!("NANNA'S", recent[0].counterparty);
+    }
+
+    #[test]
+    fn can_read_csv_statement() {
+        let statement = "Date,Payee,Amount\n2016-01-21,\"NANNA'S, LONDON\",-10.00\n\
+                         2016-01-01,ACME,1500\n";
+        let source = FileSource::parse("statement", &statement).unwrap();
+
+        let account = source.get_account("").unwrap();
+        assert_eq!("statement", account.name);
+        assert_eq!("GBP", account.currency);
+        assert_eq!("1490.00", account.balance);
+
+        let recent = source.get_recent_transactions("", 10).unwrap();
+        assert_eq!(2, recent.len());
+        assert_eq!("NANNA'S, LONDON", recent[0].counterparty);
+        assert_eq!("1500.00", recent[1].amount);
+    }
+
+    #[test]
+    fn can_fail_to_read_invalid_statement() {
+        let bad_date = FileSource::parse("statement", "date,amount\n21/01/2016,-10.00\n");
+        assert_eq!("Invalid date `21/01/2016` (expected %Y-%m-%d) at row 2",
+                   format!("{}", bad_date.err().unwrap()));
+
+        let no_amount = FileSource::parse("statement", "date,payee\n2016-01-21,ACME\n");
+        assert_eq!("The CSV has no `amount` column",
+                   format!("{}", no_amount.err().unwrap()));
+    }
+}
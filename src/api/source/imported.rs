@@ -0,0 +1,385 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use crypto::digest::Digest;
+use crypto::sha2::Sha256;
+use rand::{thread_rng, Rng};
+use rustc_serialize::json;
+use chrono::{Date, UTC};
+
+use api::cents::{to_cents, from_cents, to_transaction_cents};
+use api::client::{ApiServiceResult, Account, Transaction};
+use api::error::TellerClientError;
+use api::source::{Source, AccountSource, TransactionSource, FileSource};
+use api::source::file::DEFAULT_CURRENCY;
+use api::source::statement::Statement;
+use config::atomic_write::replace_atomically;
+
+const IMPORTED_ACCOUNT_ID_PREFIX: &'static str = "imported-";
+
+/// The accounts and transactions of banks which Teller does not cover, as
+/// imported from their statements.
+#[derive(Debug, RustcEncodable)]
+pub struct ImportedAccount {
+    pub account: Account,
+    pub transactions: Vec<Transaction>,
+}
+
+/// How many transactions of a statement were new when it was imported.
+#[derive(Debug)]
+pub struct ImportSummary {
+    pub added: usize,
+    pub skipped: usize,
+}
+
+/// Whether an account ID was made up for an imported statement rather than given by
+/// the Teller API.
+pub fn is_imported_account_id(account_id: &str) -> bool {
+    account_id.starts_with(IMPORTED_ACCOUNT_ID_PREFIX)
+}
+
+/// Makes up an ID for a newly imported account.
+pub fn new_imported_account_id() -> String {
+    format!("{}{:016x}", IMPORTED_ACCOUNT_ID_PREFIX, thread_rng().gen::<u64>())
+}
+
+/// `$XDG_DATA_HOME/teller/imports` (or `~/.local/share/teller/imports`).
+pub fn get_imports_dir() -> PathBuf {
+    let mut imports_dir = match env::var("XDG_DATA_HOME") {
+        Ok(ref xdg_data_home) if !xdg_data_home.is_empty() => PathBuf::from(xdg_data_home),
+        _ => {
+            let mut home_dir = env::home_dir().unwrap_or(PathBuf::from("."));
+            home_dir.push(".local");
+            home_dir.push("share");
+            home_dir
+        }
+    };
+    imports_dir.push("teller");
+    imports_dir.push("imports");
+    imports_dir
+}
+
+fn get_import_path(imports_dir: &PathBuf, account_id: &str) -> PathBuf {
+    imports_dir.join(format!("{}.json", account_id))
+}
+
+fn read_imported_account(imports_dir: &PathBuf, account_id: &str) -> ApiServiceResult<FileSource> {
+    let import_path = get_import_path(&imports_dir, &account_id);
+    FileSource::open(&import_path.to_string_lossy())
+}
+
+// Two transactions are the same if they share a date, amount and description.
+// Not every statement has descriptions, so the counterparty stands in for one.
+fn to_dedupe_key(t: &Transaction) -> (String, String, String) {
+    let description = if t.description.is_empty() {
+        &t.counterparty
+    } else {
+        &t.description
+    };
+    (t.date.to_owned(), t.amount.to_owned(), description.to_owned())
+}
+
+fn to_transaction_id(key: &(String, String, String), occurrence: usize) -> String {
+    let mut hasher = Sha256::new();
+    hasher.input_str(&format!("{}|{}|{}|{}", key.0, key.1, key.2, occurrence));
+    hasher.result_str()[..16].to_string()
+}
+
+/// Gives transactions without an id one which is the same whenever their
+/// statement is read again, and so can be used to recognise them later.
+pub fn with_stable_ids(transactions: Vec<Transaction>) -> Vec<Transaction> {
+    let mut occurrences: HashMap<(String, String, String), usize> = HashMap::new();
+    transactions.into_iter()
+                .map(|mut t| {
+                    let key = to_dedupe_key(&t);
+                    let occurrence = {
+                        let occurrence = occurrences.entry(key.clone()).or_insert(0);
+                        *occurrence += 1;
+                        *occurrence
+                    };
+                    if t.id.is_empty() {
+                        t.id = to_transaction_id(&key, occurrence);
+                    }
+                    t
+                })
+                .collect()
+}
+
+/// Adds the transactions of a statement to an imported account.
+///
+/// A statement may repeat transactions which were imported before, so only
+/// those beyond the number already seen with the same date, amount and
+/// description are added. Two identical coffees on one day are kept, but
+/// importing the same statement twice adds nothing.
+pub fn merge_statement(existing: Option<ImportedAccount>,
+                       account_id: &str,
+                       name: &str,
+                       statement: Statement)
+                       -> ApiServiceResult<(ImportedAccount, ImportSummary)> {
+    let (mut account, mut transactions) = match existing {
+        Some(ImportedAccount { account, transactions }) => (account, transactions),
+        None => {
+            let account = Account {
+                account_number: "".to_string(),
+                balance: "0.00".to_string(),
+                bank_code: "".to_string(),
+                currency: DEFAULT_CURRENCY.to_string(),
+                id: "".to_string(),
+                institution: "".to_string(),
+                name: name.to_string(),
+            };
+            (account, vec![])
+        }
+    };
+    account.id = account_id.to_string();
+
+    let mut seen: HashMap<(String, String, String), usize> = HashMap::new();
+    for t in transactions.iter() {
+        *seen.entry(to_dedupe_key(&t)).or_insert(0) += 1;
+    }
+
+    let mut occurrences: HashMap<(String, String, String), usize> = HashMap::new();
+    let mut added = vec![];
+    let mut skipped = 0;
+    for t in with_stable_ids(statement.transactions) {
+        let key = to_dedupe_key(&t);
+        let occurrence = {
+            let occurrence = occurrences.entry(key.clone()).or_insert(0);
+            *occurrence += 1;
+            *occurrence
+        };
+        if occurrence <= *seen.get(&key).unwrap_or(&0) {
+            skipped += 1;
+            continue;
+        }
+        added.push(t);
+    }
+
+    // A statement which states its balance is trusted over our arithmetic.
+    account.balance = match statement.balance {
+        Some(balance) => balance,
+        None => {
+            let added_amount = try!(to_transaction_cents(&added))
+                                   .into_iter()
+                                   .fold(0i64, |sum, v| sum + v);
+            from_cents(try!(to_cents(&account.balance)) + added_amount)
+        }
+    };
+    if let Some(currency) = statement.currency {
+        account.currency = currency;
+    }
+    if let Some(account_number) = statement.account_number {
+        account.account_number = account_number;
+    }
+    if let Some(bank_code) = statement.bank_code {
+        account.bank_code = bank_code;
+    }
+
+    let summary = ImportSummary {
+        added: added.len(),
+        skipped: skipped,
+    };
+    transactions.append(&mut added);
+    transactions.sort_by(|a, b| a.date.cmp(&b.date));
+
+    Ok((ImportedAccount {
+        account: account,
+        transactions: transactions,
+    },
+        summary))
+}
+
+/// Merges a statement into an imported account and writes it back.
+pub fn import_statement(imports_dir: &PathBuf,
+                        account_id: &str,
+                        name: &str,
+                        statement: Statement)
+                        -> ApiServiceResult<ImportSummary> {
+    let import_path = get_import_path(&imports_dir, &account_id);
+    let existing = if import_path.exists() {
+        let file_source = try!(read_imported_account(&imports_dir, &account_id));
+        Some(ImportedAccount {
+            account: try!(file_source.get_account(&account_id)),
+            transactions: file_source.get_all_transactions().clone(),
+        })
+    } else {
+        None
+    };
+
+    let (imported_account, summary) = try!(merge_statement(existing,
+                                                           &account_id,
+                                                           &name,
+                                                           statement));
+
+    try!(fs::create_dir_all(&imports_dir));
+    let body = try!(json::encode(&imported_account)
+                        .map_err(|e| TellerClientError::InputError(format!("{}", e))));
+    // An import which is interrupted must not lose the transactions imported before.
+    try!(replace_atomically(&import_path, body.as_bytes()));
+
+    Ok(summary)
+}
+
+/// Removes an imported account, e.g. one which could not be given an alias.
+pub fn remove_imported_account(imports_dir: &PathBuf, account_id: &str) -> ApiServiceResult<()> {
+    try!(fs::remove_file(&get_import_path(&imports_dir, &account_id)));
+    Ok(())
+}
+
+/// Reads imported accounts from disk and every other account from `source`.
+pub struct ImportedSource<S: Source> {
+    source: S,
+    imports_dir: PathBuf,
+    imported_account_ids: Vec<String>,
+}
+
+impl<S: Source> ImportedSource<S> {
+    /// Reads the accounts within `imported_account_ids` from `imports_dir`.
+    pub fn new(source: S,
+               imports_dir: PathBuf,
+               imported_account_ids: Vec<String>)
+               -> ImportedSource<S> {
+        ImportedSource {
+            source: source,
+            imports_dir: imports_dir,
+            imported_account_ids: imported_account_ids,
+        }
+    }
+}
+
+impl<S: Source> AccountSource for ImportedSource<S> {
+    fn get_accounts(&self) -> ApiServiceResult<Vec<Account>> {
+        let mut accounts = try!(self.source.get_accounts());
+        for account_id in self.imported_account_ids.iter() {
+            accounts.push(try!(self.get_account(&account_id)));
+        }
+        Ok(accounts)
+    }
+
+    fn get_account(&self, account_id: &str) -> ApiServiceResult<Account> {
+        if is_imported_account_id(&account_id) {
+            read_imported_account(&self.imports_dir, &account_id)
+                .and_then(|file_source| file_source.get_account(&account_id))
+        } else {
+            self.source.get_account(&account_id)
+        }
+    }
+}
+
+impl<S: Source> TransactionSource for ImportedSource<S> {
+    fn get_transactions(&self,
+                        account_id: &str,
+                        from: &Date<UTC>,
+                        to: &Date<UTC>)
+                        -> ApiServiceResult<Vec<Transaction>> {
+        if is_imported_account_id(&account_id) {
+            read_imported_account(&self.imports_dir, &account_id)
+                .and_then(|file_source| file_source.get_transactions(&account_id, &from, &to))
+        } else {
+            self.source.get_transactions(&account_id, &from, &to)
+        }
+    }
+
+    fn get_recent_transactions(&self,
+                               account_id: &str,
+                               count: u32)
+                               -> ApiServiceResult<Vec<Transaction>> {
+        if is_imported_account_id(&account_id) {
+            read_imported_account(&self.imports_dir, &account_id)
+                .and_then(|file_source| file_source.get_recent_transactions(&account_id, count))
+        } else {
+            self.source.get_recent_transactions(&account_id, count)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+
+    use api::client::Transaction;
+    use api::source::{AccountSource, TransactionSource, FileSource};
+    use api::source::statement::Statement;
+    use redact::{enable_redaction, redact_output};
+    use super::{ImportedSource, merge_statement, import_statement};
+
+    fn to_transaction(date: &str, amount: &str, description: &str) -> Transaction {
+        Transaction {
+            amount: amount.to_string(),
+            counterparty: "".to_string(),
+            date: date.to_string(),
+            description: description.to_string(),
+            id: "".to_string(),
+        }
+    }
+
+    fn to_statement(transactions: Vec<Transaction>) -> Statement {
+        Statement { transactions: transactions, ..Statement::default() }
+    }
+
+    #[test]
+    fn can_deduplicate_imported_transactions() {
+        let first = to_statement(vec![to_transaction("2016-01-21", "-2.70", "NANNA'S"),
+                                      to_transaction("2016-01-21", "-2.70", "NANNA'S"),
+                                      to_transaction("2016-01-01", "1500.00", "SALARY")]);
+        let (imported, summary) = merge_statement(None, "imported-1", "joint", first).unwrap();
+
+        assert_eq!(3, summary.added);
+        assert_eq!("1494.60", imported.account.balance);
+        assert_eq!("joint", imported.account.name);
+        assert_eq!("2016-01-01", imported.transactions[0].date);
+        assert!(imported.transactions[1].id != imported.transactions[2].id);
+
+        let second = to_statement(vec![to_transaction("2016-01-21", "-2.70", "NANNA'S"),
+                                       to_transaction("2016-01-21", "-2.70", "NANNA'S"),
+                                       to_transaction("2016-01-22", "-10.00", "TESCO")]);
+        let to_ids = |transactions: &Vec<Transaction>| -> Vec<String> {
+            transactions.iter().map(|t| t.id.to_owned()).collect()
+        };
+        let first_ids = to_ids(&imported.transactions);
+        let (imported, summary) = merge_statement(Some(imported), "imported-1", "joint", second)
+                                      .unwrap();
+
+        assert_eq!(1, summary.added);
+        assert_eq!(2, summary.skipped);
+        assert_eq!(4, imported.transactions.len());
+        assert_eq!("1484.60", imported.account.balance);
+        assert_eq!(first_ids[..], to_ids(&imported.transactions)[..3]);
+    }
+
+    #[test]
+    fn can_redact_imported_account() {
+        let imports_dir = env::temp_dir().join("teller-test-imports-redact");
+        let _ = fs::remove_dir_all(&imports_dir);
+        let statement = Statement {
+            account_number: Some("87654321".to_string()),
+            bank_code: Some("402030".to_string()),
+            transactions: vec![Transaction {
+                                   counterparty: "CORNER BAKERY".to_string(),
+                                   ..to_transaction("2016-01-21", "-2.70", "")
+                               }],
+            ..Statement::default()
+        };
+        import_statement(&imports_dir, "imported-2", "joint", statement).unwrap();
+
+        enable_redaction();
+        let source = ImportedSource::new(FileSource::new("stdin", vec![]).unwrap(),
+                                         imports_dir.clone(),
+                                         vec!["imported-2".to_string()]);
+        let account = source.get_account("imported-2").unwrap();
+        let transactions = source.get_recent_transactions("imported-2", 1).unwrap();
+        let output = redact_output(&format!("{} {} {}",
+                                            account.account_number,
+                                            account.bank_code,
+                                            transactions[0].counterparty));
+
+        assert!(!output.contains("87654321"));
+        assert!(!output.contains("402030"));
+        assert!(!output.contains("CORNER BAKERY"));
+
+        let _ = fs::remove_dir_all(&imports_dir);
+    }
+}
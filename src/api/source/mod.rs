@@ -0,0 +1,71 @@
+pub mod statement;
+pub mod csv;
+pub mod ofx;
+pub mod qif;
+pub mod file;
+pub mod cache;
+pub mod imported;
+
+pub use self::file::FileSource;
+pub use self::cache::{CacheSource, get_cache_dir};
+pub use self::imported::{ImportedSource, get_imports_dir};
+
+use api::client::{TellerClient, ApiServiceResult, Account, Transaction};
+use chrono::{Date, UTC};
+
+/// Anything which can list accounts, such as the Teller API or a statement.
+pub trait AccountSource {
+    /// Gets every account.
+    fn get_accounts(&self) -> ApiServiceResult<Vec<Account>>;
+
+    /// Gets a single account by its ID.
+    fn get_account(&self, account_id: &str) -> ApiServiceResult<Account>;
+}
+
+/// Anything which can list the transactions of an account.
+pub trait TransactionSource {
+    /// Gets an account's transactions between two dates, oldest first.
+    fn get_transactions(&self,
+                        account_id: &str,
+                        from: &Date<UTC>,
+                        to: &Date<UTC>)
+                        -> ApiServiceResult<Vec<Transaction>>;
+
+    /// Gets up to `count` of an account's most recent transactions, newest first.
+    fn get_recent_transactions(&self,
+                               account_id: &str,
+                               count: u32)
+                               -> ApiServiceResult<Vec<Transaction>>;
+}
+
+/// Both accounts and transactions, which is all that the `inform` traits need.
+pub trait Source: AccountSource + TransactionSource {}
+
+impl<S: AccountSource + TransactionSource + ?Sized> Source for S {}
+
+impl AccountSource for TellerClient {
+    fn get_accounts(&self) -> ApiServiceResult<Vec<Account>> {
+        TellerClient::get_accounts(self)
+    }
+
+    fn get_account(&self, account_id: &str) -> ApiServiceResult<Account> {
+        TellerClient::get_account(self, &account_id)
+    }
+}
+
+impl TransactionSource for TellerClient {
+    fn get_transactions(&self,
+                        account_id: &str,
+                        from: &Date<UTC>,
+                        to: &Date<UTC>)
+                        -> ApiServiceResult<Vec<Transaction>> {
+        TellerClient::get_transactions(self, &account_id, &from, &to)
+    }
+
+    fn get_recent_transactions(&self,
+                               account_id: &str,
+                               count: u32)
+                               -> ApiServiceResult<Vec<Transaction>> {
+        self.raw_transactions(&account_id, count, 1)
+    }
+}
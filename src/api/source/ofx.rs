@@ -0,0 +1,102 @@
+use api::client::ApiServiceResult;
+use api::source::statement::{Statement, to_amount, to_transaction};
+
+const OFX_DATE_FORMAT: &'static str = "%Y%m%d";
+
+// OFX 1.x is SGML, which leaves elements unclosed, while OFX 2.x is XML. In
+// both the value of an element runs from its tag until the next tag.
+fn get_element(text: &str, tag: &str) -> Option<String> {
+    let open_tag = format!("<{}>", tag);
+    text.find(&open_tag).map(|start| {
+        let value = &text[start + open_tag.len()..];
+        let end = value.find('<').unwrap_or(value.len());
+        value[..end]
+            .trim()
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&amp;", "&")
+    })
+}
+
+fn get_blocks<'a>(text: &'a str, tag: &str) -> Vec<&'a str> {
+    let open_tag = format!("<{}>", tag);
+    let close_tag = format!("</{}>", tag);
+
+    let mut blocks = vec![];
+    let mut rest = text;
+    while let Some(start) = rest.find(&open_tag) {
+        let block = &rest[start + open_tag.len()..];
+        let end = block.find(&close_tag).or(block.find(&open_tag)).unwrap_or(block.len());
+        blocks.push(&block[..end]);
+        rest = &block[end..];
+    }
+    blocks
+}
+
+/// Reads the transactions, currency and ledger balance of an OFX (or QFX) statement.
+pub fn parse_ofx(text: &str, decimal_mark: Option<char>) -> ApiServiceResult<Statement> {
+    let mut transactions = vec![];
+    for (idx, block) in get_blocks(&text, "STMTTRN").into_iter().enumerate() {
+        let position = format!("transaction {}", idx + 1);
+        let get_field = |tag: &str| get_element(&block, tag).unwrap_or("".to_string());
+
+        // Dates may be followed by a time and a timezone, e.g. 20160121120000[0:GMT].
+        let date: String = get_field("DTPOSTED").chars().take(8).collect();
+        let name = get_field("NAME");
+        let counterparty = if name.is_empty() {
+            get_field("PAYEE")
+        } else {
+            name
+        };
+        transactions.push(try!(to_transaction(&position,
+                                              &get_field("TRNAMT"),
+                                              decimal_mark,
+                                              &date,
+                                              OFX_DATE_FORMAT,
+                                              &counterparty,
+                                              &get_field("MEMO"),
+                                              &get_field("FITID"))));
+    }
+
+    let balance = get_blocks(&text, "LEDGERBAL")
+                      .first()
+                      .and_then(|block| get_element(&block, "BALAMT"))
+                      .and_then(|balance| to_amount(&balance, decimal_mark).ok());
+
+    Ok(Statement {
+        account_number: get_element(&text, "ACCTID"),
+        bank_code: get_element(&text, "BANKID"),
+        currency: get_element(&text, "CURDEF"),
+        balance: balance,
+        transactions: transactions,
+        ..Statement::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_ofx;
+
+    #[test]
+    fn can_parse_ofx() {
+        let ofx = "OFXHEADER:100\nDATA:OFXSGML\n\n<OFX><BANKMSGSRSV1><STMTTRNRS><STMTRS>\n\
+                   <CURDEF>GBP\n<BANKACCTFROM><BANKID>000000<ACCTID>00000000</BANKACCTFROM>\n\
+                   <BANKTRANLIST>\n<STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20160121120000[0:GMT]\
+                   <TRNAMT>-10.00<FITID>abc1<NAME>NANNA'S<MEMO>COFFEE &amp; CAKE</STMTTRN>\n\
+                   <STMTTRN><TRNTYPE>CREDIT<DTPOSTED>20160101<TRNAMT>1500.00<FITID>abc2\
+                   <NAME>ACME</STMTTRN>\n</BANKTRANLIST>\n\
+                   <LEDGERBAL><BALAMT>1490.00<DTASOF>20160121</LEDGERBAL>\n\
+                   </STMTRS></STMTTRNRS></BANKMSGSRSV1></OFX>";
+        let statement = parse_ofx(&ofx, None).unwrap();
+
+        assert_eq!(Some("GBP".to_string()), statement.currency);
+        assert_eq!(Some("00000000".to_string()), statement.account_number);
+        assert_eq!(Some("1490.00".to_string()), statement.balance);
+        assert_eq!(2, statement.transactions.len());
+        assert_eq!("2016-01-21", statement.transactions[0].date);
+        assert_eq!("-10.00", statement.transactions[0].amount);
+        assert_eq!("NANNA'S", statement.transactions[0].counterparty);
+        assert_eq!("COFFEE & CAKE", statement.transactions[0].description);
+        assert_eq!("abc2", statement.transactions[1].id);
+    }
+}
@@ -0,0 +1,91 @@
+use api::client::{ApiServiceResult, Transaction};
+use api::source::statement::to_transaction;
+
+fn to_qif_date(date: &str) -> String {
+    date.replace("'", "/").replace(" ", "")
+}
+
+// QIF comes from Quicken in the US, so its dates are month first unless
+// a date format says otherwise. Years are sometimes written as '16.
+fn to_qif_date_format(date: &str) -> &'static str {
+    if date.contains('-') {
+        "%Y-%m-%d"
+    } else if date.rsplit('/').next().map(|year| year.len() <= 2).unwrap_or(false) {
+        "%m/%d/%y"
+    } else {
+        "%m/%d/%Y"
+    }
+}
+
+/// Reads the transactions of a QIF statement, whose records end with `^`.
+pub fn to_qif_transactions(text: &str,
+                           date_format: Option<&str>,
+                           decimal_mark: Option<char>)
+                           -> ApiServiceResult<Vec<Transaction>> {
+    let mut transactions = vec![];
+
+    let mut record: Vec<(char, String)> = vec![];
+    for (idx, line) in text.lines().enumerate() {
+        let line = line.trim();
+        let mut chars = line.chars();
+        match chars.next() {
+            // Headers such as !Type:Bank.
+            None | Some('!') => (),
+            Some('^') => {
+                let position = format!("line {}", idx + 1);
+                let get_field = |code: char| {
+                    record.iter()
+                          .find(|&&(c, _)| c == code)
+                          .map(|&(_, ref value)| &value[..])
+                          .unwrap_or("")
+                };
+
+                let date = to_qif_date(get_field('D'));
+                let date_format = date_format.unwrap_or_else(|| to_qif_date_format(&date));
+                // Some exports only give the amount within `U`.
+                let amount = match get_field('T') {
+                    "" => get_field('U'),
+                    amount => amount,
+                };
+                transactions.push(try!(to_transaction(&position,
+                                                      &amount,
+                                                      decimal_mark,
+                                                      &date,
+                                                      &date_format,
+                                                      get_field('P'),
+                                                      get_field('M'),
+                                                      get_field('N'))));
+                record.clear();
+            }
+            Some(code) => record.push((code, chars.as_str().to_string())),
+        }
+    }
+
+    Ok(transactions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::to_qif_transactions;
+
+    #[test]
+    fn can_read_qif() {
+        let qif = "!Type:Bank\nD01/21/2016\nT-10.00\nPNANNA'S\nMCOFFEE\n^\nD1/ 1'16\nT1,500.00\n\
+                   PACME\n^\n";
+        let transactions = to_qif_transactions(&qif, None, None).unwrap();
+
+        assert_eq!(2, transactions.len());
+        assert_eq!("2016-01-21", transactions[0].date);
+        assert_eq!("-10.00", transactions[0].amount);
+        assert_eq!("NANNA'S", transactions[0].counterparty);
+        assert_eq!("COFFEE", transactions[0].description);
+        assert_eq!("2016-01-01", transactions[1].date);
+        assert_eq!("1500.00", transactions[1].amount);
+
+        let uk_transactions = to_qif_transactions("!Type:Bank\nD21/01/2016\nT-10.00\n^\n",
+                                                  Some("%d/%m/%Y"),
+                                                  None)
+                                  .unwrap();
+        assert_eq!("2016-01-21", uk_transactions[0].date);
+    }
+}
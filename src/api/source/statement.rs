@@ -0,0 +1,322 @@
+use rustc_serialize::json::{Json, Object};
+use chrono::NaiveDate;
+
+use api::client::{ApiServiceResult, Transaction};
+use api::error::TellerClientError;
+use api::source::csv::{CsvColumns, to_csv_transactions};
+use api::source::ofx::parse_ofx;
+use api::source::qif::to_qif_transactions;
+
+/// The format of transaction dates, which statements are converted to.
+pub const DEFAULT_DATE_FORMAT: &'static str = "%Y-%m-%d";
+
+/// The formats of statement which can be read.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StatementFormat {
+    Json,
+    Csv,
+    Ofx,
+    Qif,
+}
+
+/// Reads a format name such as `csv` or `qfx`.
+pub fn to_statement_format(format: &str) -> Option<StatementFormat> {
+    match &format.to_lowercase()[..] {
+        "json" => Some(StatementFormat::Json),
+        "csv" => Some(StatementFormat::Csv),
+        "ofx" | "qfx" => Some(StatementFormat::Ofx),
+        "qif" => Some(StatementFormat::Qif),
+        _ => None,
+    }
+}
+
+/// Guesses the format of a statement from its text, falling back to CSV.
+pub fn detect_statement_format(text: &str) -> StatementFormat {
+    let text = text.trim_left();
+    if text.starts_with('[') || text.starts_with('{') {
+        StatementFormat::Json
+    } else if text.starts_with("OFXHEADER") || text.contains("<OFX>") {
+        StatementFormat::Ofx
+    } else if text.starts_with("!Type") || text.starts_with("!Account") {
+        StatementFormat::Qif
+    } else {
+        StatementFormat::Csv
+    }
+}
+
+/// How a statement should be read. Without a format it is guessed from the
+/// text, and without a date format each format uses its usual one. See
+/// `to_amount` for amounts read without a decimal mark.
+#[derive(Debug, Default)]
+pub struct ParseOptions {
+    pub format: Option<StatementFormat>,
+    pub columns: CsvColumns,
+    pub date_format: Option<String>,
+    pub decimal_mark: Option<char>,
+}
+
+/// The transactions of a statement alongside whatever it says about its account.
+#[derive(Debug, Default)]
+pub struct Statement {
+    pub id: Option<String>,
+    pub name: Option<String>,
+    pub institution: Option<String>,
+    pub account_number: Option<String>,
+    pub bank_code: Option<String>,
+    pub currency: Option<String>,
+    pub balance: Option<String>,
+    pub transactions: Vec<Transaction>,
+}
+
+/// An error about the statement at `position`, e.g. a line number.
+pub fn to_input_error(position: &str, message: &str) -> TellerClientError {
+    TellerClientError::InputError(format!("{} at {}", message, position))
+}
+
+// An unsigned amount with `decimal_mark`, whose whole part may be grouped in
+// threes by `thousands_separator`, e.g. `1,234.56` or `1.234,56`.
+fn parse_unsigned_amount(amount: &str,
+                         decimal_mark: char,
+                         thousands_separator: char)
+                         -> Option<f64> {
+    let mut parts = amount.splitn(2, decimal_mark);
+    let whole = parts.next().unwrap_or("");
+    let fraction = parts.next().unwrap_or("");
+    let groups: Vec<&str> = whole.split(thousands_separator).collect();
+    let is_grouped = groups.iter().enumerate().all(|(idx, group)| {
+        let is_sized = match idx {
+            0 if groups.len() == 1 => true,
+            0 => !group.is_empty() && group.len() <= 3 && !group.starts_with('0'),
+            _ => group.len() == 3,
+        };
+        is_sized && group.chars().all(|c| c.is_digit(10))
+    });
+    if !is_grouped || (whole.is_empty() && fraction.is_empty()) ||
+       !fraction.chars().all(|c| c.is_digit(10)) {
+        return None;
+    }
+    format!("{}.{}", groups.concat(), fraction).parse::<f64>().ok()
+}
+
+/// Reads an amount, ignoring currency symbols and thousands separators.
+///
+/// Without a decimal mark it is `.`, but an amount such as `1,000` (or
+/// `1.000`) which could be read either way is an error rather than a guess.
+pub fn to_amount(amount: &str, decimal_mark: Option<char>) -> Result<String, String> {
+    let text: String = amount.chars()
+                             .filter(|c| !c.is_whitespace())
+                             .filter(|c| !(*c == '£' || *c == '$' || *c == '€'))
+                             .collect();
+    let (sign, unsigned) = match text.chars().next() {
+        Some('-') => (-1f64, &text[1..]),
+        Some('+') => (1f64, &text[1..]),
+        _ => (1f64, &text[..]),
+    };
+    let to_amount_with = |decimal_mark: char| {
+        let thousands_separator = if decimal_mark == ',' { '.' } else { ',' };
+        parse_unsigned_amount(unsigned, decimal_mark, thousands_separator)
+    };
+
+    let parsed = match decimal_mark {
+        Some(decimal_mark) => to_amount_with(decimal_mark),
+        None => {
+            match (to_amount_with('.'), to_amount_with(',')) {
+                (Some(point), Some(comma)) if point != comma => {
+                    return Err(format!("Ambiguous amount `{}`, as its decimal mark could be `.` \
+                                        or `,`",
+                                       amount.trim()))
+                }
+                (None, Some(_)) => {
+                    return Err(format!("Invalid amount `{}` (is its decimal mark `,`?)",
+                                       amount.trim()))
+                }
+                (point, _) => point,
+            }
+        }
+    };
+    parsed.map(|unsigned| format!("{:.2}", sign * unsigned))
+          .ok_or(format!("Invalid amount `{}`", amount.trim()))
+}
+
+/// Reads a date in `date_format` as `YYYY-MM-DD`.
+pub fn to_date(date: &str, date_format: &str) -> Option<String> {
+    NaiveDate::parse_from_str(date.trim(), &date_format)
+        .ok()
+        .map(|d| d.format(DEFAULT_DATE_FORMAT).to_string())
+}
+
+/// Reads the fields of a transaction, erroring with its `position` if the amount or
+/// date is invalid.
+pub fn to_transaction(position: &str,
+                      amount: &str,
+                      decimal_mark: Option<char>,
+                      date: &str,
+                      date_format: &str,
+                      counterparty: &str,
+                      description: &str,
+                      id: &str)
+                      -> ApiServiceResult<Transaction> {
+    let amount = try!(to_amount(&amount, decimal_mark)
+                          .map_err(|message| to_input_error(position, &message)));
+    let date = try!(to_date(&date, &date_format).ok_or_else(|| {
+        to_input_error(position,
+                       &format!("Invalid date `{}` (expected {})", date, date_format))
+    }));
+
+    Ok(Transaction {
+        amount: amount,
+        counterparty: counterparty.trim().to_string(),
+        date: date,
+        description: description.trim().to_string(),
+        id: id.trim().to_string(),
+    })
+}
+
+fn get_json_string(object: &Object, key: &str) -> Option<String> {
+    match object.get(key) {
+        Some(&Json::String(ref s)) if !s.is_empty() => Some(s.to_owned()),
+        Some(&Json::String(_)) | Some(&Json::Null) | None => None,
+        Some(other) => Some(other.to_string()),
+    }
+}
+
+fn from_json(json: Json,
+             date_format: &str,
+             decimal_mark: Option<char>)
+             -> ApiServiceResult<Statement> {
+    // Either a list of transactions or an object which contains them.
+    let (transactions, object) = match json {
+        Json::Array(transactions) => (transactions, Object::new()),
+        Json::Object(mut object) => {
+            match object.remove("transactions") {
+                Some(Json::Array(transactions)) => (transactions, object),
+                _ => {
+                    return Err(TellerClientError::InputError("Expected a list of `transactions` \
+                                                              within the JSON"
+                                                                 .to_string()))
+                }
+            }
+        }
+        _ => {
+            return Err(TellerClientError::InputError("Expected a list of transactions within \
+                                                      the JSON"
+                                                         .to_string()))
+        }
+    };
+
+    let mut statement = Statement::default();
+    for (idx, transaction) in transactions.into_iter().enumerate() {
+        let position = format!("transaction {}", idx + 1);
+        let t = match transaction {
+            Json::Object(t) => t,
+            _ => return Err(to_input_error(&position, "Expected an object")),
+        };
+        let get_field = |key: &str| get_json_string(&t, key).unwrap_or("".to_string());
+        statement.transactions.push(try!(to_transaction(&position,
+                                                        &get_field("amount"),
+                                                        decimal_mark,
+                                                        &get_field("date"),
+                                                        &date_format,
+                                                        &get_field("counterparty"),
+                                                        &get_field("description"),
+                                                        &get_field("id"))));
+    }
+
+    if let Some(&Json::Object(ref account)) = object.get("account") {
+        statement.id = get_json_string(&account, "id");
+        statement.name = get_json_string(&account, "name");
+        statement.institution = get_json_string(&account, "institution");
+        statement.account_number = get_json_string(&account, "account_number");
+        statement.bank_code = get_json_string(&account, "bank_code");
+        statement.currency = get_json_string(&account, "currency");
+        statement.balance = get_json_string(&account, "balance")
+                                .and_then(|b| to_amount(&b, decimal_mark).ok());
+    }
+    // The transactions of `teller serve` come with their currency.
+    if let Some(currency) = get_json_string(&object, "currency") {
+        statement.currency = Some(currency);
+    }
+
+    Ok(statement)
+}
+
+/// Reads the transactions of a JSON, CSV, OFX or QIF statement.
+pub fn parse_statement(text: &str, options: &ParseOptions) -> ApiServiceResult<Statement> {
+    let format = options.format.clone().unwrap_or_else(|| detect_statement_format(&text));
+    let date_format = options.date_format.as_ref().map(|f| &f[..]);
+
+    match format {
+        StatementFormat::Json => {
+            let json = try!(Json::from_str(&text).map_err(|e| {
+                TellerClientError::InputError(format!("Invalid JSON: {}", e))
+            }));
+            from_json(json, date_format.unwrap_or(DEFAULT_DATE_FORMAT), options.decimal_mark)
+        }
+        StatementFormat::Csv => {
+            let transactions = try!(to_csv_transactions(&text,
+                                                        &options.columns,
+                                                        date_format.unwrap_or(DEFAULT_DATE_FORMAT),
+                                                        options.decimal_mark));
+            Ok(Statement { transactions: transactions, ..Statement::default() })
+        }
+        StatementFormat::Ofx => parse_ofx(&text, options.decimal_mark),
+        StatementFormat::Qif => {
+            let transactions = try!(to_qif_transactions(&text, date_format, options.decimal_mark));
+            Ok(Statement { transactions: transactions, ..Statement::default() })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{StatementFormat, ParseOptions, detect_statement_format, parse_statement, to_amount};
+
+    #[test]
+    fn can_detect_statement_format() {
+        assert_eq!(StatementFormat::Json, detect_statement_format("  [{\"date\": \"2016-01-01\"}]"));
+        assert_eq!(StatementFormat::Ofx, detect_statement_format("OFXHEADER:100\nDATA:OFXSGML"));
+        assert_eq!(StatementFormat::Qif, detect_statement_format("!Type:Bank\nD01/21/2016"));
+        assert_eq!(StatementFormat::Csv, detect_statement_format("date,amount\n"));
+    }
+
+    #[test]
+    fn can_read_amounts() {
+        assert_eq!(Ok("-1000.50".to_string()), to_amount(" -£1,000.5 ", None));
+        assert_eq!(Ok("1500.00".to_string()), to_amount("1500", None));
+        assert_eq!(Ok("-1234.56".to_string()), to_amount("-1.234,56 €", Some(',')));
+        assert_eq!(Ok("1000.00".to_string()), to_amount("1.000", Some(',')));
+        assert_eq!(true, to_amount("ten", None).is_err());
+        assert_eq!(true, to_amount("1,2,3", Some('.')).is_err());
+    }
+
+    #[test]
+    fn can_reject_ambiguous_amounts() {
+        assert_eq!(Err("Ambiguous amount `1,000`, as its decimal mark could be `.` or `,`"
+                           .to_string()),
+                   to_amount("1,000", None));
+        assert_eq!(true, to_amount("1.000", None).is_err());
+        assert_eq!(Err("Invalid amount `-2,70` (is its decimal mark `,`?)".to_string()),
+                   to_amount("-2,70", None));
+        assert_eq!(Ok("1.00".to_string()), to_amount("1,000", Some(',')));
+    }
+
+    #[test]
+    fn can_read_json_statement() {
+        let statement = r#"{
+            "account": {"name": "Joint", "currency": "EUR", "balance": "100"},
+            "transactions": [
+                {"date": "2016-01-21", "amount": -10, "counterparty": "NANNA'S"},
+                {"date": "2015-12-01", "amount": "1,000.00", "description": "SALARY", "id": "1"}
+            ]
+        }"#;
+        let statement = parse_statement(&statement, &ParseOptions::default()).unwrap();
+
+        assert_eq!(Some("Joint".to_string()), statement.name);
+        assert_eq!(Some("EUR".to_string()), statement.currency);
+        assert_eq!(Some("100.00".to_string()), statement.balance);
+        assert_eq!(2, statement.transactions.len());
+        assert_eq!("-10.00", statement.transactions[0].amount);
+        assert_eq!("1000.00", statement.transactions[1].amount);
+        assert_eq!("SALARY", statement.transactions[1].description);
+    }
+}
@@ -0,0 +1,3 @@
+pub mod rule;
+
+pub use self::rule::{Rule, Severity, Measure, Comparison, parse_rule};
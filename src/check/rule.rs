@@ -0,0 +1,246 @@
+use std::str::FromStr; // Use of #from_str.
+
+use cli::arg_types::AccountType;
+use cli::parse::to_account_type;
+
+/// How urgent an alert is, which decides the exit status of `teller check`.
+#[derive(Debug, PartialEq, PartialOrd, Clone, Copy)]
+pub enum Severity {
+    Warning,
+    Critical,
+}
+
+/// What a rule compares against its threshold.
+#[derive(Debug, PartialEq)]
+pub enum Measure {
+    Balance,
+    Outgoing,
+    Incoming,
+}
+
+/// How a measure is compared against a threshold.
+#[derive(Debug, PartialEq)]
+pub enum Comparison {
+    LessThan,
+    LessThanOrEqual,
+    GreaterThan,
+    GreaterThanOrEqual,
+}
+
+/// A rule describes the condition that should raise an alert, e.g.
+/// `balance of current < 500` or `warning: outgoings this month > 2000`.
+#[derive(Debug)]
+pub struct Rule {
+    pub text: String,
+    pub severity: Severity,
+    pub measure: Measure,
+    pub account: AccountType,
+    pub comparison: Comparison,
+    pub threshold: f64,
+    pub after_day: Option<u32>,
+}
+
+impl Rule {
+    /// Whether `amount` should raise an alert on `day_of_month`. A rule with `after_day`
+    /// only raises one after that day of the month.
+    pub fn is_violated(&self, amount: f64, day_of_month: u32) -> bool {
+        let is_due = self.after_day.map_or(true, |after_day| day_of_month > after_day);
+        is_due &&
+        match self.comparison {
+            Comparison::LessThan => amount < self.threshold,
+            Comparison::LessThanOrEqual => amount <= self.threshold,
+            Comparison::GreaterThan => amount > self.threshold,
+            Comparison::GreaterThanOrEqual => amount >= self.threshold,
+        }
+    }
+
+    /// A name for the measure of a rule, such as `balance_current`.
+    pub fn get_label(&self) -> String {
+        let measure = match self.measure {
+            Measure::Balance => "balance",
+            Measure::Outgoing => "outgoing",
+            Measure::Incoming => "incoming",
+        };
+        let account = match self.account {
+            AccountType::Current | AccountType::None => "current".to_string(),
+            AccountType::Savings => "savings".to_string(),
+            AccountType::Business => "business".to_string(),
+            AccountType::Unknown(ref alias) => alias.to_owned(),
+        };
+        format!("{}_{}", measure, account)
+    }
+
+    /// The threshold as a Nagios range which alerts exactly when the rule is violated,
+    /// e.g. `500:` for `< 500` or `@~:0` for `<= 0`.
+    pub fn get_perfdata_range(&self) -> String {
+        match self.comparison {
+            Comparison::LessThan => format!("{}:", self.threshold),
+            Comparison::LessThanOrEqual => format!("@~:{}", self.threshold),
+            Comparison::GreaterThan => format!("~:{}", self.threshold),
+            Comparison::GreaterThanOrEqual => format!("@{}:", self.threshold),
+        }
+    }
+}
+
+fn to_day_of_month(nth: &str) -> Result<u32, String> {
+    let day = nth.trim_right_matches(|c: char| c.is_alphabetic());
+    match u32::from_str(day) {
+        Ok(day) if day >= 1 && day <= 31 => Ok(day),
+        _ => Err(format!("{} is not a day of the month", nth)),
+    }
+}
+
+/// Parses a rule such as `warning: outgoings this month of current > 2000 by the 20th`.
+pub fn parse_rule(text: &str) -> Result<Rule, String> {
+    let lowercase_text = text.trim().to_lowercase();
+
+    let (severity, rule_text) = if lowercase_text.starts_with("warning:") {
+        (Severity::Warning, &lowercase_text["warning:".len()..])
+    } else if lowercase_text.starts_with("critical:") {
+        (Severity::Critical, &lowercase_text["critical:".len()..])
+    } else {
+        (Severity::Critical, &lowercase_text[..])
+    };
+    let words: Vec<&str> = rule_text.split_whitespace().collect();
+    let word_at = |idx: usize| words.get(idx).map(|w| *w);
+
+    let to_account_and_next_idx = |idx: usize| {
+        match (word_at(idx), word_at(idx + 1)) {
+            (Some("of"), Some(alias)) => (to_account_type(alias), idx + 2),
+            _ => (AccountType::None, idx),
+        }
+    };
+
+    // `no salary received by the 3rd [of <account>]` is a shorthand for
+    // there having been no incomings this month once the day has passed.
+    if word_at(0) == Some("no") && word_at(2) == Some("received") && word_at(3) == Some("by") &&
+       word_at(4) == Some("the") {
+        let after_day = try!(to_day_of_month(word_at(5).unwrap_or("")));
+        let (account, next_idx) = to_account_and_next_idx(6);
+        if next_idx != words.len() {
+            return Err(format!("Unable to understand the end of rule: {}", text));
+        }
+        return Ok(Rule {
+            text: text.trim().to_string(),
+            severity: severity,
+            measure: Measure::Incoming,
+            account: account,
+            comparison: Comparison::LessThanOrEqual,
+            threshold: 0f64,
+            after_day: Some(after_day),
+        });
+    }
+
+    let (measure, next_idx) = match (word_at(0), word_at(1), word_at(2)) {
+        (Some("balance"), _, _) => (Measure::Balance, 1),
+        (Some("outgoings"), Some("this"), Some("month")) => (Measure::Outgoing, 3),
+        (Some("incomings"), Some("this"), Some("month")) => (Measure::Incoming, 3),
+        _ => {
+            return Err(format!("A rule must start with `balance`, `outgoings this month` or \
+                                `incomings this month`: {}",
+                               text))
+        }
+    };
+    let (account, next_idx) = to_account_and_next_idx(next_idx);
+    let comparison = match word_at(next_idx) {
+        Some("<") => Comparison::LessThan,
+        Some("<=") => Comparison::LessThanOrEqual,
+        Some(">") => Comparison::GreaterThan,
+        Some(">=") => Comparison::GreaterThanOrEqual,
+        _ => return Err(format!("A rule must compare with one of <, <=, > or >=: {}", text)),
+    };
+    let threshold = match word_at(next_idx + 1).map(f64::from_str) {
+        Some(Ok(threshold)) => threshold,
+        _ => return Err(format!("A rule must compare against an amount: {}", text)),
+    };
+    let after_day = match (word_at(next_idx + 2), word_at(next_idx + 3), word_at(next_idx + 4)) {
+        (None, _, _) => None,
+        (Some("by"), Some("the"), Some(nth)) if next_idx + 5 == words.len() => {
+            Some(try!(to_day_of_month(nth)))
+        }
+        _ => return Err(format!("Unable to understand the end of rule: {}", text)),
+    };
+
+    Ok(Rule {
+        text: text.trim().to_string(),
+        severity: severity,
+        measure: measure,
+        account: account,
+        comparison: comparison,
+        threshold: threshold,
+        after_day: after_day,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Severity, Measure, Comparison, parse_rule};
+
+    #[test]
+    fn can_parse_balance_rule() {
+        let rule = parse_rule("balance of savings < 500").unwrap();
+
+        assert_eq!(Severity::Critical, rule.severity);
+        assert_eq!(Measure::Balance, rule.measure);
+        assert_eq!(Comparison::LessThan, rule.comparison);
+        assert_eq!(500f64, rule.threshold);
+        assert_eq!("balance_savings", rule.get_label());
+        assert_eq!(true, rule.is_violated(499.99f64, 1));
+        assert_eq!(false, rule.is_violated(500f64, 1));
+    }
+
+    #[test]
+    fn can_parse_outgoings_rule_with_severity() {
+        let rule = parse_rule("warning: outgoings this month > 2000").unwrap();
+
+        assert_eq!(Severity::Warning, rule.severity);
+        assert_eq!(Measure::Outgoing, rule.measure);
+        assert_eq!("outgoing_current", rule.get_label());
+        assert_eq!(true, rule.is_violated(2000.01f64, 1));
+    }
+
+    #[test]
+    fn can_parse_no_incomings_rule() {
+        let rule = parse_rule("no salary received by the 3rd").unwrap();
+
+        assert_eq!(Measure::Incoming, rule.measure);
+        assert_eq!(Some(3), rule.after_day);
+        assert_eq!(false, rule.is_violated(0f64, 3));
+        assert_eq!(true, rule.is_violated(0f64, 4));
+        assert_eq!(false, rule.is_violated(2500f64, 4));
+    }
+
+    #[test]
+    fn can_represent_less_than_as_perfdata_range() {
+        let rule = parse_rule("balance < 500").unwrap();
+
+        assert_eq!("500:", rule.get_perfdata_range());
+    }
+
+    #[test]
+    fn can_represent_less_than_or_equal_as_perfdata_range() {
+        let rule = parse_rule("no salary received by the 3rd").unwrap();
+
+        assert_eq!("@~:0", rule.get_perfdata_range());
+    }
+
+    #[test]
+    fn can_represent_greater_than_as_perfdata_range() {
+        let rule = parse_rule("outgoings this month > 2000").unwrap();
+
+        assert_eq!("~:2000", rule.get_perfdata_range());
+    }
+
+    #[test]
+    fn can_represent_greater_than_or_equal_as_perfdata_range() {
+        let rule = parse_rule("outgoings this month >= 1500.5").unwrap();
+
+        assert_eq!("@1500.5:", rule.get_perfdata_range());
+    }
+
+    #[test]
+    fn can_fail_to_parse_rule() {
+        assert_eq!(true, parse_rule("balance of current is low").is_err());
+        assert_eq!(true, parse_rule("savings < 500").is_err());
+    }
+}
@@ -1,3 +1,5 @@
+/// An account chosen by one of the aliases within the config. `None` is the default
+/// (current) account.
 #[derive(Debug)]
 pub enum AccountType {
     Current,
@@ -7,20 +9,38 @@ pub enum AccountType {
     None,
 }
 
+/// How a list of amounts over time is displayed.
 #[derive(Debug)]
 pub enum OutputFormat {
     Spark,
+    Sparkline,
+    Bars,
+    Line,
     Standard,
 }
 
+/// Whether output is coloured, from `--color`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+/// How transactions are grouped over time.
 #[derive(Debug)]
 pub enum Interval {
     Monthly,
 }
 
+/// How far back a command looks.
 #[derive(Debug)]
 pub enum Timeframe {
     Year,
     SixMonths,
     ThreeMonths,
 }
+
+/// The number of seconds to wait between each poll.
+#[derive(Debug)]
+pub struct PollingInterval(pub u64);
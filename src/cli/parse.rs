@@ -1,7 +1,9 @@
 use rustc_serialize::{Decodable, Decoder};
 
-use cli::arg_types::{AccountType, OutputFormat, Interval, Timeframe};
+use cli::arg_types::{AccountType, OutputFormat, Interval, Timeframe, PollingInterval,
+                     ColorChoice};
 
+/// The arguments of the `teller` binary, as decoded by docopt.
 #[derive(Debug, RustcDecodable)]
 pub struct CliArgs {
     cmd_init: bool,
@@ -10,62 +12,128 @@ pub struct CliArgs {
     cmd_accounts: bool,
     cmd_transactions: bool,
     cmd_counterparties: bool,
+    cmd_counterparty: bool,
     cmd_balances: bool,
     cmd_outgoings: bool,
     cmd_incomings: bool,
+    cmd_anomalies: bool,
     cmd_balance: bool,
     cmd_outgoing: bool,
     cmd_incoming: bool,
+    cmd_stats: bool,
+    cmd_watch: bool,
+    cmd_check: bool,
+    cmd_metrics: bool,
+    cmd_serve: bool,
+    cmd_import: bool,
+    cmd_export: bool,
+    pub arg_counterparty: String,
     pub arg_account: AccountType,
+    pub arg_rule: Vec<String>,
+    pub arg_file: String,
     pub flag_interval: Interval,
     pub flag_timeframe: Timeframe,
     pub flag_count: i64,
+    pub flag_threshold: f64,
+    pub flag_within: i64,
     pub flag_show_description: bool,
     pub flag_hide_currency: bool,
     pub flag_output: OutputFormat,
+    pub flag_every: PollingInterval,
+    pub flag_hook: String,
+    pub flag_perfdata: bool,
+    pub flag_textfile: String,
+    pub flag_listen: String,
+    pub flag_refresh: PollingInterval,
+    pub flag_redact: bool,
+    pub flag_color: ColorChoice,
+    pub flag_input: String,
+    pub flag_account: String,
+    pub flag_format: String,
+    pub flag_out: String,
+    pub flag_columns: String,
+    pub flag_date_format: String,
+    pub flag_decimal_mark: String,
+    pub flag_config: String,
+    pub flag_profile: String,
+    pub flag_auth_token: String,
+    pub flag_alias: Vec<String>,
+    pub flag_from_file: String,
+    pub flag_yes: bool,
     flag_help: bool,
     flag_version: bool,
 }
 
+/// Reads an account alias, which is `AccountType::None` when empty.
+pub fn to_account_type(s: &str) -> AccountType {
+    let default_acccount_type = AccountType::None;
+    match s {
+        "" => default_acccount_type,
+        "current" => AccountType::Current,
+        "savings" => AccountType::Savings,
+        "business" => AccountType::Business,
+        s => AccountType::Unknown(s.to_string()),
+    }
+}
+
+/// Reads an interval, which is monthly unless another is supported.
+pub fn to_interval(s: &str) -> Interval {
+    let default_interval = Interval::Monthly;
+    match s {
+        "" => default_interval,
+        "monthly" => Interval::Monthly,
+        _ => {
+            error!("teller-cli currently only suports an interval of monthly");
+            default_interval
+        }
+    }
+}
+
+/// Reads a duration such as `30s`, `5m` or `1h` as a number of seconds. Nothing
+/// is read from a duration of zero.
+pub fn to_seconds(s: &str) -> Option<u64> {
+    let (number, unit) = s.split_at(s.find(|c: char| !c.is_digit(10)).unwrap_or(s.len()));
+    let seconds_per_unit = match unit {
+        "" | "s" => Some(1),
+        "m" => Some(60),
+        "h" => Some(60 * 60),
+        _ => None,
+    };
+    match (number.parse::<u64>(), seconds_per_unit) {
+        (Ok(n), Some(seconds)) if n > 0 => Some(n * seconds),
+        _ => None,
+    }
+}
+
+/// Reads a timeframe, which is six months unless another is given.
+pub fn to_timeframe(s: &str) -> Timeframe {
+    let default_timeframe = Timeframe::SixMonths;
+    match s {
+        "year" => Timeframe::Year,
+        "6-months" => Timeframe::SixMonths,
+        "3-months" => Timeframe::ThreeMonths,
+        _ => default_timeframe,
+    }
+}
+
 impl Decodable for AccountType {
     fn decode<D: Decoder>(d: &mut D) -> Result<AccountType, D::Error> {
         let s = try!(d.read_str());
-        let default_acccount_type = AccountType::None;
-        Ok(match &*s {
-            "" => default_acccount_type,
-            "current" => AccountType::Current,
-            "savings" => AccountType::Savings,
-            "business" => AccountType::Business,
-            s => AccountType::Unknown(s.to_string()),
-        })
+        Ok(to_account_type(&s))
     }
 }
 
 impl Decodable for Interval {
     fn decode<D: Decoder>(d: &mut D) -> Result<Interval, D::Error> {
         let s = try!(d.read_str());
-        let default_interval = Interval::Monthly;
-        Ok(match &*s {
-            "" => default_interval,
-            "monthly" => Interval::Monthly,
-            _ => {
-                error!("teller-cli currently only suports an interval of monthly");
-                default_interval
-            }
-        })
+        Ok(to_interval(&s))
     }
 }
 
 impl Decodable for Timeframe {
     fn decode<D: Decoder>(d: &mut D) -> Result<Timeframe, D::Error> {
         let s = try!(d.read_str());
-        let default_timeframe = Timeframe::SixMonths;
-        Ok(match &*s {
-            "year" => Timeframe::Year,
-            "6-months" => Timeframe::SixMonths,
-            "3-months" => Timeframe::ThreeMonths,
-            _ => default_timeframe,
-        })
+        Ok(to_timeframe(&s))
     }
 }
 
@@ -75,12 +143,48 @@ impl Decodable for OutputFormat {
         let default_output_format = OutputFormat::Standard;
         Ok(match &*s {
             "spark" => OutputFormat::Spark,
+            "sparkline" => OutputFormat::Sparkline,
+            "bars" => OutputFormat::Bars,
+            "line" => OutputFormat::Line,
             "standard" => OutputFormat::Standard,
             _ => default_output_format,
         })
     }
 }
 
+impl Decodable for ColorChoice {
+    fn decode<D: Decoder>(d: &mut D) -> Result<ColorChoice, D::Error> {
+        let s = try!(d.read_str());
+        let default_color_choice = ColorChoice::Auto;
+        Ok(match &*s {
+            "always" => ColorChoice::Always,
+            "never" => ColorChoice::Never,
+            "auto" => ColorChoice::Auto,
+            _ => {
+                error!("teller-cli could not understand --color={} so will only colour output to \
+                        a terminal",
+                       s);
+                default_color_choice
+            }
+        })
+    }
+}
+
+impl Decodable for PollingInterval {
+    fn decode<D: Decoder>(d: &mut D) -> Result<PollingInterval, D::Error> {
+        let s = try!(d.read_str());
+        let default_polling_interval = PollingInterval(5 * 60);
+        Ok(match to_seconds(&s) {
+            Some(seconds) => PollingInterval(seconds),
+            None => {
+                error!("teller-cli could not understand the interval {} so will poll every 5m", s);
+                default_polling_interval
+            }
+        })
+    }
+}
+
+/// The command which the arguments ask for.
 #[derive(Debug, PartialEq)]
 pub enum CommandType {
     ShowUsage,
@@ -89,14 +193,24 @@ pub enum CommandType {
     ShowBalance,
     ShowOutgoing,
     ShowIncoming,
+    ShowCounterparty,
+    ShowStats,
     ListTransactions,
     ListCounterparties,
     ListBalances,
     ListOutgoings,
     ListIncomings,
+    ListAnomalies,
+    Watch,
+    Check,
+    Metrics,
+    Serve,
+    Import,
+    Export,
     None,
 }
 
+/// Works out which command the arguments ask for.
 pub fn get_command_type(arguments: &CliArgs) -> CommandType {
     match *arguments {
         CliArgs { cmd_init, .. } if cmd_init => CommandType::Initialise,
@@ -104,11 +218,20 @@ pub fn get_command_type(arguments: &CliArgs) -> CommandType {
         CliArgs { cmd_balance, .. } if cmd_balance => CommandType::ShowBalance,
         CliArgs { cmd_outgoing, .. } if cmd_outgoing => CommandType::ShowOutgoing,
         CliArgs { cmd_incoming, .. } if cmd_incoming => CommandType::ShowIncoming,
+        CliArgs { cmd_counterparty, .. } if cmd_counterparty => CommandType::ShowCounterparty,
+        CliArgs { cmd_stats, .. } if cmd_stats => CommandType::ShowStats,
         CliArgs { cmd_transactions, .. } if cmd_transactions => CommandType::ListTransactions,
         CliArgs { cmd_counterparties, .. } if cmd_counterparties => CommandType::ListCounterparties,
         CliArgs { cmd_balances, .. } if cmd_balances => CommandType::ListBalances,
         CliArgs { cmd_incomings, .. } if cmd_incomings => CommandType::ListIncomings,
         CliArgs { cmd_outgoings, .. } if cmd_outgoings => CommandType::ListOutgoings,
+        CliArgs { cmd_anomalies, .. } if cmd_anomalies => CommandType::ListAnomalies,
+        CliArgs { cmd_watch, .. } if cmd_watch => CommandType::Watch,
+        CliArgs { cmd_check, .. } if cmd_check => CommandType::Check,
+        CliArgs { cmd_metrics, .. } if cmd_metrics => CommandType::Metrics,
+        CliArgs { cmd_serve, .. } if cmd_serve => CommandType::Serve,
+        CliArgs { cmd_import, .. } if cmd_import => CommandType::Import,
+        CliArgs { cmd_export, .. } if cmd_export => CommandType::Export,
         CliArgs { flag_help, flag_version, .. } if flag_help || flag_version => CommandType::None,
         _ => CommandType::ShowUsage,
     }
@@ -120,7 +243,8 @@ mod tests {
     use super::CommandType;
     use super::get_command_type;
 
-    use cli::arg_types::{AccountType, OutputFormat, Interval, Timeframe};
+    use cli::arg_types::{AccountType, OutputFormat, Interval, Timeframe, PollingInterval,
+                     ColorChoice};
 
     #[test]
     fn can_fallback_to_show_usage_command_type() {
@@ -131,19 +255,54 @@ mod tests {
             cmd_accounts: false,
             cmd_transactions: false,
             cmd_counterparties: false,
+            cmd_counterparty: false,
             cmd_balances: false,
             cmd_outgoings: false,
             cmd_incomings: false,
+            cmd_anomalies: false,
             cmd_balance: false,
             cmd_outgoing: false,
             cmd_incoming: false,
+            cmd_stats: false,
+            cmd_watch: false,
+            cmd_check: false,
+            cmd_metrics: false,
+            cmd_serve: false,
+            cmd_import: false,
+            cmd_export: false,
+            arg_counterparty: "".to_string(),
             arg_account: AccountType::None,
+            arg_rule: vec![],
+            arg_file: "".to_string(),
             flag_interval: Interval::Monthly,
             flag_timeframe: Timeframe::Year,
             flag_count: 0i64,
+            flag_threshold: 0f64,
+            flag_within: 0i64,
             flag_show_description: false,
             flag_hide_currency: false,
             flag_output: OutputFormat::Standard,
+            flag_every: PollingInterval(300),
+            flag_hook: "".to_string(),
+            flag_perfdata: false,
+            flag_textfile: "".to_string(),
+            flag_listen: "127.0.0.1:8080".to_string(),
+            flag_refresh: PollingInterval(300),
+            flag_redact: false,
+            flag_color: ColorChoice::Auto,
+            flag_input: "".to_string(),
+            flag_account: "".to_string(),
+            flag_format: "".to_string(),
+            flag_out: "".to_string(),
+            flag_columns: "".to_string(),
+            flag_date_format: "".to_string(),
+            flag_decimal_mark: "".to_string(),
+            flag_config: "".to_string(),
+            flag_profile: "".to_string(),
+            flag_auth_token: "".to_string(),
+            flag_alias: vec![],
+            flag_from_file: "".to_string(),
+            flag_yes: false,
             flag_help: false,
             flag_version: false,
         };
@@ -162,19 +321,54 @@ mod tests {
             cmd_accounts: false,
             cmd_transactions: false,
             cmd_counterparties: false,
+            cmd_counterparty: false,
             cmd_balances: false,
             cmd_outgoings: false,
             cmd_incomings: false,
+            cmd_anomalies: false,
             cmd_balance: false,
             cmd_outgoing: false,
             cmd_incoming: false,
+            cmd_stats: false,
+            cmd_watch: false,
+            cmd_check: false,
+            cmd_metrics: false,
+            cmd_serve: false,
+            cmd_import: false,
+            cmd_export: false,
+            arg_counterparty: "".to_string(),
             arg_account: AccountType::None,
+            arg_rule: vec![],
+            arg_file: "".to_string(),
             flag_interval: Interval::Monthly,
             flag_timeframe: Timeframe::Year,
             flag_count: 0i64,
+            flag_threshold: 0f64,
+            flag_within: 0i64,
             flag_show_description: false,
             flag_hide_currency: false,
             flag_output: OutputFormat::Standard,
+            flag_every: PollingInterval(300),
+            flag_hook: "".to_string(),
+            flag_perfdata: false,
+            flag_textfile: "".to_string(),
+            flag_listen: "127.0.0.1:8080".to_string(),
+            flag_refresh: PollingInterval(300),
+            flag_redact: false,
+            flag_color: ColorChoice::Auto,
+            flag_input: "".to_string(),
+            flag_account: "".to_string(),
+            flag_format: "".to_string(),
+            flag_out: "".to_string(),
+            flag_columns: "".to_string(),
+            flag_date_format: "".to_string(),
+            flag_decimal_mark: "".to_string(),
+            flag_config: "".to_string(),
+            flag_profile: "".to_string(),
+            flag_auth_token: "".to_string(),
+            flag_alias: vec![],
+            flag_from_file: "".to_string(),
+            flag_yes: false,
             flag_help: false,
             flag_version: false,
         };
@@ -193,19 +387,54 @@ mod tests {
             cmd_accounts: true,
             cmd_transactions: false,
             cmd_counterparties: false,
+            cmd_counterparty: false,
             cmd_balances: false,
             cmd_outgoings: false,
             cmd_incomings: false,
+            cmd_anomalies: false,
             cmd_balance: false,
             cmd_outgoing: false,
             cmd_incoming: false,
+            cmd_stats: false,
+            cmd_watch: false,
+            cmd_check: false,
+            cmd_metrics: false,
+            cmd_serve: false,
+            cmd_import: false,
+            cmd_export: false,
+            arg_counterparty: "".to_string(),
             arg_account: AccountType::None,
+            arg_rule: vec![],
+            arg_file: "".to_string(),
             flag_interval: Interval::Monthly,
             flag_timeframe: Timeframe::Year,
             flag_count: 0i64,
+            flag_threshold: 0f64,
+            flag_within: 0i64,
             flag_show_description: false,
             flag_hide_currency: false,
             flag_output: OutputFormat::Standard,
+            flag_every: PollingInterval(300),
+            flag_hook: "".to_string(),
+            flag_perfdata: false,
+            flag_textfile: "".to_string(),
+            flag_listen: "127.0.0.1:8080".to_string(),
+            flag_refresh: PollingInterval(300),
+            flag_redact: false,
+            flag_color: ColorChoice::Auto,
+            flag_input: "".to_string(),
+            flag_account: "".to_string(),
+            flag_format: "".to_string(),
+            flag_out: "".to_string(),
+            flag_columns: "".to_string(),
+            flag_date_format: "".to_string(),
+            flag_decimal_mark: "".to_string(),
+            flag_config: "".to_string(),
+            flag_profile: "".to_string(),
+            flag_auth_token: "".to_string(),
+            flag_alias: vec![],
+            flag_from_file: "".to_string(),
+            flag_yes: false,
             flag_help: false,
             flag_version: false,
         };
@@ -224,19 +453,54 @@ mod tests {
             cmd_accounts: false,
             cmd_transactions: true,
             cmd_counterparties: false,
+            cmd_counterparty: false,
             cmd_balances: false,
             cmd_outgoings: false,
             cmd_incomings: false,
+            cmd_anomalies: false,
             cmd_balance: false,
             cmd_outgoing: false,
             cmd_incoming: false,
+            cmd_stats: false,
+            cmd_watch: false,
+            cmd_check: false,
+            cmd_metrics: false,
+            cmd_serve: false,
+            cmd_import: false,
+            cmd_export: false,
+            arg_counterparty: "".to_string(),
             arg_account: AccountType::None,
+            arg_rule: vec![],
+            arg_file: "".to_string(),
             flag_interval: Interval::Monthly,
             flag_timeframe: Timeframe::Year,
             flag_count: 0i64,
+            flag_threshold: 0f64,
+            flag_within: 0i64,
             flag_show_description: false,
             flag_hide_currency: false,
             flag_output: OutputFormat::Standard,
+            flag_every: PollingInterval(300),
+            flag_hook: "".to_string(),
+            flag_perfdata: false,
+            flag_textfile: "".to_string(),
+            flag_listen: "127.0.0.1:8080".to_string(),
+            flag_refresh: PollingInterval(300),
+            flag_redact: false,
+            flag_color: ColorChoice::Auto,
+            flag_input: "".to_string(),
+            flag_account: "".to_string(),
+            flag_format: "".to_string(),
+            flag_out: "".to_string(),
+            flag_columns: "".to_string(),
+            flag_date_format: "".to_string(),
+            flag_decimal_mark: "".to_string(),
+            flag_config: "".to_string(),
+            flag_profile: "".to_string(),
+            flag_auth_token: "".to_string(),
+            flag_alias: vec![],
+            flag_from_file: "".to_string(),
+            flag_yes: false,
             flag_help: false,
             flag_version: false,
         };
@@ -255,19 +519,54 @@ mod tests {
             cmd_accounts: false,
             cmd_transactions: false,
             cmd_counterparties: true,
+            cmd_counterparty: false,
             cmd_balances: false,
             cmd_outgoings: false,
             cmd_incomings: false,
+            cmd_anomalies: false,
             cmd_balance: false,
             cmd_outgoing: false,
             cmd_incoming: false,
+            cmd_stats: false,
+            cmd_watch: false,
+            cmd_check: false,
+            cmd_metrics: false,
+            cmd_serve: false,
+            cmd_import: false,
+            cmd_export: false,
+            arg_counterparty: "".to_string(),
             arg_account: AccountType::None,
+            arg_rule: vec![],
+            arg_file: "".to_string(),
             flag_interval: Interval::Monthly,
             flag_timeframe: Timeframe::Year,
             flag_count: 0i64,
+            flag_threshold: 0f64,
+            flag_within: 0i64,
             flag_show_description: false,
             flag_hide_currency: false,
             flag_output: OutputFormat::Standard,
+            flag_every: PollingInterval(300),
+            flag_hook: "".to_string(),
+            flag_perfdata: false,
+            flag_textfile: "".to_string(),
+            flag_listen: "127.0.0.1:8080".to_string(),
+            flag_refresh: PollingInterval(300),
+            flag_redact: false,
+            flag_color: ColorChoice::Auto,
+            flag_input: "".to_string(),
+            flag_account: "".to_string(),
+            flag_format: "".to_string(),
+            flag_out: "".to_string(),
+            flag_columns: "".to_string(),
+            flag_date_format: "".to_string(),
+            flag_decimal_mark: "".to_string(),
+            flag_config: "".to_string(),
+            flag_profile: "".to_string(),
+            flag_auth_token: "".to_string(),
+            flag_alias: vec![],
+            flag_from_file: "".to_string(),
+            flag_yes: false,
             flag_help: false,
             flag_version: false,
         };
@@ -286,19 +585,54 @@ mod tests {
             cmd_accounts: false,
             cmd_transactions: false,
             cmd_counterparties: false,
+            cmd_counterparty: false,
             cmd_balances: true,
             cmd_outgoings: false,
             cmd_incomings: false,
+            cmd_anomalies: false,
             cmd_balance: false,
             cmd_outgoing: false,
             cmd_incoming: false,
+            cmd_stats: false,
+            cmd_watch: false,
+            cmd_check: false,
+            cmd_metrics: false,
+            cmd_serve: false,
+            cmd_import: false,
+            cmd_export: false,
+            arg_counterparty: "".to_string(),
             arg_account: AccountType::None,
+            arg_rule: vec![],
+            arg_file: "".to_string(),
             flag_interval: Interval::Monthly,
             flag_timeframe: Timeframe::Year,
             flag_count: 0i64,
+            flag_threshold: 0f64,
+            flag_within: 0i64,
             flag_show_description: false,
             flag_hide_currency: false,
             flag_output: OutputFormat::Standard,
+            flag_every: PollingInterval(300),
+            flag_hook: "".to_string(),
+            flag_perfdata: false,
+            flag_textfile: "".to_string(),
+            flag_listen: "127.0.0.1:8080".to_string(),
+            flag_refresh: PollingInterval(300),
+            flag_redact: false,
+            flag_color: ColorChoice::Auto,
+            flag_input: "".to_string(),
+            flag_account: "".to_string(),
+            flag_format: "".to_string(),
+            flag_out: "".to_string(),
+            flag_columns: "".to_string(),
+            flag_date_format: "".to_string(),
+            flag_decimal_mark: "".to_string(),
+            flag_config: "".to_string(),
+            flag_profile: "".to_string(),
+            flag_auth_token: "".to_string(),
+            flag_alias: vec![],
+            flag_from_file: "".to_string(),
+            flag_yes: false,
             flag_help: false,
             flag_version: false,
         };
@@ -317,19 +651,54 @@ mod tests {
             cmd_accounts: false,
             cmd_transactions: false,
             cmd_counterparties: false,
+            cmd_counterparty: false,
             cmd_balances: false,
             cmd_outgoings: true,
             cmd_incomings: false,
+            cmd_anomalies: false,
             cmd_balance: false,
             cmd_outgoing: false,
             cmd_incoming: false,
+            cmd_stats: false,
+            cmd_watch: false,
+            cmd_check: false,
+            cmd_metrics: false,
+            cmd_serve: false,
+            cmd_import: false,
+            cmd_export: false,
+            arg_counterparty: "".to_string(),
             arg_account: AccountType::None,
+            arg_rule: vec![],
+            arg_file: "".to_string(),
             flag_interval: Interval::Monthly,
             flag_timeframe: Timeframe::Year,
             flag_count: 0i64,
+            flag_threshold: 0f64,
+            flag_within: 0i64,
             flag_show_description: false,
             flag_hide_currency: false,
             flag_output: OutputFormat::Standard,
+            flag_every: PollingInterval(300),
+            flag_hook: "".to_string(),
+            flag_perfdata: false,
+            flag_textfile: "".to_string(),
+            flag_listen: "127.0.0.1:8080".to_string(),
+            flag_refresh: PollingInterval(300),
+            flag_redact: false,
+            flag_color: ColorChoice::Auto,
+            flag_input: "".to_string(),
+            flag_account: "".to_string(),
+            flag_format: "".to_string(),
+            flag_out: "".to_string(),
+            flag_columns: "".to_string(),
+            flag_date_format: "".to_string(),
+            flag_decimal_mark: "".to_string(),
+            flag_config: "".to_string(),
+            flag_profile: "".to_string(),
+            flag_auth_token: "".to_string(),
+            flag_alias: vec![],
+            flag_from_file: "".to_string(),
+            flag_yes: false,
             flag_help: false,
             flag_version: false,
         };
@@ -348,19 +717,54 @@ mod tests {
             cmd_accounts: false,
             cmd_transactions: false,
             cmd_counterparties: false,
+            cmd_counterparty: false,
             cmd_balances: false,
             cmd_outgoings: false,
             cmd_incomings: true,
+            cmd_anomalies: false,
             cmd_balance: false,
             cmd_outgoing: false,
             cmd_incoming: false,
+            cmd_stats: false,
+            cmd_watch: false,
+            cmd_check: false,
+            cmd_metrics: false,
+            cmd_serve: false,
+            cmd_import: false,
+            cmd_export: false,
+            arg_counterparty: "".to_string(),
             arg_account: AccountType::None,
+            arg_rule: vec![],
+            arg_file: "".to_string(),
             flag_interval: Interval::Monthly,
             flag_timeframe: Timeframe::Year,
             flag_count: 0i64,
+            flag_threshold: 0f64,
+            flag_within: 0i64,
             flag_show_description: false,
             flag_hide_currency: false,
             flag_output: OutputFormat::Standard,
+            flag_every: PollingInterval(300),
+            flag_hook: "".to_string(),
+            flag_perfdata: false,
+            flag_textfile: "".to_string(),
+            flag_listen: "127.0.0.1:8080".to_string(),
+            flag_refresh: PollingInterval(300),
+            flag_redact: false,
+            flag_color: ColorChoice::Auto,
+            flag_input: "".to_string(),
+            flag_account: "".to_string(),
+            flag_format: "".to_string(),
+            flag_out: "".to_string(),
+            flag_columns: "".to_string(),
+            flag_date_format: "".to_string(),
+            flag_decimal_mark: "".to_string(),
+            flag_config: "".to_string(),
+            flag_profile: "".to_string(),
+            flag_auth_token: "".to_string(),
+            flag_alias: vec![],
+            flag_from_file: "".to_string(),
+            flag_yes: false,
             flag_help: false,
             flag_version: false,
         };
@@ -379,19 +783,54 @@ mod tests {
             cmd_accounts: false,
             cmd_transactions: false,
             cmd_counterparties: false,
+            cmd_counterparty: false,
             cmd_balances: false,
             cmd_outgoings: false,
             cmd_incomings: false,
+            cmd_anomalies: false,
             cmd_balance: true,
             cmd_outgoing: false,
             cmd_incoming: false,
+            cmd_stats: false,
+            cmd_watch: false,
+            cmd_check: false,
+            cmd_metrics: false,
+            cmd_serve: false,
+            cmd_import: false,
+            cmd_export: false,
+            arg_counterparty: "".to_string(),
             arg_account: AccountType::None,
+            arg_rule: vec![],
+            arg_file: "".to_string(),
             flag_interval: Interval::Monthly,
             flag_timeframe: Timeframe::Year,
             flag_count: 0i64,
+            flag_threshold: 0f64,
+            flag_within: 0i64,
             flag_show_description: false,
             flag_hide_currency: false,
             flag_output: OutputFormat::Standard,
+            flag_every: PollingInterval(300),
+            flag_hook: "".to_string(),
+            flag_perfdata: false,
+            flag_textfile: "".to_string(),
+            flag_listen: "127.0.0.1:8080".to_string(),
+            flag_refresh: PollingInterval(300),
+            flag_redact: false,
+            flag_color: ColorChoice::Auto,
+            flag_input: "".to_string(),
+            flag_account: "".to_string(),
+            flag_format: "".to_string(),
+            flag_out: "".to_string(),
+            flag_columns: "".to_string(),
+            flag_date_format: "".to_string(),
+            flag_decimal_mark: "".to_string(),
+            flag_config: "".to_string(),
+            flag_profile: "".to_string(),
+            flag_auth_token: "".to_string(),
+            flag_alias: vec![],
+            flag_from_file: "".to_string(),
+            flag_yes: false,
             flag_help: false,
             flag_version: false,
         };
@@ -410,19 +849,54 @@ mod tests {
             cmd_accounts: false,
             cmd_transactions: false,
             cmd_counterparties: false,
+            cmd_counterparty: false,
             cmd_balances: false,
             cmd_outgoings: false,
             cmd_incomings: false,
+            cmd_anomalies: false,
             cmd_balance: false,
             cmd_outgoing: true,
             cmd_incoming: false,
+            cmd_stats: false,
+            cmd_watch: false,
+            cmd_check: false,
+            cmd_metrics: false,
+            cmd_serve: false,
+            cmd_import: false,
+            cmd_export: false,
+            arg_counterparty: "".to_string(),
             arg_account: AccountType::None,
+            arg_rule: vec![],
+            arg_file: "".to_string(),
             flag_interval: Interval::Monthly,
             flag_timeframe: Timeframe::Year,
             flag_count: 0i64,
+            flag_threshold: 0f64,
+            flag_within: 0i64,
             flag_show_description: false,
             flag_hide_currency: false,
             flag_output: OutputFormat::Standard,
+            flag_every: PollingInterval(300),
+            flag_hook: "".to_string(),
+            flag_perfdata: false,
+            flag_textfile: "".to_string(),
+            flag_listen: "127.0.0.1:8080".to_string(),
+            flag_refresh: PollingInterval(300),
+            flag_redact: false,
+            flag_color: ColorChoice::Auto,
+            flag_input: "".to_string(),
+            flag_account: "".to_string(),
+            flag_format: "".to_string(),
+            flag_out: "".to_string(),
+            flag_columns: "".to_string(),
+            flag_date_format: "".to_string(),
+            flag_decimal_mark: "".to_string(),
+            flag_config: "".to_string(),
+            flag_profile: "".to_string(),
+            flag_auth_token: "".to_string(),
+            flag_alias: vec![],
+            flag_from_file: "".to_string(),
+            flag_yes: false,
             flag_help: false,
             flag_version: false,
         };
@@ -441,19 +915,54 @@ mod tests {
             cmd_accounts: false,
             cmd_transactions: false,
             cmd_counterparties: false,
+            cmd_counterparty: false,
             cmd_balances: false,
             cmd_outgoings: false,
             cmd_incomings: false,
+            cmd_anomalies: false,
             cmd_balance: false,
             cmd_outgoing: false,
             cmd_incoming: true,
+            cmd_stats: false,
+            cmd_watch: false,
+            cmd_check: false,
+            cmd_metrics: false,
+            cmd_serve: false,
+            cmd_import: false,
+            cmd_export: false,
+            arg_counterparty: "".to_string(),
             arg_account: AccountType::None,
+            arg_rule: vec![],
+            arg_file: "".to_string(),
             flag_interval: Interval::Monthly,
             flag_timeframe: Timeframe::Year,
             flag_count: 0i64,
+            flag_threshold: 0f64,
+            flag_within: 0i64,
             flag_show_description: false,
             flag_hide_currency: false,
             flag_output: OutputFormat::Standard,
+            flag_every: PollingInterval(300),
+            flag_hook: "".to_string(),
+            flag_perfdata: false,
+            flag_textfile: "".to_string(),
+            flag_listen: "127.0.0.1:8080".to_string(),
+            flag_refresh: PollingInterval(300),
+            flag_redact: false,
+            flag_color: ColorChoice::Auto,
+            flag_input: "".to_string(),
+            flag_account: "".to_string(),
+            flag_format: "".to_string(),
+            flag_out: "".to_string(),
+            flag_columns: "".to_string(),
+            flag_date_format: "".to_string(),
+            flag_decimal_mark: "".to_string(),
+            flag_config: "".to_string(),
+            flag_profile: "".to_string(),
+            flag_auth_token: "".to_string(),
+            flag_alias: vec![],
+            flag_from_file: "".to_string(),
+            flag_yes: false,
             flag_help: false,
             flag_version: false,
         };
@@ -463,4 +972,598 @@ mod tests {
         assert_eq!(CommandType::ShowIncoming, command_type);
     }
 
+    #[test]
+    fn can_get_show_counterparty_command_type() {
+        let args = CliArgs {
+            cmd_init: false,
+            cmd_list: false,
+            cmd_show: true,
+            cmd_accounts: false,
+            cmd_transactions: false,
+            cmd_counterparties: false,
+            cmd_counterparty: true,
+            cmd_balances: false,
+            cmd_outgoings: false,
+            cmd_incomings: false,
+            cmd_anomalies: false,
+            cmd_balance: false,
+            cmd_outgoing: false,
+            cmd_incoming: false,
+            cmd_stats: false,
+            cmd_watch: false,
+            cmd_check: false,
+            cmd_metrics: false,
+            cmd_serve: false,
+            cmd_import: false,
+            cmd_export: false,
+            arg_counterparty: "NANNA'S".to_string(),
+            arg_account: AccountType::None,
+            arg_rule: vec![],
+            arg_file: "".to_string(),
+            flag_interval: Interval::Monthly,
+            flag_timeframe: Timeframe::Year,
+            flag_count: 0i64,
+            flag_threshold: 0f64,
+            flag_within: 0i64,
+            flag_show_description: false,
+            flag_hide_currency: false,
+            flag_output: OutputFormat::Standard,
+            flag_every: PollingInterval(300),
+            flag_hook: "".to_string(),
+            flag_perfdata: false,
+            flag_textfile: "".to_string(),
+            flag_listen: "127.0.0.1:8080".to_string(),
+            flag_refresh: PollingInterval(300),
+            flag_redact: false,
+            flag_color: ColorChoice::Auto,
+            flag_input: "".to_string(),
+            flag_account: "".to_string(),
+            flag_format: "".to_string(),
+            flag_out: "".to_string(),
+            flag_columns: "".to_string(),
+            flag_date_format: "".to_string(),
+            flag_decimal_mark: "".to_string(),
+            flag_config: "".to_string(),
+            flag_profile: "".to_string(),
+            flag_auth_token: "".to_string(),
+            flag_alias: vec![],
+            flag_from_file: "".to_string(),
+            flag_yes: false,
+            flag_help: false,
+            flag_version: false,
+        };
+
+        let command_type = get_command_type(&args);
+
+        assert_eq!(CommandType::ShowCounterparty, command_type);
+    }
+
+    #[test]
+    fn can_get_show_stats_command_type() {
+        let args = CliArgs {
+            cmd_init: false,
+            cmd_list: false,
+            cmd_show: false,
+            cmd_accounts: false,
+            cmd_transactions: false,
+            cmd_counterparties: false,
+            cmd_counterparty: false,
+            cmd_balances: false,
+            cmd_outgoings: false,
+            cmd_incomings: false,
+            cmd_anomalies: false,
+            cmd_balance: false,
+            cmd_outgoing: false,
+            cmd_incoming: false,
+            cmd_stats: true,
+            cmd_watch: false,
+            cmd_check: false,
+            cmd_metrics: false,
+            cmd_serve: false,
+            cmd_import: false,
+            cmd_export: false,
+            arg_counterparty: "".to_string(),
+            arg_account: AccountType::None,
+            arg_rule: vec![],
+            arg_file: "".to_string(),
+            flag_interval: Interval::Monthly,
+            flag_timeframe: Timeframe::Year,
+            flag_count: 0i64,
+            flag_threshold: 0f64,
+            flag_within: 0i64,
+            flag_show_description: false,
+            flag_hide_currency: false,
+            flag_output: OutputFormat::Standard,
+            flag_every: PollingInterval(300),
+            flag_hook: "".to_string(),
+            flag_perfdata: false,
+            flag_textfile: "".to_string(),
+            flag_listen: "127.0.0.1:8080".to_string(),
+            flag_refresh: PollingInterval(300),
+            flag_redact: false,
+            flag_color: ColorChoice::Auto,
+            flag_input: "".to_string(),
+            flag_account: "".to_string(),
+            flag_format: "".to_string(),
+            flag_out: "".to_string(),
+            flag_columns: "".to_string(),
+            flag_date_format: "".to_string(),
+            flag_decimal_mark: "".to_string(),
+            flag_config: "".to_string(),
+            flag_profile: "".to_string(),
+            flag_auth_token: "".to_string(),
+            flag_alias: vec![],
+            flag_from_file: "".to_string(),
+            flag_yes: false,
+            flag_help: false,
+            flag_version: false,
+        };
+
+        let command_type = get_command_type(&args);
+
+        assert_eq!(CommandType::ShowStats, command_type);
+    }
+
+    #[test]
+    fn can_get_list_anomalies_command_type() {
+        let args = CliArgs {
+            cmd_init: false,
+            cmd_list: true,
+            cmd_show: false,
+            cmd_accounts: false,
+            cmd_transactions: false,
+            cmd_counterparties: false,
+            cmd_counterparty: false,
+            cmd_balances: false,
+            cmd_outgoings: false,
+            cmd_incomings: false,
+            cmd_anomalies: true,
+            cmd_balance: false,
+            cmd_outgoing: false,
+            cmd_incoming: false,
+            cmd_stats: false,
+            cmd_watch: false,
+            cmd_check: false,
+            cmd_metrics: false,
+            cmd_serve: false,
+            cmd_import: false,
+            cmd_export: false,
+            arg_counterparty: "".to_string(),
+            arg_account: AccountType::None,
+            arg_rule: vec![],
+            arg_file: "".to_string(),
+            flag_interval: Interval::Monthly,
+            flag_timeframe: Timeframe::Year,
+            flag_count: 0i64,
+            flag_threshold: 0f64,
+            flag_within: 0i64,
+            flag_show_description: false,
+            flag_hide_currency: false,
+            flag_output: OutputFormat::Standard,
+            flag_every: PollingInterval(300),
+            flag_hook: "".to_string(),
+            flag_perfdata: false,
+            flag_textfile: "".to_string(),
+            flag_listen: "127.0.0.1:8080".to_string(),
+            flag_refresh: PollingInterval(300),
+            flag_redact: false,
+            flag_color: ColorChoice::Auto,
+            flag_input: "".to_string(),
+            flag_account: "".to_string(),
+            flag_format: "".to_string(),
+            flag_out: "".to_string(),
+            flag_columns: "".to_string(),
+            flag_date_format: "".to_string(),
+            flag_decimal_mark: "".to_string(),
+            flag_config: "".to_string(),
+            flag_profile: "".to_string(),
+            flag_auth_token: "".to_string(),
+            flag_alias: vec![],
+            flag_from_file: "".to_string(),
+            flag_yes: false,
+            flag_help: false,
+            flag_version: false,
+        };
+
+        let command_type = get_command_type(&args);
+
+        assert_eq!(CommandType::ListAnomalies, command_type);
+    }
+
+    #[test]
+    fn can_get_watch_command_type() {
+        let args = CliArgs {
+            cmd_init: false,
+            cmd_list: false,
+            cmd_show: false,
+            cmd_accounts: false,
+            cmd_transactions: false,
+            cmd_counterparties: false,
+            cmd_counterparty: false,
+            cmd_balances: false,
+            cmd_outgoings: false,
+            cmd_incomings: false,
+            cmd_anomalies: false,
+            cmd_balance: false,
+            cmd_outgoing: false,
+            cmd_incoming: false,
+            cmd_stats: false,
+            cmd_watch: true,
+            cmd_check: false,
+            cmd_metrics: false,
+            cmd_serve: false,
+            cmd_import: false,
+            cmd_export: false,
+            arg_counterparty: "".to_string(),
+            arg_account: AccountType::None,
+            arg_rule: vec![],
+            arg_file: "".to_string(),
+            flag_interval: Interval::Monthly,
+            flag_timeframe: Timeframe::Year,
+            flag_count: 0i64,
+            flag_threshold: 0f64,
+            flag_within: 0i64,
+            flag_show_description: false,
+            flag_hide_currency: false,
+            flag_output: OutputFormat::Standard,
+            flag_every: PollingInterval(300),
+            flag_hook: "".to_string(),
+            flag_perfdata: false,
+            flag_textfile: "".to_string(),
+            flag_listen: "127.0.0.1:8080".to_string(),
+            flag_refresh: PollingInterval(300),
+            flag_redact: false,
+            flag_color: ColorChoice::Auto,
+            flag_input: "".to_string(),
+            flag_account: "".to_string(),
+            flag_format: "".to_string(),
+            flag_out: "".to_string(),
+            flag_columns: "".to_string(),
+            flag_date_format: "".to_string(),
+            flag_decimal_mark: "".to_string(),
+            flag_config: "".to_string(),
+            flag_profile: "".to_string(),
+            flag_auth_token: "".to_string(),
+            flag_alias: vec![],
+            flag_from_file: "".to_string(),
+            flag_yes: false,
+            flag_help: false,
+            flag_version: false,
+        };
+
+        let command_type = get_command_type(&args);
+
+        assert_eq!(CommandType::Watch, command_type);
+    }
+
+    #[test]
+    fn can_get_check_command_type() {
+        let args = CliArgs {
+            cmd_init: false,
+            cmd_list: false,
+            cmd_show: false,
+            cmd_accounts: false,
+            cmd_transactions: false,
+            cmd_counterparties: false,
+            cmd_counterparty: false,
+            cmd_balances: false,
+            cmd_outgoings: false,
+            cmd_incomings: false,
+            cmd_anomalies: false,
+            cmd_balance: false,
+            cmd_outgoing: false,
+            cmd_incoming: false,
+            cmd_stats: false,
+            cmd_watch: false,
+            cmd_check: true,
+            cmd_metrics: false,
+            cmd_serve: false,
+            cmd_import: false,
+            cmd_export: false,
+            arg_counterparty: "".to_string(),
+            arg_account: AccountType::None,
+            arg_rule: vec![],
+            arg_file: "".to_string(),
+            flag_interval: Interval::Monthly,
+            flag_timeframe: Timeframe::Year,
+            flag_count: 0i64,
+            flag_threshold: 0f64,
+            flag_within: 0i64,
+            flag_show_description: false,
+            flag_hide_currency: false,
+            flag_output: OutputFormat::Standard,
+            flag_every: PollingInterval(300),
+            flag_hook: "".to_string(),
+            flag_perfdata: false,
+            flag_textfile: "".to_string(),
+            flag_listen: "127.0.0.1:8080".to_string(),
+            flag_refresh: PollingInterval(300),
+            flag_redact: false,
+            flag_color: ColorChoice::Auto,
+            flag_input: "".to_string(),
+            flag_account: "".to_string(),
+            flag_format: "".to_string(),
+            flag_out: "".to_string(),
+            flag_columns: "".to_string(),
+            flag_date_format: "".to_string(),
+            flag_decimal_mark: "".to_string(),
+            flag_config: "".to_string(),
+            flag_profile: "".to_string(),
+            flag_auth_token: "".to_string(),
+            flag_alias: vec![],
+            flag_from_file: "".to_string(),
+            flag_yes: false,
+            flag_help: false,
+            flag_version: false,
+        };
+
+        let command_type = get_command_type(&args);
+
+        assert_eq!(CommandType::Check, command_type);
+    }
+
+    #[test]
+    fn can_get_metrics_command_type() {
+        let args = CliArgs {
+            cmd_init: false,
+            cmd_list: false,
+            cmd_show: false,
+            cmd_accounts: false,
+            cmd_transactions: false,
+            cmd_counterparties: false,
+            cmd_counterparty: false,
+            cmd_balances: false,
+            cmd_outgoings: false,
+            cmd_incomings: false,
+            cmd_anomalies: false,
+            cmd_balance: false,
+            cmd_outgoing: false,
+            cmd_incoming: false,
+            cmd_stats: false,
+            cmd_watch: false,
+            cmd_check: false,
+            cmd_metrics: true,
+            cmd_serve: false,
+            cmd_import: false,
+            cmd_export: false,
+            arg_counterparty: "".to_string(),
+            arg_account: AccountType::None,
+            arg_rule: vec![],
+            arg_file: "".to_string(),
+            flag_interval: Interval::Monthly,
+            flag_timeframe: Timeframe::Year,
+            flag_count: 0i64,
+            flag_threshold: 0f64,
+            flag_within: 0i64,
+            flag_show_description: false,
+            flag_hide_currency: false,
+            flag_output: OutputFormat::Standard,
+            flag_every: PollingInterval(300),
+            flag_hook: "".to_string(),
+            flag_perfdata: false,
+            flag_textfile: "".to_string(),
+            flag_listen: "127.0.0.1:8080".to_string(),
+            flag_refresh: PollingInterval(300),
+            flag_redact: false,
+            flag_color: ColorChoice::Auto,
+            flag_input: "".to_string(),
+            flag_account: "".to_string(),
+            flag_format: "".to_string(),
+            flag_out: "".to_string(),
+            flag_columns: "".to_string(),
+            flag_date_format: "".to_string(),
+            flag_decimal_mark: "".to_string(),
+            flag_config: "".to_string(),
+            flag_profile: "".to_string(),
+            flag_auth_token: "".to_string(),
+            flag_alias: vec![],
+            flag_from_file: "".to_string(),
+            flag_yes: false,
+            flag_help: false,
+            flag_version: false,
+        };
+
+        let command_type = get_command_type(&args);
+
+        assert_eq!(CommandType::Metrics, command_type);
+    }
+
+    #[test]
+    fn can_get_serve_command_type() {
+        let args = CliArgs {
+            cmd_init: false,
+            cmd_list: false,
+            cmd_show: false,
+            cmd_accounts: false,
+            cmd_transactions: false,
+            cmd_counterparties: false,
+            cmd_counterparty: false,
+            cmd_balances: false,
+            cmd_outgoings: false,
+            cmd_incomings: false,
+            cmd_anomalies: false,
+            cmd_balance: false,
+            cmd_outgoing: false,
+            cmd_incoming: false,
+            cmd_stats: false,
+            cmd_watch: false,
+            cmd_check: false,
+            cmd_metrics: false,
+            cmd_serve: true,
+            cmd_import: false,
+            cmd_export: false,
+            arg_counterparty: "".to_string(),
+            arg_account: AccountType::None,
+            arg_rule: vec![],
+            arg_file: "".to_string(),
+            flag_interval: Interval::Monthly,
+            flag_timeframe: Timeframe::Year,
+            flag_count: 0i64,
+            flag_threshold: 0f64,
+            flag_within: 0i64,
+            flag_show_description: false,
+            flag_hide_currency: false,
+            flag_output: OutputFormat::Standard,
+            flag_every: PollingInterval(300),
+            flag_hook: "".to_string(),
+            flag_perfdata: false,
+            flag_textfile: "".to_string(),
+            flag_listen: "127.0.0.1:8080".to_string(),
+            flag_refresh: PollingInterval(300),
+            flag_redact: false,
+            flag_color: ColorChoice::Auto,
+            flag_input: "".to_string(),
+            flag_account: "".to_string(),
+            flag_format: "".to_string(),
+            flag_out: "".to_string(),
+            flag_columns: "".to_string(),
+            flag_date_format: "".to_string(),
+            flag_decimal_mark: "".to_string(),
+            flag_config: "".to_string(),
+            flag_profile: "".to_string(),
+            flag_auth_token: "".to_string(),
+            flag_alias: vec![],
+            flag_from_file: "".to_string(),
+            flag_yes: false,
+            flag_help: false,
+            flag_version: false,
+        };
+
+        let command_type = get_command_type(&args);
+
+        assert_eq!(CommandType::Serve, command_type);
+    }
+
+    #[test]
+    fn can_get_import_command_type() {
+        let args = CliArgs {
+            cmd_init: false,
+            cmd_list: false,
+            cmd_show: false,
+            cmd_accounts: false,
+            cmd_transactions: false,
+            cmd_counterparties: false,
+            cmd_counterparty: false,
+            cmd_balances: false,
+            cmd_outgoings: false,
+            cmd_incomings: false,
+            cmd_anomalies: false,
+            cmd_balance: false,
+            cmd_outgoing: false,
+            cmd_incoming: false,
+            cmd_stats: false,
+            cmd_watch: false,
+            cmd_check: false,
+            cmd_metrics: false,
+            cmd_serve: false,
+            cmd_import: true,
+            cmd_export: false,
+            arg_counterparty: "".to_string(),
+            arg_account: AccountType::None,
+            arg_rule: vec![],
+            arg_file: "statement.ofx".to_string(),
+            flag_interval: Interval::Monthly,
+            flag_timeframe: Timeframe::Year,
+            flag_count: 0i64,
+            flag_threshold: 0f64,
+            flag_within: 0i64,
+            flag_show_description: false,
+            flag_hide_currency: false,
+            flag_output: OutputFormat::Standard,
+            flag_every: PollingInterval(300),
+            flag_hook: "".to_string(),
+            flag_perfdata: false,
+            flag_textfile: "".to_string(),
+            flag_listen: "127.0.0.1:8080".to_string(),
+            flag_refresh: PollingInterval(300),
+            flag_redact: false,
+            flag_color: ColorChoice::Auto,
+            flag_input: "".to_string(),
+            flag_account: "joint".to_string(),
+            flag_format: "".to_string(),
+            flag_out: "".to_string(),
+            flag_columns: "".to_string(),
+            flag_date_format: "".to_string(),
+            flag_decimal_mark: "".to_string(),
+            flag_config: "".to_string(),
+            flag_profile: "".to_string(),
+            flag_auth_token: "".to_string(),
+            flag_alias: vec![],
+            flag_from_file: "".to_string(),
+            flag_yes: false,
+            flag_help: false,
+            flag_version: false,
+        };
+
+        let command_type = get_command_type(&args);
+
+        assert_eq!(CommandType::Import, command_type);
+    }
+
+    #[test]
+    fn can_get_export_command_type() {
+        let args = CliArgs {
+            cmd_init: false,
+            cmd_list: false,
+            cmd_show: false,
+            cmd_accounts: false,
+            cmd_transactions: false,
+            cmd_counterparties: false,
+            cmd_counterparty: false,
+            cmd_balances: false,
+            cmd_outgoings: false,
+            cmd_incomings: false,
+            cmd_anomalies: false,
+            cmd_balance: false,
+            cmd_outgoing: false,
+            cmd_incoming: false,
+            cmd_stats: false,
+            cmd_watch: false,
+            cmd_check: false,
+            cmd_metrics: false,
+            cmd_serve: false,
+            cmd_import: false,
+            cmd_export: true,
+            arg_counterparty: "".to_string(),
+            arg_account: AccountType::None,
+            arg_rule: vec![],
+            arg_file: "".to_string(),
+            flag_interval: Interval::Monthly,
+            flag_timeframe: Timeframe::Year,
+            flag_count: 0i64,
+            flag_threshold: 0f64,
+            flag_within: 0i64,
+            flag_show_description: false,
+            flag_hide_currency: false,
+            flag_output: OutputFormat::Standard,
+            flag_every: PollingInterval(300),
+            flag_hook: "".to_string(),
+            flag_perfdata: false,
+            flag_textfile: "".to_string(),
+            flag_listen: "127.0.0.1:8080".to_string(),
+            flag_refresh: PollingInterval(300),
+            flag_redact: false,
+            flag_color: ColorChoice::Auto,
+            flag_input: "".to_string(),
+            flag_account: "".to_string(),
+            flag_format: "ledger".to_string(),
+            flag_out: "".to_string(),
+            flag_columns: "".to_string(),
+            flag_date_format: "".to_string(),
+            flag_decimal_mark: "".to_string(),
+            flag_config: "".to_string(),
+            flag_profile: "".to_string(),
+            flag_auth_token: "".to_string(),
+            flag_alias: vec![],
+            flag_from_file: "".to_string(),
+            flag_yes: false,
+            flag_help: false,
+            flag_version: false,
+        };
+
+        let command_type = get_command_type(&args);
+
+        assert_eq!(CommandType::Export, command_type);
+    }
+
 }
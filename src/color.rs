@@ -0,0 +1,170 @@
+use std::env;
+use std::sync::Mutex;
+
+use libc;
+
+use cli::arg_types::ColorChoice;
+use locale::parse_amount;
+
+const BOLD: &'static str = "\x1b[1m";
+const RED: &'static str = "\x1b[31m";
+const GREEN: &'static str = "\x1b[32m";
+const CYAN: &'static str = "\x1b[36m";
+const RESET: &'static str = "\x1b[0m";
+
+/// Colours are added after a table has been aligned, as escape codes would
+/// otherwise be counted within the widths of its columns.
+#[derive(Debug)]
+pub struct Colorizer {
+    color_output: bool,
+}
+
+impl Colorizer {
+    /// A colorizer which leaves output plain until colour is enabled.
+    pub fn new() -> Colorizer {
+        Colorizer { color_output: false }
+    }
+
+    fn paint_word(&self, word: &str) -> String {
+        // Amounts are told apart from dates and counts by how they are
+        // displayed, e.g. `-2.70` or `£1,234.56`.
+        match parse_amount(&word) {
+            Some(amount) if amount < 0f64 => format!("{}{}{}", RED, word, RESET),
+            Some(amount) if amount > 0f64 => format!("{}{}{}", GREEN, word, RESET),
+            _ => word.to_string(),
+        }
+    }
+
+    /// Colours the negative amounts within some text red and the positive ones green.
+    pub fn paint_amounts(&self, text: &str) -> String {
+        if !self.color_output {
+            return text.to_string();
+        }
+
+        let mut painted = String::new();
+        let mut word = String::new();
+        for c in text.chars() {
+            if c.is_whitespace() {
+                painted.push_str(&self.paint_word(&word));
+                word.clear();
+                painted.push(c);
+            } else {
+                word.push(c);
+            }
+        }
+        painted.push_str(&self.paint_word(&word));
+        painted
+    }
+
+    /// Makes the first row of a table bold and colours the amounts within the
+    /// rest of it.
+    pub fn paint_table(&self, table: &str) -> String {
+        if !self.color_output {
+            return table.to_string();
+        }
+
+        let (header, rows) = match table.find('\n') {
+            Some(idx) => table.split_at(idx),
+            None => (table, ""),
+        };
+        let header = if header.trim().is_empty() {
+            header.to_string()
+        } else {
+            format!("{}{}{}", BOLD, header, RESET)
+        };
+        header + &self.paint_amounts(&rows)
+    }
+
+    /// Colours each of `markers`, such as the alias `(current)`, wherever it appears.
+    pub fn paint_markers(&self, text: &str, markers: &Vec<String>) -> String {
+        if !self.color_output {
+            return text.to_string();
+        }
+
+        markers.iter()
+               .filter(|marker| !marker.is_empty())
+               .fold(text.to_string(), |text, marker| {
+                   text.replace(&marker[..], &format!("{}{}{}", CYAN, marker, RESET))
+               })
+    }
+}
+
+/// Whether to colour output: `auto` colours it when it goes to a terminal and
+/// `NO_COLOR` is not set, while `always` and `never` take precedence over both.
+pub fn should_color(choice: &ColorChoice, is_terminal: bool, is_no_color_set: bool) -> bool {
+    match *choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => is_terminal && !is_no_color_set,
+    }
+}
+
+/// Whether to colour what is written to stdout. See `should_color`.
+pub fn should_color_stdout(choice: &ColorChoice) -> bool {
+    let is_terminal = unsafe { libc::isatty(libc::STDOUT_FILENO) == 1 };
+    let is_no_color_set = env::var_os("NO_COLOR").map_or(false, |value| !value.is_empty());
+    should_color(&choice, is_terminal, is_no_color_set)
+}
+
+lazy_static! {
+    static ref COLORIZER: Mutex<Colorizer> = Mutex::new(Colorizer::new());
+}
+
+/// Colours the output of every command from now on.
+pub fn enable_color() {
+    COLORIZER.lock().unwrap().color_output = true;
+}
+
+/// Colours the amounts within some text if colour is enabled.
+pub fn paint_amounts(text: &str) -> String {
+    COLORIZER.lock().unwrap().paint_amounts(&text)
+}
+
+/// Colours a table if colour is enabled.
+pub fn paint_table(table: &str) -> String {
+    COLORIZER.lock().unwrap().paint_table(&table)
+}
+
+/// Colours some markers within text if colour is enabled.
+pub fn paint_markers(text: &str, markers: &Vec<String>) -> String {
+    COLORIZER.lock().unwrap().paint_markers(&text, &markers)
+}
+
+#[cfg(test)]
+mod tests {
+    use cli::arg_types::ColorChoice;
+    use super::{Colorizer, should_color};
+
+    const TABLE: &'static str = "row  date        amount (GBP)\n1 (current)  2016-01-21  -2.70\n2    \
+                                 2016-01-22  1500.00\n";
+
+    #[test]
+    fn can_leave_output_plain() {
+        let colorizer = Colorizer::new();
+
+        assert_eq!(TABLE, colorizer.paint_table(TABLE));
+        assert_eq!(TABLE,
+                   colorizer.paint_markers(TABLE, &vec!["(current)".to_string()]));
+    }
+
+    #[test]
+    fn can_paint_table() {
+        let mut colorizer = Colorizer::new();
+        colorizer.color_output = true;
+
+        assert_eq!("\x1b[1mrow  date        amount (GBP)\x1b[0m\n1 (current)  2016-01-21  \
+                    \x1b[31m-2.70\x1b[0m\n2    2016-01-22  \x1b[32m1500.00\x1b[0m\n",
+                   colorizer.paint_table(TABLE));
+        assert_eq!("1 \x1b[36m(current)\x1b[0m 0.00",
+                   colorizer.paint_markers("1 (current) 0.00", &vec!["(current)".to_string()]));
+    }
+
+    #[test]
+    fn can_decide_whether_to_color() {
+        assert!(should_color(&ColorChoice::Auto, true, false));
+        assert!(!should_color(&ColorChoice::Auto, true, true));
+        assert!(!should_color(&ColorChoice::Auto, false, false));
+        assert!(should_color(&ColorChoice::Always, false, true));
+        assert!(!should_color(&ColorChoice::Never, true, false));
+    }
+}
@@ -0,0 +1,288 @@
+use std::iter::repeat;
+
+use locale::format_amount;
+
+use command::representations::to_sparkline;
+
+const LINE_CHART_HEIGHT: usize = 10;
+
+// Partial blocks let a bar end within a column rather than upon its edge.
+const PARTIAL_BLOCKS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+fn to_amount_str(amount: f64) -> String {
+    format_amount(&format!("{:.2}", amount))
+}
+
+fn get_min_max(points: &Vec<(String, f64)>) -> (f64, f64) {
+    let min = points.iter().map(|p| p.1).fold(f64::INFINITY, f64::min);
+    let max = points.iter().map(|p| p.1).fold(f64::NEG_INFINITY, f64::max);
+    (min, max)
+}
+
+fn to_bar(fraction: f64, width: usize, is_negative: bool) -> String {
+    // Negative amounts are shaded, as a bar only has a length.
+    if is_negative {
+        let length = (fraction * width as f64).round() as usize;
+        return repeat('░').take(length).collect();
+    }
+
+    let eighths = (fraction * width as f64 * 8f64).round() as usize;
+    let mut bar: String = repeat('█').take(eighths / 8).collect();
+    if eighths % 8 > 0 {
+        bar.push(PARTIAL_BLOCKS[eighths % 8]);
+    }
+    bar
+}
+
+/// A sparkline of the most recent amounts which fit within the width,
+/// followed by the smallest and largest of them.
+pub fn to_sparkline_chart(label: &str, points: &Vec<(String, f64)>, width: usize) -> String {
+    if points.is_empty() {
+        return String::new();
+    }
+
+    let (min, max) = get_min_max(&points);
+    let summary = format!("min {} max {}", to_amount_str(min), to_amount_str(max));
+    let available = width.saturating_sub(label.chars().count() + summary.len() + 4).max(1);
+
+    let skip_n = points.len().saturating_sub(available);
+    let amounts = points.iter().skip(skip_n).map(|p| p.1).collect::<Vec<f64>>();
+    format!("{}  {}  {}\n", label, to_sparkline(&amounts), summary)
+}
+
+/// A horizontal bar for each amount, labelled with its interval (or name) and
+/// scaled so that the longest bar reaches the edge of the width.
+pub fn to_bar_chart(points: &Vec<(String, f64)>, width: usize) -> String {
+    let amounts = points.iter().map(|p| to_amount_str(p.1)).collect::<Vec<String>>();
+    let label_width = points.iter().map(|p| p.0.chars().count()).max().unwrap_or(0);
+    let amount_width = amounts.iter().map(|a| a.len()).max().unwrap_or(0);
+    let bar_width = width.saturating_sub(label_width + amount_width + 4).max(1);
+    let max_abs = points.iter().map(|p| p.1.abs()).fold(0f64, f64::max);
+
+    let mut chart = String::new();
+    for (point, amount) in points.iter().zip(amounts.iter()) {
+        let fraction = if max_abs == 0f64 {
+            0f64
+        } else {
+            point.1.abs() / max_abs
+        };
+        chart.push_str(&format!("{:<lw$}  {:<bw$}  {:>aw$}\n",
+                                point.0,
+                                to_bar(fraction, bar_width, point.1 < 0f64),
+                                amount,
+                                lw = label_width,
+                                bw = bar_width,
+                                aw = amount_width));
+    }
+    chart
+}
+
+/// An ASCII line chart whose axis is labelled with the largest and smallest
+/// amounts and the first and last intervals, stretched across the width.
+pub fn to_line_chart(points: &Vec<(String, f64)>, width: usize) -> String {
+    if points.is_empty() {
+        return String::new();
+    }
+
+    let (min, max) = get_min_max(&points);
+    let (min_label, max_label) = (to_amount_str(min), to_amount_str(max));
+    let axis_width = min_label.len().max(max_label.len());
+    let plot_width = width.saturating_sub(axis_width + 2).max(points.len()).max(2);
+    let height = LINE_CHART_HEIGHT;
+
+    let last_point = (points.len() - 1) as f64;
+    let to_row = |amount: f64| -> usize {
+        if max == min {
+            0
+        } else {
+            ((amount - min) / (max - min) * (height - 1) as f64).round() as usize
+        }
+    };
+    // Each column shows the amount between its neighbouring points.
+    let rows = (0..plot_width)
+                   .map(|column| {
+                       let position = column as f64 * last_point / (plot_width - 1) as f64;
+                       let idx = position.floor() as usize;
+                       let amount = if idx as f64 >= last_point {
+                           points[points.len() - 1].1
+                       } else {
+                           let fraction = position - idx as f64;
+                           points[idx].1 + (points[idx + 1].1 - points[idx].1) * fraction
+                       };
+                       to_row(amount)
+                   })
+                   .collect::<Vec<usize>>();
+    let point_columns = (0..points.len())
+                            .map(|idx| {
+                                (idx as f64 * (plot_width - 1) as f64 / last_point.max(1f64))
+                                    .round() as usize
+                            })
+                            .collect::<Vec<usize>>();
+
+    let mut grid = vec![vec![' '; plot_width]; height];
+    for column in 0..plot_width {
+        let row = rows[column];
+        let previous_row = if column == 0 { row } else { rows[column - 1] };
+        grid[row][column] = if point_columns.contains(&column) {
+            '*'
+        } else if row > previous_row {
+            '/'
+        } else if row < previous_row {
+            '\\'
+        } else {
+            '-'
+        };
+        // Steep lines would otherwise have gaps within them.
+        let (low, high) = (row.min(previous_row), row.max(previous_row));
+        for gap_row in (low + 1)..high {
+            grid[gap_row][column] = '|';
+        }
+    }
+
+    let mut chart = String::new();
+    for (idx, grid_row) in grid.iter().rev().enumerate() {
+        let label = if idx == 0 {
+            &max_label[..]
+        } else if idx == height - 1 {
+            &min_label[..]
+        } else {
+            ""
+        };
+        let line: String = grid_row.iter().cloned().collect();
+        chart.push_str(&format!("{:>aw$} |{}\n", label, line.trim_right(), aw = axis_width));
+    }
+    chart.push_str(&format!("{:>aw$} +{}\n",
+                            "",
+                            repeat('-').take(plot_width).collect::<String>(),
+                            aw = axis_width));
+
+    let first_label = &points[0].0;
+    let last_label = &points[points.len() - 1].0;
+    if points.len() > 1 {
+        let gap = plot_width.saturating_sub(first_label.chars().count());
+        chart.push_str(&format!("{:>aw$}  {}{:>gap$}\n",
+                                "",
+                                first_label,
+                                last_label,
+                                aw = axis_width,
+                                gap = gap));
+    } else {
+        chart.push_str(&format!("{:>aw$}  {}\n", "", first_label, aw = axis_width));
+    }
+    chart
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter::repeat;
+
+    use super::{to_sparkline_chart, to_bar_chart, to_line_chart};
+
+    fn to_points(amounts: &[f64]) -> Vec<(String, f64)> {
+        amounts.iter()
+               .enumerate()
+               .map(|(idx, amount)| (format!("{:02}-2016", idx + 1), *amount))
+               .collect()
+    }
+
+    #[test]
+    fn can_fit_sparkline_within_width() {
+        let points = to_points(&[1f64, 2f64, 3f64, 4f64, 5f64, 6f64, 7f64, 8f64, 9f64, 10f64]);
+
+        assert_eq!("coffee  ▁█  min 1.00 max 10.00\n",
+                   to_sparkline_chart("coffee", &points, 30));
+        assert_eq!("coffee  ▁▂▃▄▅▅▆▇█  min 1.00 max 10.00\n",
+                   to_sparkline_chart("coffee", &points, 37));
+        assert_eq!("coffee  ▁▂▃▃▄▅▆▆▇█  min 1.00 max 10.00\n",
+                   to_sparkline_chart("coffee", &points, 80));
+    }
+
+    #[test]
+    fn can_represent_sparkline_of_one_point_or_none() {
+        assert_eq!("coffee  ▁  min 2.70 max 2.70\n",
+                   to_sparkline_chart("coffee", &to_points(&[2.7f64]), 80));
+        assert_eq!("coffee  ▁▁▁  min 2.70 max 2.70\n",
+                   to_sparkline_chart("coffee", &to_points(&[2.7f64, 2.7f64, 2.7f64]), 80));
+        assert_eq!("", to_sparkline_chart("coffee", &vec![], 80));
+    }
+
+    #[test]
+    fn can_represent_bar_chart_in_eighths() {
+        let points = vec![("a".to_string(), 8f64),
+                          ("b".to_string(), 1.5f64),
+                          ("c".to_string(), -4f64),
+                          ("d".to_string(), 0f64)];
+
+        assert_eq!("a  ████████   8.00\n\
+                    b  █▌         1.50\n\
+                    c  ░░░░      -4.00\n\
+                    d             0.00\n",
+                   to_bar_chart(&points, 18));
+    }
+
+    #[test]
+    fn can_represent_bar_chart_of_zeroes() {
+        let points = vec![("a".to_string(), 0f64)];
+
+        assert_eq!("a     0.00\n", to_bar_chart(&points, 10));
+    }
+
+    #[test]
+    fn can_interpolate_line_chart_between_points() {
+        let points = vec![("a".to_string(), 0f64), ("b".to_string(), 9f64)];
+
+        assert_eq!("9.00 |         *
+     |        /
+     |       /
+     |      /
+     |     /
+     |    /
+     |   /
+     |  /
+     | /
+0.00 |*
+     +----------
+      a        b
+",
+                   to_line_chart(&points, 16));
+    }
+
+    #[test]
+    fn can_label_line_chart_axis() {
+        let points = vec![("01-2016".to_string(), 1500f64),
+                          ("02-2016".to_string(), -2.7f64),
+                          ("03-2016".to_string(), 1500f64)];
+        let chart = to_line_chart(&points, 40);
+        let lines = chart.lines().collect::<Vec<&str>>();
+
+        assert_eq!(12, lines.len());
+        assert!(lines[0].starts_with("1500.00 |*"));
+        assert!(lines[0].ends_with("*"));
+        assert_eq!("  -2.70 |               *", lines[9]);
+        assert_eq!(format!("{:>7} +{}", "", repeat('-').take(31).collect::<String>()),
+                   lines[10]);
+        assert_eq!(format!("{:>7}  01-2016{:>24}", "", "03-2016"), lines[11]);
+    }
+
+    #[test]
+    fn can_represent_line_chart_of_one_point_or_a_constant() {
+        assert_eq!("5.00 |
+     |
+     |
+     |
+     |
+     |
+     |
+     |
+     |
+5.00 |*-
+     +--
+      01-2016
+",
+                   to_line_chart(&to_points(&[5f64]), 2));
+
+        let chart = to_line_chart(&to_points(&[5f64, 5f64, 5f64]), 15);
+        assert!(chart.contains("5.00 |*---*---*\n"));
+        assert_eq!("", to_line_chart(&vec![], 80));
+    }
+}
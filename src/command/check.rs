@@ -0,0 +1,131 @@
+use std::str::FromStr; // Use of #from_str.
+
+use config::Config;
+use api::Source;
+use api::inform::{Money, GetAccountBalance, GetOutgoing, GetIncoming, ApiServiceResult};
+use check::{Rule, Severity, Measure, parse_rule};
+use locale::get_display_format;
+
+use chrono::{Datelike, UTC};
+
+// Exit codes follow the conventions of Nagios plugins.
+const OK: i32 = 0;
+const WARNING: i32 = 1;
+const CRITICAL: i32 = 2;
+const UNKNOWN: i32 = 3;
+
+fn to_status(exit_code: i32) -> &'static str {
+    match exit_code {
+        OK => "OK",
+        WARNING => "WARNING",
+        CRITICAL => "CRITICAL",
+        _ => "UNKNOWN",
+    }
+}
+
+fn get_money_for_rule(teller: &Source, config: &Config, rule: &Rule) -> ApiServiceResult<Money> {
+    let account_id = config.get_account_id(&rule.account);
+    let for_month = UTC::now().date();
+    match rule.measure {
+        Measure::Balance => teller.get_account_balance(&account_id),
+        Measure::Outgoing => teller.get_outgoing(&account_id, &for_month),
+        Measure::Incoming => teller.get_incoming(&account_id, &for_month),
+    }
+}
+
+fn represent_perfdata(rule: &Rule, money: &Money) -> String {
+    let (warning, critical) = match rule.severity {
+        Severity::Warning => (rule.get_perfdata_range(), "".to_string()),
+        Severity::Critical => ("".to_string(), rule.get_perfdata_range()),
+    };
+    format!("{}={};{};{}", rule.get_label(), money.amount, warning, critical)
+}
+
+fn represent_check(exit_code: i32, summary: &str, perfdata: &Vec<String>, show_perfdata: &bool) {
+    if *show_perfdata && !perfdata.is_empty() {
+        println!("TELLER {} - {} | {}", to_status(exit_code), summary, perfdata.join(" "))
+    } else {
+        println!("TELLER {} - {}", to_status(exit_code), summary)
+    }
+}
+
+pub fn check_command(teller: &Source,
+                     config: &Config,
+                     rule_texts: &Vec<String>,
+                     show_perfdata: &bool)
+                     -> i32 {
+    info!("Calling the check command");
+    let rule_texts = if rule_texts.is_empty() {
+        config.checks.to_owned().unwrap_or(vec![])
+    } else {
+        rule_texts.to_owned()
+    };
+    if rule_texts.is_empty() {
+        represent_check(UNKNOWN, "No rules were given or configured", &vec![], &show_perfdata);
+        return UNKNOWN;
+    }
+
+    let day_of_month = UTC::today().day();
+    let display_format = get_display_format();
+
+    let mut exit_code = OK;
+    let mut violations = vec![];
+    let mut perfdata = vec![];
+    for rule_text in rule_texts.iter() {
+        let rule = match parse_rule(&rule_text) {
+            Ok(rule) => rule,
+            Err(e) => {
+                represent_check(UNKNOWN, &e, &vec![], &show_perfdata);
+                return UNKNOWN;
+            }
+        };
+        let money = match get_money_for_rule(teller, &config, &rule) {
+            Ok(money) => money,
+            Err(e) => {
+                represent_check(UNKNOWN,
+                                &format!("Unable to check `{}`: {}", rule.text, e),
+                                &vec![],
+                                &show_perfdata);
+                return UNKNOWN;
+            }
+        };
+        let amount = match f64::from_str(&money.amount) {
+            Ok(amount) => amount,
+            Err(_) => {
+                represent_check(UNKNOWN,
+                                &format!("Unable to check `{}`: {} is not an amount",
+                                         rule.text,
+                                         money.amount),
+                                &vec![],
+                                &show_perfdata);
+                return UNKNOWN;
+            }
+        };
+
+        if rule.is_violated(amount, day_of_month) {
+            let rule_exit_code = match rule.severity {
+                Severity::Warning => WARNING,
+                Severity::Critical => CRITICAL,
+            };
+            if rule_exit_code > exit_code {
+                exit_code = rule_exit_code;
+            }
+            violations.push(format!("{} ({})",
+                                    rule.text,
+                                    money.get_balance_for_display(&display_format, &false)));
+        }
+        perfdata.push(represent_perfdata(&rule, &money));
+    }
+
+    let summary = if violations.is_empty() {
+        match rule_texts.len() {
+            1 => "1 rule passed".to_string(),
+            count => format!("{} rules passed", count),
+        }
+    } else {
+        violations.join(", ")
+    };
+    represent_check(exit_code, &summary, &perfdata, &show_perfdata);
+
+    exit_code
+}
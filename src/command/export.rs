@@ -0,0 +1,151 @@
+use std::path::PathBuf;
+
+use config::Config;
+use config::atomic_write::replace_atomically;
+use api::Source;
+use api::inform::ApiServiceResult;
+use api::source::imported::with_stable_ids;
+use cli::arg_types::{Timeframe, AccountType};
+use export::{ExportFormat, TextFormat, ExportedAccount, to_export_format, to_asset_account,
+             represent_journal, represent_ofx, represent_qif, write_sqlite};
+use redact::redact_output;
+
+use command::timeframe_to_date_range;
+
+fn to_alias(account: &AccountType) -> String {
+    match *account {
+        AccountType::Current | AccountType::None => "current".to_string(),
+        AccountType::Savings => "savings".to_string(),
+        AccountType::Business => "business".to_string(),
+        AccountType::Unknown(ref alias) => alias.to_owned(),
+    }
+}
+
+fn get_exported_account(teller: &Source,
+                        alias: &str,
+                        account_id: &str,
+                        timeframe: &Timeframe)
+                        -> ApiServiceResult<ExportedAccount> {
+    let (from, to) = timeframe_to_date_range(&timeframe);
+    let account = try!(teller.get_account(&account_id));
+    // Statements read with --input may not have ids, which every format
+    // relies upon to recognise what was exported before.
+    let transactions = with_stable_ids(try!(teller.get_transactions(&account_id, &from, &to)));
+    Ok(ExportedAccount {
+        alias: alias.to_string(),
+        account: account,
+        transactions: transactions,
+    })
+}
+
+fn get_export(teller: &Source,
+              config: &Config,
+              account: &AccountType,
+              timeframe: &Timeframe,
+              text_format: &TextFormat)
+              -> ApiServiceResult<String> {
+    let alias = to_alias(&account);
+    let ExportedAccount { account: teller_account, transactions, .. } =
+        try!(get_exported_account(teller, &alias, &config.get_account_id(&account), &timeframe));
+    let (from, to) = timeframe_to_date_range(&timeframe);
+
+    Ok(match *text_format {
+        TextFormat::Journal(ref journal_format) => {
+            try!(represent_journal(&journal_format,
+                                   &to_asset_account(&alias),
+                                   &teller_account.currency,
+                                   &transactions,
+                                   &config))
+        }
+        TextFormat::Ofx => represent_ofx(&teller_account, &account, &transactions, &from, &to),
+        TextFormat::Qif => represent_qif(&transactions),
+    })
+}
+
+// A database holds every account with an alias unless one is asked for.
+fn export_sqlite(teller: &Source,
+                 config: &Config,
+                 account: &AccountType,
+                 timeframe: &Timeframe,
+                 out: &str)
+                 -> i32 {
+    let account_aliases = match (account, config.get_account_aliases()) {
+        (&AccountType::None, ref account_aliases) if !account_aliases.is_empty() => {
+            account_aliases.clone()
+        }
+        _ => vec![(to_alias(&account), config.get_account_id(&account))],
+    };
+
+    let mut exported_accounts = vec![];
+    for (alias, account_id) in account_aliases {
+        match get_exported_account(teller, &alias, &account_id, &timeframe) {
+            Ok(exported_account) => exported_accounts.push(exported_account),
+            Err(e) => {
+                error!("Unable to export transactions: {}", e);
+                return 1;
+            }
+        }
+    }
+
+    match write_sqlite(&out, &config, &exported_accounts, &redact_output) {
+        Ok(_) => 0,
+        Err(e) => {
+            error!("Unable to write the export to {}: {}", out, e);
+            1
+        }
+    }
+}
+
+// An export holds account numbers and transactions, so it is kept private,
+// and one which fails halfway does not replace the previous export.
+fn write_export(out: &str, export: &str) -> Result<(), ::std::io::Error> {
+    replace_atomically(&PathBuf::from(out), export.as_bytes())
+}
+
+pub fn export_command(teller: &Source,
+                      config: &Config,
+                      account: &AccountType,
+                      timeframe: &Timeframe,
+                      format: &str,
+                      out: &str)
+                      -> i32 {
+    info!("Calling the export command");
+    let text_format = match to_export_format(&format) {
+        Some(ExportFormat::Text(text_format)) => text_format,
+        Some(ExportFormat::Sqlite) => {
+            if out.is_empty() {
+                error!("Unable to export to sqlite without an --out path for the database");
+                return 1;
+            }
+            return export_sqlite(teller, &config, &account, &timeframe, &out);
+        }
+        None => {
+            error!("Unable to export to `{}` (expected a --format of ledger, hledger, beancount, \
+                    ofx, qif or sqlite)",
+                   format);
+            return 1;
+        }
+    };
+
+    match get_export(teller, &config, &account, &timeframe, &text_format) {
+        Ok(export) => {
+            let export = redact_output(&export);
+            if out.is_empty() {
+                print!("{}", export);
+                0
+            } else {
+                match write_export(&out, &export) {
+                    Ok(_) => 0,
+                    Err(e) => {
+                        error!("Unable to write the export to {}: {}", out, e);
+                        1
+                    }
+                }
+            }
+        }
+        Err(err) => {
+            error!("Unable to export transactions: {}", err);
+            1
+        }
+    }
+}
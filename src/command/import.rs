@@ -0,0 +1,147 @@
+use std::path::PathBuf;
+
+use config::{Config, get_config, get_config_path, get_profile_name, update_config};
+use api::source::get_imports_dir;
+use api::source::csv::CsvColumns;
+use api::source::file::read_input;
+use api::source::imported::{import_statement, is_imported_account_id, new_imported_account_id,
+                            remove_imported_account};
+use api::source::statement::{ParseOptions, parse_statement, to_statement_format};
+
+fn to_parse_options(format: &str,
+                    columns: &str,
+                    date_format: &str,
+                    decimal_mark: &str)
+                    -> Result<ParseOptions, String> {
+    let format = match format {
+        "" => None,
+        format => {
+            Some(try!(to_statement_format(&format).ok_or(format!("Unknown format {} (expected \
+                                                                  ofx, qif, csv or json)",
+                                                                 format))))
+        }
+    };
+    Ok(ParseOptions {
+        format: format,
+        columns: try!(CsvColumns::parse(&columns)),
+        date_format: if date_format.is_empty() {
+            None
+        } else {
+            Some(date_format.to_string())
+        },
+        decimal_mark: match decimal_mark {
+            "" => None,
+            "." => Some('.'),
+            "," => Some(','),
+            _ => return Err(format!("Unknown decimal mark {} (expected . or ,)", decimal_mark)),
+        },
+    })
+}
+
+// An alias which is new is given to a new imported account, while one which
+// already points at an imported account has the statement added to it.
+fn get_imported_account_id(profile: &Config, alias: &str) -> Result<(String, bool), String> {
+    match profile.get_account_aliases().into_iter().find(|&(ref a, _)| a == alias) {
+        Some((_, ref account_id)) if is_imported_account_id(&account_id) => {
+            Ok((account_id.to_owned(), false))
+        }
+        Some(_) => {
+            Err(format!("{} is the alias of an account at Teller, so statements can not be \
+                         imported into it",
+                        alias))
+        }
+        None => Ok((new_imported_account_id(), true)),
+    }
+}
+
+fn save_account_alias(config_file_path: &PathBuf,
+                      profile_name: &str,
+                      alias: &str,
+                      account_id: &str)
+                      -> bool {
+    let saved = update_config(&config_file_path, |config| {
+        let mut config = config.unwrap_or(Config::new_with_auth_token_only(""));
+        let mut profile = config.get_profile(&profile_name)
+                                .unwrap_or(Config::new_with_auth_token_only(""));
+        profile.set_account_alias(&alias, &account_id);
+        config.set_profile(&profile_name, profile);
+        Ok(config)
+    });
+    match saved {
+        Ok(_) => true,
+        Err(e) => {
+            error!("Unable to write the config to {}: {}",
+                   config_file_path.display(),
+                   e);
+            false
+        }
+    }
+}
+
+pub fn import_command(file: &str,
+                      alias: &str,
+                      format: &str,
+                      columns: &str,
+                      date_format: &str,
+                      decimal_mark: &str,
+                      config_flag: &str,
+                      profile_flag: &str)
+                      -> i32 {
+    info!("Calling the import command");
+
+    if alias.is_empty() {
+        error!("Unable to import a statement without an --account to import it into");
+        return 1;
+    }
+    let parse_options = match to_parse_options(&format, &columns, &date_format, &decimal_mark) {
+        Ok(parse_options) => parse_options,
+        Err(e) => {
+            error!("Unable to import {}: {}", file, e);
+            return 1;
+        }
+    };
+    let statement = match read_input(&file).and_then(|text| parse_statement(&text, &parse_options)) {
+        Ok(statement) => statement,
+        Err(e) => {
+            error!("Unable to import {}: {}", file, e);
+            return 1;
+        }
+    };
+
+    // Importing needs no `auth_token`, so a config is only read to find the
+    // alias and is created if there is not one yet.
+    let config_file_path = get_config_path(&config_flag);
+    let profile_name = get_profile_name(&profile_flag);
+    let profile = get_config(&config_file_path)
+                      .and_then(|config| config.get_profile(&profile_name))
+                      .unwrap_or(Config::new_with_auth_token_only(""));
+    let (account_id, is_new_account) = match get_imported_account_id(&profile, &alias) {
+        Ok(account) => account,
+        Err(e) => {
+            error!("Unable to import {}: {}", file, e);
+            return 1;
+        }
+    };
+
+    let name = statement.name.clone().unwrap_or(alias.to_string());
+    let summary = match import_statement(&get_imports_dir(), &account_id, &name, statement) {
+        Ok(summary) => summary,
+        Err(e) => {
+            error!("Unable to import {}: {}", file, e);
+            return 1;
+        }
+    };
+    if is_new_account && !save_account_alias(&config_file_path, &profile_name, &alias, &account_id) {
+        // Nothing could find a new account without its alias, so it is removed again.
+        if let Err(e) = remove_imported_account(&get_imports_dir(), &account_id) {
+            error!("Unable to remove the import of {}: {}", file, e);
+        }
+        return 1;
+    }
+
+    println!("Imported {} new transactions into {} ({} already imported)",
+             summary.added,
+             alias,
+             summary.skipped);
+    0
+}
@@ -1,102 +1,334 @@
 use std::path::PathBuf;
-use config::{Config, get_config_path, get_config_file_to_write, write_config};
-use inquirer::{Question, Answer, ask_question, ask_questions};
+use config::{Config, DEFAULT_PROFILE, get_config_path, get_profile_name, update_config};
+use config::error::ConfigError;
+use config::auth_token::{ask_passphrase, encrypt_auth_token, get_auth_token_from_env,
+                         get_passphrase_from_env};
+use config::init_answers::{InitAnswers, get_init_answers};
+use inquirer::{Question, ask_question, ask_questions};
 
-use api::TellerClient;
+use api::{Account, TellerClient};
 use command::representations::represent_list_accounts;
 
-pub fn configure_cli(config_file_path: &PathBuf) -> Option<Config> {
-    match ask_questions_for_config() {
-        None => None,
-        Some(config) => {
-            match get_config_file_to_write(&config_file_path) {
-                Ok(mut config_file) => {
-                    let _ = write_config(&mut config_file, &config);
-                    Some(config)
+// The other profiles within an existing config are kept, and an existing
+// profile of the same name is only replaced when `replace` is set. Both are
+// checked while the config is locked, so that two `init` commands cannot
+// overwrite each other's profiles.
+fn save_profile(config_file_path: &PathBuf,
+                profile_name: &str,
+                profile: &Config,
+                replace: bool)
+                -> bool {
+    let saved = update_config(&config_file_path, |config| {
+        let has_profile = config.as_ref()
+                                .and_then(|config| config.get_profile(&profile_name))
+                                .is_some();
+        if has_profile && !replace {
+            return Err(ConfigError::InitError(format!("The {} profile already exists within \
+                                                       {}. Pass --yes to replace it.",
+                                                      profile_name,
+                                                      config_file_path.display())));
+        }
+        let mut config = config.unwrap_or(Config::new_with_auth_token_only(""));
+        config.set_profile(&profile_name, profile.clone());
+        Ok(config)
+    });
+    match saved {
+        Ok(_) => true,
+        Err(ConfigError::InitError(message)) => {
+            error!("{}", message);
+            false
+        }
+        Err(e) => {
+            error!("Unable to write the config to {}: {}",
+                   config_file_path.display(),
+                   e);
+            false
+        }
+    }
+}
+
+pub fn configure_cli(config_file_path: &PathBuf, profile_name: &str) -> Option<Config> {
+    match ask_questions_for_config(&config_file_path, &profile_name) {
+        Some(ref profile) if !save_profile(&config_file_path, &profile_name, &profile, true) => {
+            None
+        }
+        profile => profile,
+    }
+}
+
+// Sets up a profile without asking anything, so that provisioning scripts can
+// run `teller init` without a TTY. The `auth_token` is checked against the API
+// and an existing profile is only replaced with `--yes`.
+fn configure_cli_from_answers(config_file_path: &PathBuf,
+                              profile_name: &str,
+                              init_answers: &InitAnswers,
+                              yes: bool)
+                              -> i32 {
+    let accounts = {
+        let auth_token = init_answers.auth_token.as_ref().map_or("", |auth_token| &auth_token[..]);
+        if auth_token.is_empty() {
+            error!("An `auth_token` is needed from --auth-token, --from-file or TELLER_AUTH_TOKEN");
+            return 1;
+        }
+        let teller = TellerClient::new(auth_token.to_owned());
+        match teller.get_accounts() {
+            Ok(accounts) => accounts,
+            Err(e) => {
+                error!("Unable to validate the `auth_token`: {}", e);
+                return 1;
+            }
+        }
+    };
+
+    let mut profile = match init_answers.to_config(&accounts) {
+        Ok(profile) => profile,
+        Err(e) => {
+            error!("Unable to set the aliases: {}", e);
+            return 1;
+        }
+    };
+
+    if let Some(passphrase) = get_passphrase_from_env() {
+        match encrypt_auth_token(&profile.auth_token, &passphrase) {
+            Ok(encrypted_auth_token) => profile.encrypted_auth_token = Some(encrypted_auth_token),
+            Err(e) => {
+                error!("Unable to encrypt the `auth_token`: {}", e);
+                return 1;
+            }
+        }
+    }
+
+    if save_profile(&config_file_path, &profile_name, &profile, yes) {
+        println!("Saved the {} profile within {}.",
+                 profile_name,
+                 config_file_path.display());
+        0
+    } else {
+        1
+    }
+}
+
+// A mistyped `auth_token` is asked for again a few times before giving up.
+const MAX_AUTH_TOKEN_ATTEMPTS: u32 = 3;
+
+// The `auth_token` is checked by listing the accounts it can see before
+// anything else is asked. Once every attempt has failed the last error is
+// returned.
+fn ask_for_auth_token() -> Result<(String, Vec<Account>), String> {
+    let question = Question::password("auth_token", "What is your `auth_token` on teller.io?")
+                       .with_validator(|auth_token| {
+                           if auth_token.is_empty() {
+                               Err("An `auth_token` needs to be entered to initialise the \
+                                    config."
+                                       .to_string())
+                           } else if auth_token.contains(char::is_whitespace) {
+                               Err("An `auth_token` cannot contain spaces.".to_string())
+                           } else {
+                               Ok(())
+                           }
+                       });
+
+    let mut last_error = None;
+    for attempt in 1..(MAX_AUTH_TOKEN_ATTEMPTS + 1) {
+        let auth_token = match ask_question(&question) {
+            Some(answer) => answer.value,
+            None => break,
+        };
+        match TellerClient::new(auth_token.to_owned()).get_accounts() {
+            Ok(accounts) => return Ok((auth_token, accounts)),
+            Err(e) => {
+                if attempt < MAX_AUTH_TOKEN_ATTEMPTS {
+                    error!("Unable to list accounts with this `auth_token`: {}", e);
                 }
-                Err(e) => panic!("ERROR: opening file to write: {}", e),
+                last_error = Some(e);
             }
         }
     }
+    Err(match last_error {
+        Some(e) => format!("Unable to list accounts with this `auth_token`: {}", e),
+        None => "An `auth_token` needs to be entered to initialise the config.".to_string(),
+    })
 }
 
-fn ask_questions_for_config() -> Option<Config> {
-    let get_auth_token_question = Question::new("auth_token",
-                                                "What is your `auth_token` on teller.io?");
+fn mask_auth_token(auth_token: &str) -> String {
+    format!("{}...", auth_token.chars().take(4).collect::<String>())
+}
 
-    let auth_token_answer = match ask_question(&get_auth_token_question) {
-        Some(auth_token_answer) => auth_token_answer,
-        None => {
-            error!("An `auth_token` needs to be entered to initialise the config.");
+fn ask_questions_for_config(config_file_path: &PathBuf, profile_name: &str) -> Option<Config> {
+    let (auth_token, accounts) = match ask_for_auth_token() {
+        Ok(auth_token_and_accounts) => auth_token_and_accounts,
+        Err(message) => {
+            error!("{}", message);
             return None; // Exit the whole function returning None.
         }
     };
 
-    let mut config = Config::new_with_auth_token_only(auth_token_answer.value);
+    let mut config = Config::new_with_auth_token_only(auth_token);
 
     print!("\n");
-    let accounts = {
-        let teller = TellerClient::new(&config.auth_token);
-        match teller.get_accounts() {
-            Ok(accounts) => accounts,
-            Err(e) => panic!("Unable to list accounts: {}", e),
-        }
-    };
-    represent_list_accounts(&accounts, &config);
-
-    println!("Please type the row (e.g. 3) of the account you wish to place against an alias and \
+    println!("Please type the row (e.g. 2) of the account you wish to place against an alias and \
               press <enter> to set this in the config. Leave empty if irrelevant.");
     print!("\n");
 
+    let choices = accounts.iter()
+                          .map(|account| {
+                              format!("{} {} ({} {})",
+                                      account.name,
+                                      account.account_number,
+                                      account.balance,
+                                      account.currency)
+                          })
+                          .collect::<Vec<String>>();
     let questions = vec![
-        Question::new(
+        Question::select(
             "current",
             "Which is your current account?",
+            choices.clone(),
         ),
-        Question::new(
+        Question::select(
             "savings",
             "Which is your savings account?",
+            choices.clone(),
         ),
-        Question::new(
+        Question::select(
             "business",
             "Which is your business account?",
+            choices.clone(),
         ),
     ];
 
-    let non_empty_answers = ask_questions(&questions);
-    let mut fa_iter = non_empty_answers.iter();
-
-    let to_account_id = |answer: &Answer| {
-        let row_number: u32 = answer.value
-                                    .parse()
-                                    .expect(&format!("ERROR: {:?} did not contain a number",
-                                                     answer));
-        accounts[(row_number - 1) as usize].id.to_owned()
-    };
+    let answers = ask_questions(&questions);
+    // A select which was left empty, or not answered within its attempts,
+    // leaves its alias unset.
+    let unset_aliases = questions.iter()
+                                 .map(|question| &question.name[..])
+                                 .filter(|name| !answers.iter().any(|answer| answer.name == *name))
+                                 .collect::<Vec<&str>>();
+    for answer in answers {
+        let account_id = match choices.iter().position(|choice| *choice == answer.value) {
+            Some(idx) => accounts[idx].id.to_owned(),
+            None => continue,
+        };
+        match &answer.name[..] {
+            "current" => config.current = account_id,
+            "savings" => config.savings = account_id,
+            _ => config.business = account_id,
+        }
+    }
 
-    match fa_iter.find(|&answer| answer.name == "current").map(&to_account_id) {
-        None => (),
-        Some(account_id) => config.current = account_id,
-    };
-    match fa_iter.find(|&answer| answer.name == "savings").map(&to_account_id) {
-        None => (),
-        Some(account_id) => config.savings = account_id,
-    };
-    match fa_iter.find(|&answer| answer.name == "business").map(&to_account_id) {
-        None => (),
-        Some(account_id) => config.business = account_id,
-    };
+    print!("\n");
+    println!("Your `auth_token` can be encrypted with a passphrase that is asked for whenever a \
+              command runs (or read from TELLER_PASSPHRASE). Leave empty to store it as plain text.");
+    loop {
+        let passphrase = match ask_passphrase("Passphrase: ") {
+            Ok(passphrase) => passphrase,
+            Err(e) => {
+                error!("Unable to read passphrase: {}", e);
+                return None;
+            }
+        };
+        if passphrase.is_empty() {
+            break;
+        }
+        match ask_passphrase("Confirm passphrase: ") {
+            Ok(ref confirmation) if *confirmation == passphrase => (),
+            Ok(_) => {
+                println!("The passphrases did not match.");
+                continue;
+            }
+            Err(e) => {
+                error!("Unable to read passphrase: {}", e);
+                return None;
+            }
+        }
+        match encrypt_auth_token(&config.auth_token, &passphrase) {
+            Ok(encrypted_auth_token) => config.encrypted_auth_token = Some(encrypted_auth_token),
+            Err(e) => {
+                error!("Unable to encrypt the `auth_token`: {}", e);
+                return None;
+            }
+        }
+        break;
+    }
 
-    Some(config)
+    print!("\n");
+    println!("The {} profile will be saved within {}:",
+             profile_name,
+             config_file_path.display());
+    print!("\n");
+    println!("auth_token: {} ({})",
+             mask_auth_token(&config.auth_token),
+             if config.encrypted_auth_token.is_some() {
+                 "encrypted with a passphrase"
+             } else {
+                 "stored as plain text"
+             });
+    if !unset_aliases.is_empty() {
+        println!("No account was chosen for: {}", unset_aliases.join(", "));
+    }
+    represent_list_accounts(&accounts, &config);
+    print!("\n");
+    let confirm_question = Question::confirm("confirm", "Save this config?", true);
+    match ask_question(&confirm_question) {
+        Some(ref answer) if !answer.is_yes() => {
+            println!("Nothing was saved.");
+            None
+        }
+        _ => Some(config),
+    }
 }
 
-pub fn initialise_command() -> i32 {
+pub fn initialise_command(config_flag: &str,
+                          profile_flag: &str,
+                          auth_token_flag: &str,
+                          alias_flags: &Vec<String>,
+                          from_file_flag: &str,
+                          yes: bool)
+                          -> i32 {
     info!("Calling the initialise command");
-    let config_file_path = get_config_path();
-    println!("To create the config ({}) we need to find out your `auth_token` and assign aliases \
-              to some common bank accounts.",
-             config_file_path.display());
+    let config_file_path = get_config_path(&config_flag);
+    let profile_name = get_profile_name(&profile_flag);
+
+    let mut init_answers = if from_file_flag.is_empty() {
+        InitAnswers::default()
+    } else {
+        match get_init_answers(&PathBuf::from(from_file_flag)) {
+            Ok(init_answers) => init_answers,
+            Err(e) => {
+                error!("Unable to read the answers within {}: {}", from_file_flag, e);
+                return 1;
+            }
+        }
+    };
+    if !auth_token_flag.is_empty() {
+        init_answers.auth_token = Some(auth_token_flag.to_string());
+    }
+    for alias_flag in alias_flags {
+        if let Err(e) = init_answers.set_alias(&alias_flag) {
+            error!("Unable to read --alias: {}", e);
+            return 1;
+        }
+    }
+    if yes || !init_answers.is_empty() {
+        if init_answers.auth_token.is_none() {
+            init_answers.auth_token = get_auth_token_from_env();
+        }
+        return configure_cli_from_answers(&config_file_path, &profile_name, &init_answers, yes);
+    }
+
+    if profile_name == DEFAULT_PROFILE {
+        println!("To create the config ({}) we need to find out your `auth_token` and assign \
+                  aliases to some common bank accounts.",
+                 config_file_path.display());
+    } else {
+        println!("To create the {} profile within the config ({}) we need to find out its \
+                  `auth_token` and assign aliases to some common bank accounts.",
+                 profile_name,
+                 config_file_path.display());
+    }
     print!("\n");
-    configure_cli(&config_file_path);
-    0
+    match configure_cli(&config_file_path, &profile_name) {
+        Some(_) => 0,
+        None => 1,
+    }
 }
@@ -1,9 +1,9 @@
 use config::Config;
-use api::TellerClient;
+use api::Source;
 
 use command::representations::represent_list_accounts;
 
-pub fn list_accounts_command(teller: &TellerClient,
+pub fn list_accounts_command(teller: &Source,
                              config: &Config)
                              -> i32 {
     info!("Calling the list accounts command");
@@ -0,0 +1,74 @@
+use config::Config;
+use api::Source;
+use api::inform::{Anomaly, AnomalyKind, GetAnomalies};
+use cli::arg_types::{AccountType, Timeframe};
+use locale::{format_amount, format_date, format_month};
+
+use command::representations::to_aligned_table;
+
+use command::timeframe_to_date_range;
+
+fn describe_anomaly_kind(kind: &AnomalyKind) -> &'static str {
+    match *kind {
+        AnomalyKind::UnusualAmount => "unusual amount",
+        AnomalyKind::NewCounterparty => "new counterparty",
+        AnomalyKind::DuplicateCharge => "duplicate charge",
+        AnomalyKind::UnusualMonth => "unusual month",
+    }
+}
+
+// An unusual month is dated by its month rather than by a day.
+fn format_anomaly_date(anomaly: &Anomaly) -> String {
+    match anomaly.kind {
+        AnomalyKind::UnusualMonth => format_month(&anomaly.date),
+        _ => format_date(&anomaly.date),
+    }
+}
+
+fn represent_list_anomalies(anomalies: &Vec<Anomaly>, currency: &str) {
+    let mut anomalies_table = String::new();
+
+    anomalies_table.push_str(&format!("row\tdate\tanomaly\tcounterparty\tamount ({})\treason\n",
+                                      currency));
+    for (idx, anomaly) in anomalies.iter().enumerate() {
+        let row_number = (idx + 1) as u32;
+        let new_anomaly_row = format!("{}\t{}\t{}\t{}\t{}\t{}\n",
+                                      row_number,
+                                      format_anomaly_date(&anomaly),
+                                      describe_anomaly_kind(&anomaly.kind),
+                                      anomaly.counterparty,
+                                      format_amount(&anomaly.amount),
+                                      anomaly.reason);
+        anomalies_table = anomalies_table + &new_anomaly_row;
+    }
+
+    let anomalies_str = to_aligned_table(&anomalies_table);
+
+    print!("{}", anomalies_str)
+}
+
+pub fn list_anomalies_command(teller: &Source,
+                              config: &Config,
+                              account: &AccountType,
+                              timeframe: &Timeframe,
+                              threshold: &f64,
+                              within: &i64)
+                              -> i32 {
+    info!("Calling the list anomalies command");
+    let account_id = config.get_account_id(&account);
+    let (from, to) = timeframe_to_date_range(&timeframe);
+    teller.get_anomalies(&account_id, &threshold, &within, &from, &to)
+          .map(|anomalies_with_currency| {
+              // Nothing is printed when there are no anomalies, so that
+              // cron only sends mail when something needs looking at.
+              if !anomalies_with_currency.anomalies.is_empty() {
+                  represent_list_anomalies(&anomalies_with_currency.anomalies,
+                                           &anomalies_with_currency.currency);
+              }
+              0
+          })
+          .unwrap_or_else(|err| {
+              error!("Unable to list anomalies: {}", err);
+              1
+          })
+}
@@ -1,5 +1,5 @@
 use config::Config;
-use api::TellerClient;
+use api::Source;
 use api::inform::{Balances, GetBalances};
 use cli::arg_types::{AccountType, OutputFormat, Interval, Timeframe};
 
@@ -11,7 +11,7 @@ fn represent_list_balances(hac: &Balances, output: &OutputFormat) {
     represent_list_amounts("balance", &hac, &output)
 }
 
-pub fn list_balances_command(teller: &TellerClient,
+pub fn list_balances_command(teller: &Source,
                              config: &Config,
                              account: &AccountType,
                              interval: &Interval,
@@ -1,25 +1,25 @@
 use config::Config;
-use api::TellerClient;
+use api::Source;
 use api::inform::GetCounterparties;
-use cli::arg_types::{AccountType, Timeframe};
+use cli::arg_types::{AccountType, OutputFormat, Timeframe};
+use locale::format_amount;
 
-use command::representations::to_aligned_table;
+use command::charts::{to_sparkline_chart, to_bar_chart};
+use command::representations::{to_aligned_table, to_chart_points};
+use command::terminal::get_terminal_width;
 
 use command::timeframe_to_date_range;
 
-fn represent_list_counterparties(counterparties: &Vec<(String, String)>,
-                                 currency: &str,
-                                 count: &i64) {
+fn represent_counterparties_table(counterparties: &Vec<(String, String)>, currency: &str) {
     let mut counterparties_table = String::new();
 
     counterparties_table.push_str(&format!("row\tcounterparty\tamount ({})\n", currency));
-    let skip_n = counterparties.len() - (*count as usize);
-    for (idx, counterparty) in counterparties.iter().skip(skip_n).enumerate() {
+    for (idx, counterparty) in counterparties.iter().enumerate() {
         let row_number = (idx + 1) as u32;
         let new_counterparty_row = format!("{}\t{}\t{}\n",
                                            row_number,
                                            counterparty.0,
-                                           counterparty.1);
+                                           format_amount(&counterparty.1));
         counterparties_table = counterparties_table + &new_counterparty_row;
     }
 
@@ -28,11 +28,43 @@ fn represent_list_counterparties(counterparties: &Vec<(String, String)>,
     print!("{}", counterparties_str)
 }
 
-pub fn list_counterparties_command(teller: &TellerClient,
+fn represent_list_counterparties(counterparties: &Vec<(String, String)>,
+                                 currency: &str,
+                                 count: &i64,
+                                 output: &OutputFormat) {
+    let skip_n = counterparties.len().saturating_sub(*count as usize);
+    let top_counterparties = counterparties.iter().skip(skip_n).cloned().collect();
+    match *output {
+        OutputFormat::Standard => represent_counterparties_table(&top_counterparties, &currency),
+        OutputFormat::Spark => {
+            let amounts_str = top_counterparties.iter()
+                                                .map(|c| c.1.to_owned())
+                                                .collect::<Vec<String>>()
+                                                .join(" ");
+            println!("{}", amounts_str)
+        }
+        OutputFormat::Sparkline => {
+            let label = format!("outgoing ({})", currency);
+            print!("{}",
+                   to_sparkline_chart(&label,
+                                      &to_chart_points(&top_counterparties),
+                                      get_terminal_width()))
+        }
+        // Counterparties are not a series over time, so they have no line.
+        OutputFormat::Bars | OutputFormat::Line => {
+            println!("outgoing ({})", currency);
+            print!("{}",
+                   to_bar_chart(&to_chart_points(&top_counterparties), get_terminal_width()))
+        }
+    }
+}
+
+pub fn list_counterparties_command(teller: &Source,
                                    config: &Config,
                                    account: &AccountType,
                                    timeframe: &Timeframe,
-                                   count: &i64)
+                                   count: &i64,
+                                   output: &OutputFormat)
                                    -> i32 {
     info!("Calling the list counterparties command");
     let account_id = config.get_account_id(&account);
@@ -41,7 +73,8 @@ pub fn list_counterparties_command(teller: &TellerClient,
           .map(|counterparties_with_currency| {
               represent_list_counterparties(&counterparties_with_currency.counterparties,
                                             &counterparties_with_currency.currency,
-                                            &count);
+                                            &count,
+                                            &output);
               0
           })
           .unwrap_or_else(|err| {
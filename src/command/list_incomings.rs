@@ -1,5 +1,5 @@
 use config::Config;
-use api::TellerClient;
+use api::Source;
 use api::inform::{Incomings, GetIncomings};
 use cli::arg_types::{AccountType, OutputFormat, Interval, Timeframe};
 
@@ -11,7 +11,7 @@ fn represent_list_incomings(hac: &Incomings, output: &OutputFormat) {
     represent_list_amounts("incoming", &hac, &output)
 }
 
-pub fn list_incomings_command(teller: &TellerClient,
+pub fn list_incomings_command(teller: &Source,
                               config: &Config,
                               account: &AccountType,
                               interval: &Interval,
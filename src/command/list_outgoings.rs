@@ -1,5 +1,5 @@
 use config::Config;
-use api::TellerClient;
+use api::Source;
 use api::inform::{Outgoings, GetOutgoings};
 use cli::arg_types::{AccountType, OutputFormat, Interval, Timeframe};
 
@@ -11,7 +11,7 @@ fn represent_list_outgoings(hac: &Outgoings, output: &OutputFormat) {
     represent_list_amounts("outgoing", &hac, &output)
 }
 
-pub fn list_outgoings_command(teller: &TellerClient,
+pub fn list_outgoings_command(teller: &Source,
                               config: &Config,
                               account: &AccountType,
                               interval: &Interval,
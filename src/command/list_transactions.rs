@@ -1,7 +1,8 @@
 use config::Config;
-use api::{Transaction, TellerClient};
+use api::{Transaction, Source};
 use api::inform::{TransactionsWithCurrrency, GetTransactionsWithCurrency};
 use cli::arg_types::{Timeframe, AccountType};
+use locale::{format_amount, format_date};
 
 use command::representations::to_aligned_table;
 
@@ -20,9 +21,9 @@ fn represent_list_transactions(transactions: &Vec<Transaction>,
             let row_number = (idx + 1) as u32;
             let new_transaction_row = format!("{}\t{}\t{}\t{}\t{}\n",
                                               row_number,
-                                              transaction.date,
+                                              format_date(&transaction.date),
                                               transaction.counterparty,
-                                              transaction.amount,
+                                              format_amount(&transaction.amount),
                                               transaction.description);
             transactions_table = transactions_table + &new_transaction_row;
         }
@@ -32,9 +33,9 @@ fn represent_list_transactions(transactions: &Vec<Transaction>,
             let row_number = (idx + 1) as u32;
             let new_transaction_row = format!("{}\t{}\t{}\t{}\n",
                                               row_number,
-                                              transaction.date,
+                                              format_date(&transaction.date),
                                               transaction.counterparty,
-                                              transaction.amount);
+                                              format_amount(&transaction.amount));
             transactions_table = transactions_table + &new_transaction_row;
         }
     }
@@ -44,7 +45,7 @@ fn represent_list_transactions(transactions: &Vec<Transaction>,
     print!("{}", transactions_str)
 }
 
-pub fn list_transactions_command(teller: &TellerClient,
+pub fn list_transactions_command(teller: &Source,
                                  config: &Config,
                                  account: &AccountType,
                                  timeframe: &Timeframe,
@@ -0,0 +1,129 @@
+use std::path::PathBuf;
+
+use config::Config;
+use config::atomic_write::replace_atomically;
+use api::Source;
+use api::inform::{ApiServiceResult, GetAccountBalance, GetOutgoing, GetIncoming, GetCounterparties};
+use cli::arg_types::Timeframe;
+use redact::redact_output;
+
+use chrono::UTC;
+
+use command::timeframe_to_date_range;
+
+struct Gauge {
+    name: &'static str,
+    help: &'static str,
+    samples: Vec<(Vec<(&'static str, String)>, String)>,
+}
+
+impl Gauge {
+    fn new(name: &'static str, help: &'static str) -> Gauge {
+        Gauge {
+            name: name,
+            help: help,
+            samples: vec![],
+        }
+    }
+}
+
+fn escape_label_value(value: &str) -> String {
+    value.replace("\\", "\\\\").replace("\"", "\\\"").replace("\n", "\\n")
+}
+
+fn represent_gauges(gauges: &Vec<Gauge>) -> String {
+    let mut metrics = String::new();
+    for gauge in gauges.iter() {
+        metrics.push_str(&format!("# HELP {} {}\n", gauge.name, gauge.help));
+        metrics.push_str(&format!("# TYPE {} gauge\n", gauge.name));
+        for &(ref labels, ref value) in gauge.samples.iter() {
+            let labels_str = labels.iter()
+                                   .map(|&(name, ref value)| {
+                                       format!("{}=\"{}\"", name, escape_label_value(&value))
+                                   })
+                                   .collect::<Vec<String>>()
+                                   .join(",");
+            metrics.push_str(&format!("{}{{{}}} {}\n", gauge.name, labels_str, value));
+        }
+    }
+    metrics.push_str("# EOF\n");
+    metrics
+}
+
+fn get_gauges(teller: &Source,
+              config: &Config,
+              timeframe: &Timeframe,
+              count: &i64)
+              -> ApiServiceResult<Vec<Gauge>> {
+    let mut balance = Gauge::new("teller_account_balance", "The current balance of an account.");
+    let mut outgoing = Gauge::new("teller_account_outgoing_month_to_date",
+                                  "The money that has gone out of an account this month.");
+    let mut incoming = Gauge::new("teller_account_incoming_month_to_date",
+                                  "The money that has come into an account this month.");
+    let mut counterparty = Gauge::new("teller_counterparty_outgoing",
+                                      "The money that has gone to a top counterparty during the \
+                                       timeframe.");
+
+    let for_month = UTC::now().date();
+    let (from, to) = timeframe_to_date_range(&timeframe);
+    for (alias, account_id) in config.get_account_aliases() {
+        let account_balance = try!(teller.get_account_balance(&account_id));
+        let labels = vec![("account", account_id.to_owned()),
+                          ("alias", alias.to_owned()),
+                          ("currency", account_balance.currency.to_owned())];
+        balance.samples.push((labels.clone(), account_balance.amount));
+
+        let account_outgoing = try!(teller.get_outgoing(&account_id, &for_month));
+        outgoing.samples.push((labels.clone(), account_outgoing.amount));
+
+        let account_incoming = try!(teller.get_incoming(&account_id, &for_month));
+        incoming.samples.push((labels.clone(), account_incoming.amount));
+
+        let counterparties_with_currency = try!(teller.get_counterparties(&account_id, &from, &to));
+        let counterparties = counterparties_with_currency.counterparties;
+        let skip_n = counterparties.len().saturating_sub(*count as usize);
+        for &(ref name, ref amount) in counterparties.iter().skip(skip_n) {
+            let mut counterparty_labels = labels.clone();
+            counterparty_labels.push(("counterparty", name.to_owned()));
+            counterparty.samples.push((counterparty_labels, amount.to_owned()));
+        }
+    }
+
+    Ok(vec![balance, outgoing, incoming, counterparty])
+}
+
+// The textfile collector of node_exporter could read a half-written file,
+// so the metrics are written next to it first and then moved into place.
+fn write_textfile(textfile: &str, metrics: &str) -> Result<(), String> {
+    replace_atomically(&PathBuf::from(textfile), metrics.as_bytes()).map_err(|e| format!("{}", e))
+}
+
+pub fn metrics_command(teller: &Source,
+                       config: &Config,
+                       timeframe: &Timeframe,
+                       count: &i64,
+                       textfile: &str)
+                       -> i32 {
+    info!("Calling the metrics command");
+    match get_gauges(teller, &config, &timeframe, &count) {
+        Ok(gauges) => {
+            let metrics = redact_output(&represent_gauges(&gauges));
+            if textfile.is_empty() {
+                print!("{}", metrics);
+                0
+            } else {
+                match write_textfile(&textfile, &metrics) {
+                    Ok(_) => 0,
+                    Err(e) => {
+                        error!("Unable to write metrics to {}: {}", textfile, e);
+                        1
+                    }
+                }
+            }
+        }
+        Err(err) => {
+            error!("Unable to get metrics: {}", err);
+            1
+        }
+    }
+}
@@ -1,4 +1,6 @@
 mod representations;
+mod charts;
+mod terminal;
 
 mod show_usage;
 mod initialise;
@@ -6,16 +8,33 @@ mod list_accounts;
 mod show_balance;
 mod show_outgoing;
 mod show_incoming;
+mod show_counterparty;
+mod show_stats;
 mod list_transactions;
 mod list_counterparties;
 mod list_balances;
 mod list_outgoings;
 mod list_incomings;
+mod list_anomalies;
+mod watch;
+mod check;
+mod metrics;
+mod serve;
+mod import;
+mod export;
 
 use cli::{CommandType, CliArgs};
 
-use api::TellerClient;
-use config::{Config, get_config, get_config_path};
+use std::sync::Arc;
+use std::time;
+
+use api::{Source, TellerClient};
+use api::source::{FileSource, CacheSource, ImportedSource, get_cache_dir, get_imports_dir};
+use api::source::imported::is_imported_account_id;
+use config::{Config, get_config, get_config_path, get_profile_name, apply_env_overrides};
+use config::auth_token::{get_auth_token_from_env, resolve_auth_token};
+use redact::{SecretKind, add_secret};
+use locale::set_locale;
 use self::initialise::configure_cli;
 
 use self::show_usage::show_usage_command;
@@ -24,15 +43,25 @@ use self::list_accounts::list_accounts_command;
 use self::show_balance::show_balance_command;
 use self::show_outgoing::show_outgoing_command;
 use self::show_incoming::show_incoming_command;
+use self::show_counterparty::show_counterparty_command;
+use self::show_stats::show_stats_command;
 use self::list_transactions::list_transactions_command;
 use self::list_counterparties::list_counterparties_command;
 use self::list_balances::list_balances_command;
 use self::list_outgoings::list_outgoings_command;
 use self::list_incomings::list_incomings_command;
+use self::list_anomalies::list_anomalies_command;
+use self::watch::watch_command;
+use self::check::check_command;
+use self::metrics::metrics_command;
+use self::serve::serve_command;
+use self::import::import_command;
+use self::export::export_command;
 
 use chrono::{Date, UTC};
 use chrono::duration::Duration;
 use cli::arg_types::Timeframe;
+use cli::parse::to_seconds;
 use api::client::generate_utc_date_from_date_str;
 
 pub fn timeframe_to_date_range(timeframe: &Timeframe) -> (Date<UTC>, Date<UTC>){
@@ -54,14 +83,49 @@ pub fn timeframe_to_date_range(timeframe: &Timeframe) -> (Date<UTC>, Date<UTC>){
     (from, to)
 }
 
-fn ensure_config() -> Option<Config> {
-    get_config().or_else(|| {
-        let config_file_path = get_config_path();
-        println!("A config file could not be found at: {}",
-                 config_file_path.display());
-        println!("You will need to set the `auth_token` and give aliases to your bank accounts");
-        print!("\n");
-        configure_cli(&config_file_path)
+fn ensure_config(config_flag: &str, profile_flag: &str) -> Option<Config> {
+    let config_file_path = get_config_path(&config_flag);
+    let profile_name = get_profile_name(&profile_flag);
+    let config = match get_config(&config_file_path) {
+        Some(config) => {
+            match config.get_profile(&profile_name) {
+                Some(profile) => profile,
+                None => {
+                    error!("There is no profile named {} within {}",
+                           profile_name,
+                           config_file_path.display());
+                    return None;
+                }
+            }
+        }
+        // A config file is not needed when the `auth_token` is in the environment.
+        None if get_auth_token_from_env().is_some() => Config::new_with_auth_token_only(""),
+        None => {
+            println!("A config file could not be found at: {}",
+                     config_file_path.display());
+            println!("You will need to set the `auth_token` and give aliases to your bank \
+                      accounts");
+            print!("\n");
+            return configure_cli(&config_file_path, &profile_name);
+        }
+    };
+
+    match resolve_auth_token(apply_env_overrides(config)) {
+        Ok(config) => Some(config),
+        Err(e) => {
+            error!("Unable to get the `auth_token`: {}", e);
+            None
+        }
+    }
+}
+
+fn get_cache_max_age(config: &Config) -> Option<time::Duration> {
+    config.cache.as_ref().and_then(|cache| {
+        let max_age = to_seconds(&cache).map(time::Duration::from_secs);
+        if max_age.is_none() {
+            warn!("teller-cli could not understand the cache {} so will not cache", cache);
+        }
+        max_age
     })
 }
 
@@ -70,106 +134,292 @@ fn do_nothing_command() -> i32 {
     0
 }
 
+// The source is shared because `serve` answers requests from several threads.
+fn execute_with_source(shared_source: Arc<Source + Send + Sync>,
+                       config: &Config,
+                       command_type: &CommandType,
+                       arguments: &CliArgs)
+                       -> i32 {
+    let source: &Source = &*shared_source;
+    match *command_type {
+        CommandType::ListAccounts => list_accounts_command(source, &config),
+        CommandType::ShowBalance => {
+            let CliArgs { ref arg_account, flag_hide_currency, .. } = *arguments;
+            show_balance_command(source, &config, &arg_account, &flag_hide_currency)
+        }
+        CommandType::ShowOutgoing => {
+            let CliArgs { ref arg_account, flag_hide_currency, .. } = *arguments;
+            show_outgoing_command(source, &config, &arg_account, &flag_hide_currency)
+        }
+        CommandType::ShowIncoming => {
+            let CliArgs { ref arg_account, flag_hide_currency, .. } = *arguments;
+            show_incoming_command(source, &config, &arg_account, &flag_hide_currency)
+        }
+        CommandType::ShowCounterparty => {
+            let CliArgs {
+                ref arg_counterparty,
+                ref arg_account,
+                ref flag_interval,
+                ref flag_timeframe,
+                ..
+            } = *arguments;
+            show_counterparty_command(source,
+                                      &config,
+                                      &arg_counterparty,
+                                      &arg_account,
+                                      &flag_interval,
+                                      &flag_timeframe)
+        }
+        CommandType::ShowStats => {
+            let CliArgs { ref arg_account, ref flag_timeframe, .. } = *arguments;
+            show_stats_command(source, &config, &arg_account, &flag_timeframe)
+        }
+        CommandType::ListTransactions => {
+            let CliArgs {
+                ref arg_account,
+                flag_show_description,
+                ref flag_timeframe,
+                ..
+            } = *arguments;
+            list_transactions_command(source,
+                                      &config,
+                                      &arg_account,
+                                      &flag_timeframe,
+                                      &flag_show_description)
+        }
+        CommandType::ListCounterparties => {
+            let CliArgs {
+                ref arg_account,
+                ref flag_timeframe,
+                flag_count,
+                ref flag_output,
+                ..
+            } = *arguments;
+            list_counterparties_command(source,
+                                        &config,
+                                        &arg_account,
+                                        &flag_timeframe,
+                                        &flag_count,
+                                        &flag_output)
+        }
+        CommandType::ListBalances => {
+            let CliArgs {
+                ref arg_account,
+                ref flag_interval,
+                ref flag_timeframe,
+                ref flag_output,
+                ..
+            } = *arguments;
+            list_balances_command(source,
+                                  &config,
+                                  &arg_account,
+                                  &flag_interval,
+                                  &flag_timeframe,
+                                  &flag_output)
+        }
+        CommandType::ListOutgoings => {
+            let CliArgs {
+                ref arg_account,
+                ref flag_interval,
+                ref flag_timeframe,
+                ref flag_output,
+                ..
+            } = *arguments;
+            list_outgoings_command(source,
+                                   &config,
+                                   &arg_account,
+                                   &flag_interval,
+                                   &flag_timeframe,
+                                   &flag_output)
+        }
+        CommandType::ListIncomings => {
+            let CliArgs {
+                ref arg_account,
+                ref flag_interval,
+                ref flag_timeframe,
+                ref flag_output,
+                ..
+            } = *arguments;
+            list_incomings_command(source,
+                                   &config,
+                                   &arg_account,
+                                   &flag_interval,
+                                   &flag_timeframe,
+                                   &flag_output)
+        }
+        CommandType::ListAnomalies => {
+            let CliArgs {
+                ref arg_account,
+                ref flag_timeframe,
+                flag_threshold,
+                flag_within,
+                ..
+            } = *arguments;
+            list_anomalies_command(source,
+                                   &config,
+                                   &arg_account,
+                                   &flag_timeframe,
+                                   &flag_threshold,
+                                   &flag_within)
+        }
+        CommandType::Watch => {
+            let CliArgs { ref arg_account, ref flag_every, ref flag_hook, .. } = *arguments;
+            watch_command(source, &config, &arg_account, &flag_every, &flag_hook)
+        }
+        CommandType::Check => {
+            let CliArgs { ref arg_rule, flag_perfdata, .. } = *arguments;
+            check_command(source, &config, &arg_rule, &flag_perfdata)
+        }
+        CommandType::Metrics => {
+            let CliArgs {
+                ref flag_timeframe,
+                flag_count,
+                ref flag_textfile,
+                ..
+            } = *arguments;
+            metrics_command(source,
+                            &config,
+                            &flag_timeframe,
+                            &flag_count,
+                            &flag_textfile)
+        }
+        CommandType::Serve => {
+            let CliArgs { ref flag_listen, ref flag_refresh, .. } = *arguments;
+            serve_command(shared_source.clone(), &config, &flag_listen, &flag_refresh)
+        }
+        CommandType::Export => {
+            let CliArgs {
+                ref arg_account,
+                ref flag_timeframe,
+                ref flag_format,
+                ref flag_out,
+                ..
+            } = *arguments;
+            export_command(source,
+                           &config,
+                           &arg_account,
+                           &flag_timeframe,
+                           &flag_format,
+                           &flag_out)
+        }
+        _ => panic!("This should not have been executable but for some reason was"),
+    }
+}
+
 pub fn execute(usage: &str, command_type: &CommandType, arguments: &CliArgs) -> i32 {
     match *command_type {
         CommandType::None => do_nothing_command(),
         CommandType::ShowUsage => show_usage_command(usage),
-        CommandType::Initialise => initialise_command(),
+        CommandType::Initialise => {
+            let CliArgs {
+                ref flag_config,
+                ref flag_profile,
+                ref flag_auth_token,
+                ref flag_alias,
+                ref flag_from_file,
+                flag_yes,
+                ..
+            } = *arguments;
+            initialise_command(&flag_config,
+                               &flag_profile,
+                               &flag_auth_token,
+                               &flag_alias,
+                               &flag_from_file,
+                               flag_yes)
+        }
+        CommandType::Import => {
+            let CliArgs {
+                ref arg_file,
+                ref flag_account,
+                ref flag_format,
+                ref flag_columns,
+                ref flag_date_format,
+                ref flag_decimal_mark,
+                ref flag_config,
+                ref flag_profile,
+                ..
+            } = *arguments;
+            import_command(&arg_file,
+                           &flag_account,
+                           &flag_format,
+                           &flag_columns,
+                           &flag_date_format,
+                           &flag_decimal_mark,
+                           &flag_config,
+                           &flag_profile)
+        }
+        _ if !arguments.flag_input.is_empty() => {
+            // A statement is all that is needed, so a config is not generated,
+            // but the locale of one which exists is still used.
+            let locale = get_config(&get_config_path(&arguments.flag_config))
+                             .and_then(|config| {
+                                 config.get_profile(&get_profile_name(&arguments.flag_profile))
+                             })
+                             .and_then(|profile| profile.locale);
+            if let Some(ref locale) = locale {
+                set_locale(&locale);
+            }
+
+            match FileSource::open(&arguments.flag_input) {
+                Ok(source) => {
+                    let config = Config::new_with_auth_token_only("");
+                    execute_with_source(Arc::new(source), &config, &command_type, &arguments)
+                }
+                Err(e) => {
+                    error!("Unable to read transactions from {}: {}", arguments.flag_input, e);
+                    1
+                }
+            }
+        }
         _ => {
-            match ensure_config() {
+            match ensure_config(&arguments.flag_config, &arguments.flag_profile) {
                 None => {
                     error!("The command was not executed since a config could not be found or \
                             generated");
                     1
                 }
                 Some(config) => {
-                    let teller = TellerClient::new(&config.auth_token);
-                    match *command_type {
-                        CommandType::ListAccounts => list_accounts_command(&teller, &config),
-                        CommandType::ShowBalance => {
-                            let CliArgs { ref arg_account, flag_hide_currency, .. } = *arguments;
-                            show_balance_command(&teller, &config, &arg_account, &flag_hide_currency)
-                        }
-                        CommandType::ShowOutgoing => {
-                            let CliArgs { ref arg_account, flag_hide_currency, .. } = *arguments;
-                            show_outgoing_command(&teller, &config, &arg_account, &flag_hide_currency)
-                        }
-                        CommandType::ShowIncoming => {
-                            let CliArgs { ref arg_account, flag_hide_currency, .. } = *arguments;
-                            show_incoming_command(&teller, &config, &arg_account, &flag_hide_currency)
-                        }
-                        CommandType::ListTransactions => {
-                            let CliArgs {
-                                ref arg_account,
-                                flag_show_description,
-                                ref flag_timeframe,
-                                ..
-                            } = *arguments;
-                            list_transactions_command(&teller,
-                                                      &config,
-                                                      &arg_account,
-                                                      &flag_timeframe,
-                                                      &flag_show_description)
-                        }
-                        CommandType::ListCounterparties => {
-                            let CliArgs {
-                                ref arg_account,
-                                ref flag_timeframe,
-                                flag_count,
-                                ..
-                            } = *arguments;
-                            list_counterparties_command(&teller,
-                                                        &config,
-                                                        &arg_account,
-                                                        &flag_timeframe,
-                                                        &flag_count)
-                        }
-                        CommandType::ListBalances => {
-                            let CliArgs {
-                                ref arg_account,
-                                ref flag_interval,
-                                ref flag_timeframe,
-                                ref flag_output,
-                                ..
-                            } = *arguments;
-                            list_balances_command(&teller,
-                                                  &config,
-                                                  &arg_account,
-                                                  &flag_interval,
-                                                  &flag_timeframe,
-                                                  &flag_output)
-                        }
-                        CommandType::ListOutgoings => {
-                            let CliArgs {
-                                ref arg_account,
-                                ref flag_interval,
-                                ref flag_timeframe,
-                                ref flag_output,
-                                ..
-                            } = *arguments;
-                            list_outgoings_command(&teller,
-                                                   &config,
-                                                   &arg_account,
-                                                   &flag_interval,
-                                                   &flag_timeframe,
-                                                   &flag_output)
+                    add_secret(SecretKind::AuthToken, &config.auth_token);
+                    for (_, account_id) in config.get_account_aliases() {
+                        add_secret(SecretKind::AccountId, &account_id);
+                    }
+                    if let Some(ref locale) = config.locale {
+                        set_locale(&locale);
+                    }
+
+                    // Accounts imported from statements are read from disk.
+                    let imported_account_ids = config.get_account_aliases()
+                                                     .into_iter()
+                                                     .map(|(_, account_id)| account_id)
+                                                     .filter(|account_id| {
+                                                         is_imported_account_id(&account_id)
+                                                     })
+                                                     .collect();
+                    let teller = TellerClient::new(config.auth_token.to_owned());
+                    match get_cache_max_age(&config) {
+                        Some(max_age) => {
+                            // Profiles can have different tokens, so each has its own cache.
+                            let cache_dir = get_cache_dir()
+                                                .join(get_profile_name(&arguments.flag_profile));
+                            let teller = ImportedSource::new(CacheSource::new(teller,
+                                                                              cache_dir,
+                                                                              max_age),
+                                                             get_imports_dir(),
+                                                             imported_account_ids);
+                            execute_with_source(Arc::new(teller),
+                                                &config,
+                                                &command_type,
+                                                &arguments)
                         }
-                        CommandType::ListIncomings => {
-                            let CliArgs {
-                                ref arg_account,
-                                ref flag_interval,
-                                ref flag_timeframe,
-                                ref flag_output,
-                                ..
-                            } = *arguments;
-                            list_incomings_command(&teller,
-                                                   &config,
-                                                   &arg_account,
-                                                   &flag_interval,
-                                                   &flag_timeframe,
-                                                   &flag_output)
+                        None => {
+                            let teller = ImportedSource::new(teller,
+                                                             get_imports_dir(),
+                                                             imported_account_ids);
+                            execute_with_source(Arc::new(teller),
+                                                &config,
+                                                &command_type,
+                                                &arguments)
                         }
-                        _ => panic!("This should not have been executable but for some reason was"),
                     }
                 }
             }
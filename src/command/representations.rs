@@ -1,42 +1,90 @@
 use std::io::Write;
+use std::str::FromStr; // Use of #from_str.
 use tabwriter::TabWriter;
 
 use config::Config;
 use api::Account;
 use api::inform::HistoricalAmountsWithCurrency;
 use cli::arg_types::OutputFormat;
+use redact::redact_output;
+use color::{paint_table, paint_markers};
+use locale::{format_amount, format_money, format_month};
+
+use command::charts::{to_sparkline_chart, to_bar_chart, to_line_chart};
+use command::terminal::get_terminal_width;
 
 pub fn to_aligned_table(table_str: &str) -> String {
+    // Masking happens before alignment as masks differ in width from secrets.
+    let table_str = redact_output(&table_str);
+
     let mut tw = TabWriter::new(Vec::new());
     write!(&mut tw, "{}", table_str).unwrap();
     tw.flush().unwrap();
 
     let aligned_table_str = String::from_utf8(tw.unwrap()).unwrap();
 
-    aligned_table_str
+    paint_table(&aligned_table_str)
+}
+
+pub fn to_sparkline(amounts: &Vec<f64>) -> String {
+    const TICKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+    let min = amounts.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = amounts.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let range = max - min;
+
+    amounts.iter()
+           .map(|amount| {
+               if range == 0f64 {
+                   TICKS[0]
+               } else {
+                   let tick = ((amount - min) / range * (TICKS.len() - 1) as f64).round();
+                   TICKS[tick as usize]
+               }
+           })
+           .collect()
 }
 
 pub fn represent_list_accounts(accounts: &Vec<Account>, config: &Config) {
     let mut accounts_table = String::new();
     accounts_table.push_str("row\tname\taccount no.\tbalance\n");
+    let mut account_aliases = vec![];
     for (idx, account) in accounts.iter().enumerate() {
         let row_number = (idx + 1) as u32;
         let account_alias = config.get_account_alias_for_id(&account.id);
-        let new_account_row = format!("{} {}\t{}\t{}\t{}\t{}\n",
+        account_aliases.push(account_alias.to_owned());
+        let new_account_row = format!("{} {}\t{}\t{}\t{}\n",
                                       row_number,
                                       account_alias,
                                       account.name,
                                       account.account_number,
-                                      account.balance,
-                                      account.currency);
+                                      format_money(&account.balance, &account.currency, &false));
         accounts_table = accounts_table + &new_account_row;
     }
 
-    let accounts_str = to_aligned_table(&accounts_table);
+    let accounts_str = paint_markers(&to_aligned_table(&accounts_table), &account_aliases);
 
     print!("{}", accounts_str)
 }
 
+/// Pairs each label with its amount, for the charts.
+pub fn to_chart_points(labelled_amounts: &Vec<(String, String)>) -> Vec<(String, f64)> {
+    labelled_amounts.iter()
+                    .map(|&(ref label, ref amount)| {
+                        (redact_output(&label), f64::from_str(&amount).unwrap_or(0f64))
+                    })
+                    .collect()
+}
+
+// Months are labelled as the locale displays them.
+fn to_month_chart_points(hac: &HistoricalAmountsWithCurrency) -> Vec<(String, f64)> {
+    let labelled_amounts = hac.historical_amounts
+                              .iter()
+                              .map(|&(ref month, ref amount)| (format_month(&month), amount.to_owned()))
+                              .collect();
+    to_chart_points(&labelled_amounts)
+}
+
 pub fn represent_list_amounts(amount_type: &str,
                               hac: &HistoricalAmountsWithCurrency,
                               output: &OutputFormat) {
@@ -49,17 +97,34 @@ pub fn represent_list_amounts(amount_type: &str,
                                  .join(" ");
             println!("{}", balance_str)
         }
+        OutputFormat::Sparkline => {
+            let label = format!("{} ({})", amount_type, hac.currency);
+            print!("{}",
+                   to_sparkline_chart(&label,
+                                      &to_month_chart_points(&hac),
+                                      get_terminal_width()))
+        }
+        OutputFormat::Bars => {
+            println!("{} ({})", amount_type, hac.currency);
+            print!("{}",
+                   to_bar_chart(&to_month_chart_points(&hac), get_terminal_width()))
+        }
+        OutputFormat::Line => {
+            println!("{} ({})", amount_type, hac.currency);
+            print!("{}",
+                   to_line_chart(&to_month_chart_points(&hac), get_terminal_width()))
+        }
         OutputFormat::Standard => {
             let mut hac_table = String::new();
             let month_cols = hac.historical_amounts
                                 .iter()
-                                .map(|historical_amount| historical_amount.0.to_owned())
+                                .map(|historical_amount| format_month(&historical_amount.0))
                                 .collect::<Vec<String>>()
                                 .join("\t");
             hac_table.push_str(&format!("\t{}\n", month_cols));
             hac_table.push_str(&format!("{} ({})", amount_type, hac.currency));
             for historical_amount in hac.historical_amounts.iter() {
-                let new_amount = format!("\t{}", historical_amount.1);
+                let new_amount = format!("\t{}", format_amount(&historical_amount.1));
                 hac_table = hac_table + &new_amount;
             }
 
@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+use std::str::FromStr; // Use of #from_str.
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use hyper::Url;
+use hyper::header::ContentType;
+use hyper::method::Method;
+use hyper::server::{Server, Handler, Request, Response};
+use hyper::status::StatusCode;
+use hyper::uri::RequestUri;
+use rustc_serialize::Encodable;
+use rustc_serialize::json;
+
+use config::Config;
+use api::Source;
+use api::error::TellerClientError;
+use api::inform::{GetBalances, GetOutgoings, GetIncomings, GetCounterparties,
+                  GetTransactionsWithCurrency};
+use cli::arg_types::PollingInterval;
+use cli::parse::{to_interval, to_timeframe};
+use redact::redact_output;
+
+use command::timeframe_to_date_range;
+
+type Query = HashMap<String, String>;
+
+type ServeResult = Result<String, (StatusCode, String)>;
+
+enum Route {
+    Accounts,
+    Transactions(String),
+    Balances,
+    Outgoings,
+    Incomings,
+    Counterparties,
+}
+
+fn to_route(path: &str) -> Option<Route> {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    match segments.len() {
+        1 => {
+            match segments[0] {
+                "accounts" => Some(Route::Accounts),
+                "balances" => Some(Route::Balances),
+                "outgoings" => Some(Route::Outgoings),
+                "incomings" => Some(Route::Incomings),
+                "counterparties" => Some(Route::Counterparties),
+                _ => None,
+            }
+        }
+        3 if segments[0] == "accounts" && segments[2] == "transactions" => {
+            Some(Route::Transactions(segments[1].to_string()))
+        }
+        _ => None,
+    }
+}
+
+fn to_json<T: Encodable>(value: &T) -> ServeResult {
+    json::encode(&value).map_err(|e| (StatusCode::InternalServerError, format!("{}", e)))
+}
+
+fn to_bad_gateway(e: TellerClientError) -> (StatusCode, String) {
+    (StatusCode::BadGateway, format!("{}", e))
+}
+
+fn to_error_json(message: &str) -> String {
+    let mut error = HashMap::new();
+    error.insert("error", message);
+    json::encode(&error).unwrap_or("{}".to_string())
+}
+
+struct CachedResponse {
+    fetched_at: Instant,
+    body: String,
+}
+
+// Each response is kept for the refresh interval so that a dashboard polling
+// every few seconds does not result in a request to Teller every few seconds.
+struct ServeHandler {
+    source: Arc<Source + Send + Sync>,
+    config: Config,
+    refresh: Duration,
+    cache: Mutex<HashMap<String, CachedResponse>>,
+}
+
+impl ServeHandler {
+    fn get_account_id(&self, alias: &str) -> Result<String, (StatusCode, String)> {
+        let account_aliases = self.config.get_account_aliases();
+        // A statement read with `--input` is a single account, whatever it is called.
+        if account_aliases.is_empty() {
+            return Ok("".to_string());
+        }
+        account_aliases.into_iter()
+                       .find(|&(ref account_alias, _)| account_alias == alias)
+                       .map(|(_, account_id)| account_id)
+                       .ok_or((StatusCode::NotFound,
+                               format!("There is no account aliased as {}", alias)))
+    }
+
+    fn respond(&self, route: &Route, query: &Query) -> ServeResult {
+        let teller = &*self.source;
+        let get_param = |name: &str| query.get(name).map(|v| &v[..]).unwrap_or("");
+        let (from, to) = timeframe_to_date_range(&to_timeframe(get_param("timeframe")));
+        let interval = to_interval(get_param("interval"));
+        let account_alias = match get_param("account") {
+            "" => "current",
+            alias => alias,
+        };
+
+        match *route {
+            Route::Accounts => {
+                teller.get_accounts()
+                      .map_err(to_bad_gateway)
+                      .and_then(|accounts| to_json(&accounts))
+            }
+            Route::Transactions(ref alias) => {
+                let account_id = try!(self.get_account_id(&alias));
+                let mut twc = try!(teller.get_transactions_with_currency(&account_id, &from, &to)
+                                         .map_err(to_bad_gateway));
+                let counterparty = get_param("counterparty").to_lowercase();
+                if !counterparty.is_empty() {
+                    twc.transactions.retain(|t| t.counterparty.to_lowercase() == counterparty);
+                }
+                to_json(&twc)
+            }
+            Route::Balances => {
+                let account_id = try!(self.get_account_id(&account_alias));
+                teller.get_balances(&account_id, &interval, &from, &to)
+                      .map_err(to_bad_gateway)
+                      .and_then(|b| to_json(&b))
+            }
+            Route::Outgoings => {
+                let account_id = try!(self.get_account_id(&account_alias));
+                teller.get_outgoings(&account_id, &interval, &from, &to)
+                      .map_err(to_bad_gateway)
+                      .and_then(|o| to_json(&o))
+            }
+            Route::Incomings => {
+                let account_id = try!(self.get_account_id(&account_alias));
+                teller.get_incomings(&account_id, &interval, &from, &to)
+                      .map_err(to_bad_gateway)
+                      .and_then(|i| to_json(&i))
+            }
+            Route::Counterparties => {
+                let account_id = try!(self.get_account_id(&account_alias));
+                let mut cwc = try!(teller.get_counterparties(&account_id, &from, &to)
+                                         .map_err(to_bad_gateway));
+                if let Ok(count) = usize::from_str(get_param("count")) {
+                    let skip_n = cwc.counterparties.len().saturating_sub(count);
+                    cwc.counterparties = cwc.counterparties.split_off(skip_n);
+                }
+                to_json(&cwc)
+            }
+        }
+    }
+
+    fn get_response_body(&self, uri: &str) -> ServeResult {
+        if let Some(cached) = self.cache.lock().unwrap().get(uri) {
+            if cached.fetched_at.elapsed() < self.refresh {
+                debug!("Serving {} from the cache", uri);
+                return Ok(cached.body.to_owned());
+            }
+        }
+
+        let url = try!(Url::parse(&format!("http://localhost{}", uri))
+                           .map_err(|e| (StatusCode::BadRequest, format!("{}", e))));
+        let route = try!(to_route(url.path())
+                             .ok_or((StatusCode::NotFound, format!("{} was not found", url.path()))));
+        let mut query = Query::new();
+        for (name, value) in url.query_pairs() {
+            query.insert(name.to_string(), value.to_string());
+        }
+
+        let body = try!(self.respond(&route, &query));
+
+        let mut cache = self.cache.lock().unwrap();
+        let refresh = self.refresh;
+        cache.retain(|_, cached| cached.fetched_at.elapsed() < refresh);
+        cache.insert(uri.to_string(),
+                     CachedResponse {
+                         fetched_at: Instant::now(),
+                         body: body.to_owned(),
+                     });
+        Ok(body)
+    }
+}
+
+impl Handler for ServeHandler {
+    fn handle(&self, req: Request, mut res: Response) {
+        let result = match (&req.method, &req.uri) {
+            (&Method::Get, &RequestUri::AbsolutePath(ref uri)) => {
+                info!("GET {}", uri);
+                self.get_response_body(&uri)
+            }
+            _ => Err((StatusCode::MethodNotAllowed, "Only GET is supported".to_string())),
+        };
+
+        res.headers_mut().set(ContentType::json());
+        let body = match result {
+            Ok(body) => redact_output(&body),
+            Err((status, message)) => {
+                error!("Unable to serve {}: {}", req.uri, message);
+                *res.status_mut() = status;
+                to_error_json(&message)
+            }
+        };
+        if let Err(e) = res.send(body.as_bytes()) {
+            error!("Unable to send response: {}", e);
+        }
+    }
+}
+
+pub fn serve_command(teller: Arc<Source + Send + Sync>,
+                     config: &Config,
+                     listen: &str,
+                     refresh: &PollingInterval)
+                     -> i32 {
+    info!("Calling the serve command");
+    let PollingInterval(seconds) = *refresh;
+    let handler = ServeHandler {
+        source: teller,
+        config: config.clone(),
+        refresh: Duration::from_secs(seconds),
+        cache: Mutex::new(HashMap::new()),
+    };
+
+    match Server::http(listen).and_then(|server| server.handle(handler)) {
+        Ok(_listening) => {
+            println!("Serving on http://{}", listen);
+            // Dropping the listening guard joins the server threads, so this
+            // blocks until the process is stopped.
+            0
+        }
+        Err(e) => {
+            error!("Unable to listen on {}: {}", listen, e);
+            1
+        }
+    }
+}
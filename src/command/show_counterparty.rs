@@ -0,0 +1,92 @@
+use std::str::FromStr; // Use of #from_str.
+
+use config::Config;
+use api::Source;
+use api::inform::{CounterpartyWithCurrency, GetCounterparty};
+use cli::arg_types::{AccountType, OutputFormat, Interval, Timeframe};
+use locale::{format_amount, format_date, format_money};
+
+use command::representations::{to_aligned_table, to_sparkline, represent_list_amounts};
+
+use command::timeframe_to_date_range;
+
+fn describe_cadence(cadence_in_days: &Option<i64>) -> String {
+    match *cadence_in_days {
+        None => "one-off".to_string(),
+        Some(days) => {
+            let named_cadence = match days {
+                6...8 => Some("weekly"),
+                13...16 => Some("fortnightly"),
+                27...32 => Some("monthly"),
+                85...95 => Some("quarterly"),
+                360...370 => Some("yearly"),
+                _ => None,
+            };
+            match named_cadence {
+                Some(name) => format!("{} (every ~{} days)", name, days),
+                None => format!("every ~{} days", days),
+            }
+        }
+    }
+}
+
+fn represent_show_counterparty(cwc: &CounterpartyWithCurrency) {
+    let mut transactions_table = String::new();
+    transactions_table.push_str(&format!("row\tdate\tamount ({})\tdescription\n", cwc.currency));
+    for (idx, transaction) in cwc.transactions.iter().enumerate() {
+        let row_number = (idx + 1) as u32;
+        let new_transaction_row = format!("{}\t{}\t{}\t{}\n",
+                                          row_number,
+                                          format_date(&transaction.date),
+                                          format_amount(&transaction.amount),
+                                          transaction.description);
+        transactions_table = transactions_table + &new_transaction_row;
+    }
+    print!("{}", to_aligned_table(&transactions_table));
+    print!("\n");
+
+    represent_list_amounts("outgoing", &cwc.outgoings, &OutputFormat::Standard);
+    let amounts = cwc.outgoings
+                     .historical_amounts
+                     .iter()
+                     .map(|ia| f64::from_str(&ia.1).unwrap())
+                     .collect();
+    println!("{}", to_sparkline(&amounts));
+    print!("\n");
+
+    let mut summary_table = String::new();
+    summary_table.push_str(&format!("counterparty\t{}\n", cwc.counterparty));
+    summary_table.push_str(&format!("payments\t{}\n", cwc.payment_count));
+    summary_table.push_str(&format!("total\t{}\n", format_money(&cwc.total, &cwc.currency, &false)));
+    summary_table.push_str(&format!("average payment\t{}\n",
+                                    format_money(&cwc.average_payment, &cwc.currency, &false)));
+    summary_table.push_str(&format!("cadence\t{}\n", describe_cadence(&cwc.cadence_in_days)));
+    summary_table.push_str(&format!("share of outgoings\t{}%\n",
+                                    format_amount(&cwc.share_of_outgoings)));
+    print!("{}", to_aligned_table(&summary_table))
+}
+
+pub fn show_counterparty_command(teller: &Source,
+                                 config: &Config,
+                                 counterparty: &str,
+                                 account: &AccountType,
+                                 interval: &Interval,
+                                 timeframe: &Timeframe)
+                                 -> i32 {
+    info!("Calling the show counterparty command");
+    let account_id = config.get_account_id(&account);
+    let (from, to) = timeframe_to_date_range(&timeframe);
+    teller.get_counterparty(&account_id, &counterparty, &interval, &from, &to)
+          .map(|counterparty_with_currency| {
+              if counterparty_with_currency.transactions.is_empty() {
+                  error!("No transactions were found for the counterparty {}", counterparty);
+                  return 1;
+              }
+              represent_show_counterparty(&counterparty_with_currency);
+              0
+          })
+          .unwrap_or_else(|err| {
+              error!("Unable to show counterparty: {}", err);
+              1
+          })
+}
@@ -1,16 +1,19 @@
-use api::TellerClient;
+use api::Source;
 use api::inform::{Money, GetIncoming};
 use config::Config;
 use cli::arg_types::AccountType;
+use color::paint_amounts;
+use locale::get_display_format;
 
 use chrono::UTC;
 
 fn represent_money(money_with_currency: &Money, hide_currency: &bool) {
     println!("{}",
-             money_with_currency.get_balance_for_display(&hide_currency))
+             paint_amounts(&money_with_currency.get_balance_for_display(&get_display_format(),
+                                                                    &hide_currency)))
 }
 
-pub fn show_incoming_command(teller: &TellerClient,
+pub fn show_incoming_command(teller: &Source,
                              config: &Config,
                              account: &AccountType,
                              hide_currency: &bool)
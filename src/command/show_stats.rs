@@ -0,0 +1,55 @@
+use config::Config;
+use api::Source;
+use api::inform::{Stats, StatsWithCurrency, GetStats};
+use cli::arg_types::{AccountType, Timeframe};
+use locale::format_amount;
+
+use command::representations::to_aligned_table;
+
+use command::timeframe_to_date_range;
+
+fn represent_show_stats(swc: &StatsWithCurrency) {
+    let StatsWithCurrency { ref outgoing, ref incoming, ref currency } = *swc;
+
+    let mut stats_table = String::new();
+    stats_table.push_str(&format!("\toutgoing ({})\tincoming ({})\n", currency, currency));
+
+    let describe_weekday = |s: &Stats| s.busiest_weekday.to_owned().unwrap_or("-".to_string());
+    let describe_day_of_month = |s: &Stats| {
+        s.busiest_day_of_month.map_or("-".to_string(), |d| d.to_string())
+    };
+    let rows = vec![
+        ("transactions", outgoing.count.to_string(), incoming.count.to_string()),
+        ("mean", format_amount(&outgoing.mean), format_amount(&incoming.mean)),
+        ("median", format_amount(&outgoing.median), format_amount(&incoming.median)),
+        ("p90", format_amount(&outgoing.p90), format_amount(&incoming.p90)),
+        ("max", format_amount(&outgoing.max), format_amount(&incoming.max)),
+        ("average daily", format_amount(&outgoing.average_daily), format_amount(&incoming.average_daily)),
+        ("busiest weekday", describe_weekday(outgoing), describe_weekday(incoming)),
+        ("busiest day of month", describe_day_of_month(outgoing), describe_day_of_month(incoming)),
+    ];
+    for (name, outgoing_value, incoming_value) in rows {
+        stats_table.push_str(&format!("{}\t{}\t{}\n", name, outgoing_value, incoming_value));
+    }
+
+    print!("{}", to_aligned_table(&stats_table))
+}
+
+pub fn show_stats_command(teller: &Source,
+                          config: &Config,
+                          account: &AccountType,
+                          timeframe: &Timeframe)
+                          -> i32 {
+    info!("Calling the show stats command");
+    let account_id = config.get_account_id(&account);
+    let (from, to) = timeframe_to_date_range(&timeframe);
+    teller.get_stats(&account_id, &from, &to)
+          .map(|stats_with_currency| {
+              represent_show_stats(&stats_with_currency);
+              0
+          })
+          .unwrap_or_else(|err| {
+              error!("Unable to show stats: {}", err);
+              1
+          })
+}
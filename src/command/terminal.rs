@@ -0,0 +1,25 @@
+use std::env;
+use std::mem;
+
+use libc;
+
+const DEFAULT_TERMINAL_WIDTH: usize = 80;
+
+/// The number of columns of the terminal, which is `$COLUMNS` when it is set
+/// and 80 when stdout is not a terminal.
+pub fn get_terminal_width() -> usize {
+    let columns = env::var("COLUMNS").ok().and_then(|columns| columns.parse::<usize>().ok());
+    if let Some(columns) = columns {
+        if columns > 0 {
+            return columns;
+        }
+    }
+
+    let mut winsize: libc::winsize = unsafe { mem::zeroed() };
+    let is_terminal = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut winsize) == 0 };
+    if is_terminal && winsize.ws_col > 0 {
+        winsize.ws_col as usize
+    } else {
+        DEFAULT_TERMINAL_WIDTH
+    }
+}
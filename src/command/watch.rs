@@ -0,0 +1,94 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+use rustc_serialize::json;
+
+use config::Config;
+use config::watch_cursor::{get_watch_cursor, save_watch_cursor};
+use api::{Transaction, Source};
+use api::inform::GetNewTransactions;
+use cli::arg_types::{AccountType, PollingInterval};
+use locale::{format_amount, format_date};
+use redact::redact_output;
+
+fn represent_new_transaction(transaction: &Transaction) {
+    let transaction_str = format!("{}\t{}\t{}",
+                                  format_date(&transaction.date),
+                                  transaction.counterparty,
+                                  format_amount(&transaction.amount));
+    println!("{}", redact_output(&transaction_str))
+}
+
+// The hook is run through the shell so that it can contain pipes, and
+// receives the transaction as JSON on its stdin.
+fn run_hook(hook: &str, transaction: &Transaction) {
+    let transaction_json = match json::encode(&transaction) {
+        Ok(transaction_json) => transaction_json,
+        Err(e) => {
+            error!("Unable to encode transaction for the hook: {}", e);
+            return;
+        }
+    };
+
+    let child = Command::new("sh")
+                    .arg("-c")
+                    .arg(hook)
+                    .stdin(Stdio::piped())
+                    .spawn();
+    match child {
+        Ok(mut child) => {
+            if let Some(ref mut stdin) = child.stdin {
+                if let Err(e) = stdin.write_all(transaction_json.as_bytes()) {
+                    error!("Unable to write transaction to the hook: {}", e);
+                }
+            }
+            // Dropping stdin closes it, so that the hook sees the end of its input.
+            child.stdin.take();
+            match child.wait() {
+                Ok(status) if !status.success() => error!("The hook exited with {}", status),
+                Ok(_) => (),
+                Err(e) => error!("Unable to wait for the hook: {}", e),
+            }
+        }
+        Err(e) => error!("Unable to run the hook {}: {}", hook, e),
+    }
+}
+
+pub fn watch_command(teller: &Source,
+                     config: &Config,
+                     account: &AccountType,
+                     every: &PollingInterval,
+                     hook: &str)
+                     -> i32 {
+    info!("Calling the watch command");
+    let account_id = config.get_account_id(&account);
+    let PollingInterval(seconds) = *every;
+
+    let mut cursor = get_watch_cursor(&account_id);
+    loop {
+        match teller.get_new_transactions(&account_id, &cursor) {
+            Ok(new_transactions) => {
+                for transaction in new_transactions.transactions.iter() {
+                    represent_new_transaction(&transaction);
+                    if !hook.is_empty() {
+                        run_hook(&hook, &transaction);
+                    }
+                }
+
+                if new_transactions.cursor != cursor {
+                    cursor = new_transactions.cursor;
+                    if let Some(ref cursor) = cursor {
+                        if let Err(e) = save_watch_cursor(&account_id, &cursor) {
+                            error!("Unable to save watch cursor: {}", e);
+                        }
+                    }
+                }
+            }
+            Err(e) => error!("Unable to get new transactions: {}", e),
+        }
+
+        thread::sleep(Duration::from_secs(seconds));
+    }
+}
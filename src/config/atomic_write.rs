@@ -0,0 +1,220 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Error as StdIoError, ErrorKind};
+use std::io::prelude::*; // Required for Read, Write, etc.
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use config::error::ConfigError;
+
+const LOCK_TIMEOUT_SECS: u64 = 10;
+const LOCK_RETRY_MILLIS: u64 = 100;
+// A write takes milliseconds, so a lock this old was left by a process which
+// was killed.
+const STALE_LOCK_SECS: u64 = 60;
+
+fn with_suffix(config_path: &PathBuf, suffix: &str) -> PathBuf {
+    PathBuf::from(format!("{}{}", config_path.display(), suffix))
+}
+
+/// `<config>.bak`, which holds the config as it was before its last write.
+pub fn get_rolling_backup_path(config_path: &PathBuf) -> PathBuf {
+    with_suffix(&config_path, ".bak")
+}
+
+/// Creates (or truncates) a file which only its owner can read and write.
+#[cfg(unix)]
+pub fn create_private_file(path: &PathBuf) -> Result<File, StdIoError> {
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+    let file = try!(OpenOptions::new()
+                        .write(true)
+                        .create(true)
+                        .truncate(true)
+                        .mode(0o600)
+                        .open(&path));
+    // The mode is only used when the file is created, so an existing file is
+    // locked down too.
+    try!(fs::set_permissions(&path, fs::Permissions::from_mode(0o600)));
+    Ok(file)
+}
+
+/// Creates (or truncates) a file which only its owner can read and write.
+#[cfg(not(unix))]
+pub fn create_private_file(path: &PathBuf) -> Result<File, StdIoError> {
+    OpenOptions::new().write(true).create(true).truncate(true).open(&path)
+}
+
+/// A backup holds the same `auth_token` as the config, so it is created as a
+/// private file whatever the permissions of the original.
+pub fn copy_privately(from: &PathBuf, to: &PathBuf) -> Result<(), StdIoError> {
+    let mut from_file = try!(File::open(&from));
+    let mut to_file = try!(create_private_file(&to));
+    try!(io::copy(&mut from_file, &mut to_file));
+    to_file.sync_all()
+}
+
+fn is_stale_lock(lock_path: &PathBuf) -> bool {
+    fs::metadata(&lock_path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .map(|age| age > Duration::from_secs(STALE_LOCK_SECS))
+        .unwrap_or(false)
+}
+
+/// Held while a config is written so that concurrent teller processes do not
+/// interleave their writes. The lock file is removed when this is dropped.
+#[derive(Debug)]
+pub struct ConfigLock {
+    lock_path: PathBuf,
+}
+
+impl ConfigLock {
+    fn acquire(config_path: &PathBuf, timeout: Duration) -> Result<ConfigLock, ConfigError> {
+        let lock_path = with_suffix(&config_path, ".lock");
+        let retry = Duration::from_millis(LOCK_RETRY_MILLIS);
+        let mut waited = Duration::from_millis(0);
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&lock_path) {
+                Ok(_) => return Ok(ConfigLock { lock_path: lock_path }),
+                Err(ref e) if e.kind() == ErrorKind::AlreadyExists => {
+                    if is_stale_lock(&lock_path) {
+                        info!("Removing the stale lock {}", lock_path.display());
+                        let _ = fs::remove_file(&lock_path);
+                        continue;
+                    }
+                }
+                Err(e) => return Err(ConfigError::IoError(e)),
+            }
+            if waited >= timeout {
+                return Err(ConfigError::LockError(format!("Another teller process is writing \
+                                                           the config. Remove {} if it is not.",
+                                                          lock_path.display())));
+            }
+            thread::sleep(retry);
+            waited += retry;
+        }
+    }
+}
+
+impl Drop for ConfigLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.lock_path);
+    }
+}
+
+/// Waits for the lock on a config, giving up after a few seconds.
+pub fn lock_config(config_path: &PathBuf) -> Result<ConfigLock, ConfigError> {
+    ConfigLock::acquire(&config_path, Duration::from_secs(LOCK_TIMEOUT_SECS))
+}
+
+#[cfg(unix)]
+fn sync_parent_dir(path: &Path) {
+    // The rename is only durable once the directory containing it is synced.
+    let parent_dir = match path.parent() {
+        Some(parent_dir) if parent_dir != Path::new("") => parent_dir,
+        _ => Path::new("."),
+    };
+    if let Ok(parent_dir) = File::open(parent_dir) {
+        let _ = parent_dir.sync_all();
+    }
+}
+
+#[cfg(not(unix))]
+fn sync_parent_dir(_path: &Path) {}
+
+/// The content goes to a temporary file which is fsynced and then renamed over
+/// the file, so a crash or failed write never leaves it empty or half written.
+pub fn replace_atomically(path: &PathBuf, content: &[u8]) -> Result<(), StdIoError> {
+    let temp_path = with_suffix(&path, ".tmp");
+    let written = create_private_file(&temp_path).and_then(|mut temp_file| {
+        try!(temp_file.write_all(content));
+        temp_file.sync_all()
+    });
+    if let Err(e) = written {
+        let _ = fs::remove_file(&temp_path);
+        return Err(e);
+    }
+
+    if let Err(e) = fs::rename(&temp_path, &path) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(e);
+    }
+    sync_parent_dir(&path);
+
+    Ok(())
+}
+
+/// The previous config is kept as `<config>.bak`.
+pub fn write_atomically(config_path: &PathBuf,
+                        _lock: &ConfigLock,
+                        content: &[u8])
+                        -> Result<(), ConfigError> {
+    if config_path.exists() {
+        try!(copy_privately(&config_path, &get_rolling_backup_path(&config_path)));
+    }
+    try!(replace_atomically(&config_path, &content));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs::{self, File};
+    use std::io::prelude::*;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    use super::{ConfigLock, lock_config, write_atomically, get_rolling_backup_path};
+
+    fn read_to_string(path: &PathBuf) -> String {
+        let mut content = String::new();
+        File::open(&path).unwrap().read_to_string(&mut content).unwrap();
+        content
+    }
+
+    #[cfg(unix)]
+    fn get_mode(path: &PathBuf) -> u32 {
+        use std::os::unix::fs::PermissionsExt;
+        fs::metadata(&path).unwrap().permissions().mode() & 0o777
+    }
+
+    #[cfg(not(unix))]
+    fn get_mode(_path: &PathBuf) -> u32 {
+        0
+    }
+
+    #[test]
+    fn can_write_config_atomically() {
+        let config_path = env::temp_dir().join("teller-test-write-atomically");
+        let _ = fs::remove_file(&config_path);
+        let _ = fs::remove_file(&get_rolling_backup_path(&config_path));
+        File::create(&config_path).unwrap();
+
+        {
+            let lock = lock_config(&config_path).unwrap();
+            write_atomically(&config_path, &lock, b"first").unwrap();
+            write_atomically(&config_path, &lock, b"second").unwrap();
+        }
+
+        assert_eq!("second", read_to_string(&config_path));
+        assert_eq!("first", read_to_string(&get_rolling_backup_path(&config_path)));
+        assert_eq!(true, lock_config(&config_path).is_ok());
+        if cfg!(unix) {
+            assert_eq!(0o600, get_mode(&get_rolling_backup_path(&config_path)));
+        }
+
+        let _ = fs::remove_file(&config_path);
+        let _ = fs::remove_file(&get_rolling_backup_path(&config_path));
+    }
+
+    #[test]
+    fn can_lock_out_other_writers() {
+        let config_path = env::temp_dir().join("teller-test-lock");
+
+        let _lock = lock_config(&config_path).unwrap();
+
+        assert_eq!(true, ConfigLock::acquire(&config_path, Duration::from_millis(0)).is_err());
+    }
+}
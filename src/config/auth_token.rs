@@ -0,0 +1,192 @@
+use std::env;
+use std::process::Command;
+
+use crypto::aead::{AeadEncryptor, AeadDecryptor};
+use crypto::chacha20poly1305::ChaCha20Poly1305;
+use crypto::scrypt::{scrypt, ScryptParams};
+use rand::{OsRng, Rng};
+use rpassword;
+use rustc_serialize::hex::{FromHex, ToHex};
+
+use config::Config;
+use config::error::ConfigError;
+
+const AUTH_TOKEN_ENV_VAR: &'static str = "TELLER_AUTH_TOKEN";
+const PASSPHRASE_ENV_VAR: &'static str = "TELLER_PASSPHRASE";
+
+// 2^14 iterations of scrypt takes around 50ms, which is unnoticeable once per
+// command but makes guessing a passphrase expensive.
+const SCRYPT_LOG_N: u8 = 14;
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 1;
+
+const KEY_LENGTH: usize = 32;
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 8;
+const TAG_LENGTH: usize = 16;
+
+/// An `auth_token` encrypted with a key derived from a passphrase. Each of the
+/// byte fields is stored as hex within the config.
+#[derive(Debug, Clone, PartialEq, RustcEncodable, RustcDecodable)]
+pub struct EncryptedAuthToken {
+    pub log_n: u8,
+    pub salt: String,
+    pub nonce: String,
+    pub ciphertext: String,
+    pub tag: String,
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], log_n: u8) -> Vec<u8> {
+    let mut key = vec![0u8; KEY_LENGTH];
+    scrypt(passphrase.as_bytes(),
+           &salt,
+           &ScryptParams::new(log_n, SCRYPT_R, SCRYPT_P),
+           &mut key);
+    key
+}
+
+fn from_hex(field: &str) -> Result<Vec<u8>, ConfigError> {
+    field.from_hex().map_err(|_| {
+        ConfigError::AuthTokenError("The `encrypted_auth_token` is corrupt".to_string())
+    })
+}
+
+fn encrypt_auth_token_with_log_n(auth_token: &str,
+                                 passphrase: &str,
+                                 log_n: u8)
+                                 -> Result<EncryptedAuthToken, ConfigError> {
+    let mut rng = try!(OsRng::new());
+    let mut salt = vec![0u8; SALT_LENGTH];
+    rng.fill_bytes(&mut salt);
+    let mut nonce = vec![0u8; NONCE_LENGTH];
+    rng.fill_bytes(&mut nonce);
+
+    let key = derive_key(&passphrase, &salt, log_n);
+    let mut ciphertext = vec![0u8; auth_token.len()];
+    let mut tag = vec![0u8; TAG_LENGTH];
+    ChaCha20Poly1305::new(&key, &nonce, &[]).encrypt(auth_token.as_bytes(),
+                                                     &mut ciphertext,
+                                                     &mut tag);
+
+    Ok(EncryptedAuthToken {
+        log_n: log_n,
+        salt: salt.to_hex(),
+        nonce: nonce.to_hex(),
+        ciphertext: ciphertext.to_hex(),
+        tag: tag.to_hex(),
+    })
+}
+
+/// Encrypts an `auth_token` with a key derived from `passphrase` by scrypt.
+pub fn encrypt_auth_token(auth_token: &str,
+                          passphrase: &str)
+                          -> Result<EncryptedAuthToken, ConfigError> {
+    encrypt_auth_token_with_log_n(&auth_token, &passphrase, SCRYPT_LOG_N)
+}
+
+/// Decrypts an `auth_token`, erroring if the passphrase is incorrect.
+pub fn decrypt_auth_token(encrypted_auth_token: &EncryptedAuthToken,
+                          passphrase: &str)
+                          -> Result<String, ConfigError> {
+    let salt = try!(from_hex(&encrypted_auth_token.salt));
+    let nonce = try!(from_hex(&encrypted_auth_token.nonce));
+    let ciphertext = try!(from_hex(&encrypted_auth_token.ciphertext));
+    let tag = try!(from_hex(&encrypted_auth_token.tag));
+    if nonce.len() != NONCE_LENGTH || tag.len() != TAG_LENGTH {
+        return Err(ConfigError::AuthTokenError("The `encrypted_auth_token` is corrupt"
+                                                   .to_string()));
+    }
+
+    let key = derive_key(&passphrase, &salt, encrypted_auth_token.log_n);
+    let mut auth_token = vec![0u8; ciphertext.len()];
+    if !ChaCha20Poly1305::new(&key, &nonce, &[]).decrypt(&ciphertext, &mut auth_token, &tag) {
+        return Err(ConfigError::AuthTokenError("The passphrase is incorrect".to_string()));
+    }
+
+    String::from_utf8(auth_token).map_err(|_| {
+        ConfigError::AuthTokenError("The `encrypted_auth_token` is corrupt".to_string())
+    })
+}
+
+/// The command is run through the shell so that it can be anything a user
+/// would type, e.g. `pass teller/token | head -n1`.
+pub fn run_auth_token_command(auth_token_command: &str) -> Result<String, ConfigError> {
+    info!("Running the `auth_token_command`");
+    let output = try!(Command::new("sh").arg("-c").arg(auth_token_command).output());
+    if !output.status.success() {
+        return Err(ConfigError::AuthTokenError(format!("`{}` exited with {}",
+                                                       auth_token_command,
+                                                       output.status)));
+    }
+
+    let auth_token = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if auth_token.is_empty() {
+        Err(ConfigError::AuthTokenError(format!("`{}` did not print an `auth_token`",
+                                                auth_token_command)))
+    } else {
+        Ok(auth_token)
+    }
+}
+
+/// Reads the passphrase from `TELLER_PASSPHRASE`, or asks for it without echoing it.
+pub fn ask_passphrase(prompt: &str) -> Result<String, ConfigError> {
+    match env::var(PASSPHRASE_ENV_VAR) {
+        Ok(passphrase) => Ok(passphrase),
+        Err(_) => rpassword::prompt_password_stdout(&prompt).map_err(ConfigError::IoError),
+    }
+}
+
+/// The passphrase within `TELLER_PASSPHRASE`, if it is set.
+pub fn get_passphrase_from_env() -> Option<String> {
+    match env::var(PASSPHRASE_ENV_VAR) {
+        Ok(ref passphrase) if !passphrase.is_empty() => Some(passphrase.to_owned()),
+        _ => None,
+    }
+}
+
+/// The `auth_token` within `TELLER_AUTH_TOKEN`, if it is set.
+pub fn get_auth_token_from_env() -> Option<String> {
+    match env::var(AUTH_TOKEN_ENV_VAR) {
+        Ok(ref auth_token) if !auth_token.is_empty() => Some(auth_token.to_owned()),
+        _ => None,
+    }
+}
+
+/// Sets the `auth_token` of a config read from disk, preferring the environment
+/// over a password manager over the encrypted token over the plain text token.
+pub fn resolve_auth_token(mut config: Config) -> Result<Config, ConfigError> {
+    if let Some(auth_token) = get_auth_token_from_env() {
+        config.auth_token = auth_token;
+    } else if let Some(ref auth_token_command) = config.auth_token_command {
+        config.auth_token = try!(run_auth_token_command(&auth_token_command));
+    } else if let Some(ref encrypted_auth_token) = config.encrypted_auth_token {
+        let passphrase = try!(ask_passphrase("Passphrase for your `auth_token`: "));
+        config.auth_token = try!(decrypt_auth_token(&encrypted_auth_token, &passphrase));
+    }
+
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encrypt_auth_token_with_log_n, decrypt_auth_token, run_auth_token_command};
+
+    #[test]
+    fn can_encrypt_and_decrypt_auth_token() {
+        // A low cost keeps the test fast.
+        let encrypted_auth_token = encrypt_auth_token_with_log_n("fake-auth-token", "hunter2", 4)
+                                       .unwrap();
+
+        assert_eq!("fake-auth-token",
+                   decrypt_auth_token(&encrypted_auth_token, "hunter2").unwrap());
+        assert_eq!(true, decrypt_auth_token(&encrypted_auth_token, "hunter3").is_err());
+    }
+
+    #[test]
+    fn can_run_auth_token_command() {
+        assert_eq!("fake-auth-token",
+                   run_auth_token_command("echo fake-auth-token").unwrap());
+        assert_eq!(true, run_auth_token_command("exit 1").is_err());
+        assert_eq!(true, run_auth_token_command("true").is_err());
+    }
+}
@@ -6,11 +6,16 @@ use std::fmt::{Display, Formatter, Result as FormatResult};
 use std::io::Error as StdIoError;
 use rustc_serialize::json::{EncoderError, DecoderError};
 
+/// Why a config (or a file kept alongside it) could not be read or written.
 #[derive(Debug)]
 pub enum ConfigError {
     IoError(StdIoError),
     JsonParseError(DecoderError),
     JsonStringifyError(EncoderError),
+    AuthTokenError(String),
+    MigrationError(String),
+    LockError(String),
+    InitError(String),
 }
 
 impl Display for ConfigError {
@@ -43,6 +48,10 @@ impl StdError for ConfigError {
             ConfigError::IoError(ref err) => err.description(),
             ConfigError::JsonParseError(ref err) => err.description(),
             ConfigError::JsonStringifyError(ref err) => err.description(),
+            ConfigError::AuthTokenError(ref message) => message,
+            ConfigError::MigrationError(ref message) => message,
+            ConfigError::LockError(ref message) => message,
+            ConfigError::InitError(ref message) => message,
         }
     }
 
@@ -51,6 +60,7 @@ impl StdError for ConfigError {
             ConfigError::IoError(ref err) => err.cause(),
             ConfigError::JsonParseError(ref err) => err.cause(),
             ConfigError::JsonStringifyError(ref err) => err.cause(),
+            _ => None,
         }
     }
 }
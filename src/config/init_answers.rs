@@ -0,0 +1,178 @@
+use rustc_serialize::json;
+use std::path::PathBuf;
+use std::fs::File;
+
+use std::io::prelude::*; // Required for Read, Write, etc.
+
+use config::Config;
+use config::error::ConfigError;
+
+use api::Account;
+
+/// The answers to `teller init` given without a TTY, through `--auth-token`,
+/// `--alias` or an answers file such as:
+///
+/// ```json
+/// {"auth_token": "...", "current": "2", "savings": "<account id>"}
+/// ```
+///
+/// Each alias is either an account id or a row of `teller list accounts`.
+#[derive(Debug, Clone, Default, RustcDecodable)]
+pub struct InitAnswers {
+    pub auth_token: Option<String>,
+    pub current: Option<String>,
+    pub savings: Option<String>,
+    pub business: Option<String>,
+}
+
+impl InitAnswers {
+    /// Whether no answers were given, in which case `teller init` asks questions.
+    pub fn is_empty(&self) -> bool {
+        self.auth_token.is_none() && self.current.is_none() && self.savings.is_none() &&
+        self.business.is_none()
+    }
+
+    /// Sets an alias from a `--alias` flag such as `current=2`.
+    pub fn set_alias(&mut self, alias_flag: &str) -> Result<(), ConfigError> {
+        let mut parts = alias_flag.splitn(2, '=');
+        let alias = parts.next().unwrap_or("").trim();
+        let value = match parts.next() {
+            Some(value) => Some(value.trim().to_string()),
+            None => {
+                return Err(ConfigError::InitError(format!("`{}` should look like \
+                                                           `current=<id|row>`",
+                                                          alias_flag)))
+            }
+        };
+        match alias {
+            "current" => self.current = value,
+            "savings" => self.savings = value,
+            "business" => self.business = value,
+            _ => {
+                return Err(ConfigError::InitError(format!("`{}` is not an alias. Use current, \
+                                                           savings or business",
+                                                          alias)))
+            }
+        }
+        Ok(())
+    }
+
+    /// A profile with these answers, whose aliases are looked up within `accounts`.
+    pub fn to_config(&self, accounts: &Vec<Account>) -> Result<Config, ConfigError> {
+        let auth_token = match self.auth_token {
+            Some(ref auth_token) if !auth_token.is_empty() => auth_token.to_owned(),
+            _ => return Err(ConfigError::InitError("An `auth_token` is needed".to_string())),
+        };
+        let mut config = Config::new_with_auth_token_only(auth_token);
+        config.current = try!(to_account_id("current", &self.current, &accounts));
+        config.savings = try!(to_account_id("savings", &self.savings, &accounts));
+        config.business = try!(to_account_id("business", &self.business, &accounts));
+        Ok(config)
+    }
+}
+
+/// The account id which an answer for `alias` refers to, or an empty id without an
+/// answer.
+pub fn to_account_id(alias: &str,
+                     value: &Option<String>,
+                     accounts: &Vec<Account>)
+                     -> Result<String, ConfigError> {
+    let value = match *value {
+        Some(ref value) if !value.is_empty() => value,
+        _ => return Ok("".to_string()),
+    };
+    if let Some(account) = accounts.iter().find(|account| account.id == *value) {
+        return Ok(account.id.to_owned());
+    }
+    match value.parse::<usize>() {
+        Ok(row) if row >= 1 && row <= accounts.len() => Ok(accounts[row - 1].id.to_owned()),
+        _ => {
+            Err(ConfigError::InitError(format!("`{}` for the {} alias is neither an account id \
+                                                nor a row between 1 and {}",
+                                               value,
+                                               alias,
+                                               accounts.len())))
+        }
+    }
+}
+
+/// Reads answers from JSON.
+pub fn read_init_answers<R>(init_answers_file: &mut R) -> Result<InitAnswers, ConfigError>
+    where R: Read {
+    let mut content_str = String::new();
+    try!(init_answers_file.read_to_string(&mut content_str));
+
+    let init_answers: InitAnswers = try!(json::decode(&content_str));
+
+    Ok(init_answers)
+}
+
+/// Reads answers from a JSON file.
+pub fn get_init_answers(init_answers_path: &PathBuf) -> Result<InitAnswers, ConfigError> {
+    let mut init_answers_file = try!(File::open(&init_answers_path));
+    read_init_answers(&mut init_answers_file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InitAnswers;
+
+    use api::Account;
+
+    use std::io::Cursor;
+    use super::read_init_answers;
+
+    fn to_account(id: &str) -> Account {
+        Account {
+            account_number: "00000000".to_string(),
+            balance: "1000.00".to_string(),
+            bank_code: "000000".to_string(),
+            currency: "GBP".to_string(),
+            id: id.to_string(),
+            institution: "natwest".to_string(),
+            name: "Current".to_string(),
+        }
+    }
+
+    #[test]
+    fn can_set_aliases_to_account_ids_or_rows() {
+        let accounts = vec![to_account("savings-id"), to_account("current-id")];
+        let mut init_answers = InitAnswers::default();
+        init_answers.auth_token = Some("auth-token".to_string());
+        init_answers.set_alias("current=2").unwrap();
+        init_answers.set_alias("savings=savings-id").unwrap();
+
+        let config = init_answers.to_config(&accounts).unwrap();
+
+        assert_eq!("auth-token", config.auth_token);
+        assert_eq!("current-id", config.current);
+        assert_eq!("savings-id", config.savings);
+        assert_eq!("", config.business);
+    }
+
+    #[test]
+    fn can_reject_invalid_aliases() {
+        let accounts = vec![to_account("current-id")];
+        let mut init_answers = InitAnswers::default();
+        init_answers.auth_token = Some("auth-token".to_string());
+
+        assert_eq!(true, init_answers.set_alias("checking=1").is_err());
+        assert_eq!(true, init_answers.set_alias("current").is_err());
+
+        init_answers.set_alias("current=0").unwrap();
+        assert_eq!(true, init_answers.to_config(&accounts).is_err());
+        init_answers.set_alias("current=other-id").unwrap();
+        assert_eq!(true, init_answers.to_config(&accounts).is_err());
+    }
+
+    #[test]
+    fn can_read_init_answers() {
+        let mut reader = Cursor::new(&b"{\"auth_token\":\"auth-token\",\"current\":\"1\"}"[..]);
+
+        let init_answers = read_init_answers(&mut reader).unwrap();
+
+        assert_eq!(Some("auth-token".to_string()), init_answers.auth_token);
+        assert_eq!(Some("1".to_string()), init_answers.current);
+        assert_eq!(None, init_answers.savings);
+    }
+}
@@ -0,0 +1,126 @@
+use rustc_serialize::json::{Json, Object};
+use std::path::PathBuf;
+
+use config::error::ConfigError;
+
+/// The version of config which this teller writes.
+pub const CONFIG_VERSION: u64 = 2;
+
+const VERSION_KEY: &'static str = "version";
+
+/// Configs written before the `version` field existed are version 1.
+pub fn get_config_version(config_json: &Json) -> u64 {
+    config_json.find(VERSION_KEY).and_then(|version| version.as_u64()).unwrap_or(1)
+}
+
+fn fill_missing_strings(config: &mut Object, keys: &[&str]) {
+    for key in keys.iter() {
+        if !config.contains_key(*key) {
+            config.insert(key.to_string(), Json::String("".to_string()));
+        }
+    }
+}
+
+// Version 1 was the flat `auth_token/current/savings/business` layout, which
+// could be written by hand without some of its aliases.
+fn migrate_flat_config(config: &mut Object) {
+    let keys = ["auth_token", "current", "savings", "business"];
+    fill_missing_strings(config, &keys);
+    if let Some(&mut Json::Object(ref mut profiles)) = config.get_mut("profiles") {
+        for (_, profile) in profiles.iter_mut() {
+            if let Json::Object(ref mut profile) = *profile {
+                fill_missing_strings(profile, &keys);
+            }
+        }
+    }
+}
+
+// Each migration upgrades a config from the version it is listed against.
+const MIGRATIONS: [(u64, fn(&mut Object)); 1] = [(1, migrate_flat_config)];
+
+/// Upgrades a config from its version to `CONFIG_VERSION`.
+pub fn migrate_config_json(config_json: Json) -> Result<Json, ConfigError> {
+    let mut version = get_config_version(&config_json);
+    let mut config = match config_json {
+        Json::Object(config) => config,
+        _ => return Err(ConfigError::MigrationError("The config is not an object".to_string())),
+    };
+
+    for &(from_version, migrate) in MIGRATIONS.iter() {
+        if version == from_version {
+            info!("Migrating config from version {}", from_version);
+            migrate(&mut config);
+            version = from_version + 1;
+        }
+    }
+    config.insert(VERSION_KEY.to_string(), Json::U64(version));
+
+    Ok(Json::Object(config))
+}
+
+/// Marks a config as `CONFIG_VERSION`.
+pub fn set_config_version(config_json: &mut Json) {
+    if let Json::Object(ref mut config) = *config_json {
+        config.insert(VERSION_KEY.to_string(), Json::U64(CONFIG_VERSION));
+    }
+}
+
+/// Fields written by a newer teller, or added by hand, are carried over from
+/// the previous config so that rewriting it does not lose them.
+pub fn merge_unknown_fields(config_json: &mut Json, previous_config_json: &Json) {
+    if let (&mut Json::Object(ref mut config), &Json::Object(ref previous_config)) =
+           (config_json, previous_config_json) {
+        for (key, previous_value) in previous_config.iter() {
+            if !config.contains_key(key) {
+                config.insert(key.to_owned(), previous_value.clone());
+            } else if let Some(value) = config.get_mut(key) {
+                merge_unknown_fields(value, &previous_value);
+            }
+        }
+    }
+}
+
+/// `<config>.v<version>.bak`, which keeps a config from before it was migrated.
+pub fn get_backup_path(config_path: &PathBuf, version: u64) -> PathBuf {
+    PathBuf::from(format!("{}.v{}.bak", config_path.display(), version))
+}
+
+#[cfg(test)]
+mod tests {
+    use rustc_serialize::json::Json;
+
+    use super::{CONFIG_VERSION, get_config_version, migrate_config_json, merge_unknown_fields};
+
+    #[test]
+    fn can_migrate_flat_config() {
+        let config_json = Json::from_str("{\"auth_token\":\"auth-token\",\"current\":\"current-id\",\
+                                          \"theme\":\"dark\"}")
+                              .unwrap();
+        assert_eq!(1, get_config_version(&config_json));
+
+        let migrated_config_json = migrate_config_json(config_json).unwrap();
+
+        assert_eq!(CONFIG_VERSION, get_config_version(&migrated_config_json));
+        assert_eq!(Some(&Json::String("".to_string())), migrated_config_json.find("savings"));
+        assert_eq!(Some(&Json::String("dark".to_string())), migrated_config_json.find("theme"));
+    }
+
+    #[test]
+    fn can_merge_unknown_fields() {
+        let mut config_json = Json::from_str("{\"auth_token\":\"new-auth-token\",\
+                                              \"profiles\":{\"company\":{\"current\":\"id\"}}}")
+                                  .unwrap();
+        let previous_config_json = Json::from_str("{\"auth_token\":\"old-auth-token\",\
+                                                   \"theme\":\"dark\",\"profiles\":{\"company\":\
+                                                   {\"current\":\"id\",\"colour\":\"red\"}}}")
+                                       .unwrap();
+
+        merge_unknown_fields(&mut config_json, &previous_config_json);
+
+        assert_eq!(Some(&Json::String("new-auth-token".to_string())),
+                   config_json.find("auth_token"));
+        assert_eq!(Some(&Json::String("dark".to_string())), config_json.find("theme"));
+        assert_eq!(Some(&Json::String("red".to_string())),
+                   config_json.find_path(&["profiles", "company", "colour"]));
+    }
+}
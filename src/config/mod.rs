@@ -1,36 +1,77 @@
 pub mod error;
-
-use rustc_serialize::json;
+pub mod watch_cursor;
+pub mod auth_token;
+pub mod migration;
+pub mod atomic_write;
+pub mod init_answers;
+
+use rustc_serialize::Decodable;
+use rustc_serialize::json::{self, Json};
+use std::collections::BTreeMap;
 use std::env;
 use std::path::PathBuf;
 use std::fs::File;
-use std::io::Error as StdIoError;
+use std::io;
 use std::io::ErrorKind;
 
 use std::io::prelude::*; // Required for Read, Write, etc.
 
 use config::error::ConfigError;
+use config::auth_token::EncryptedAuthToken;
+use config::atomic_write::{ConfigLock, copy_privately, lock_config, write_atomically};
+use config::migration::{CONFIG_VERSION, get_config_version, migrate_config_json,
+                        set_config_version, merge_unknown_fields, get_backup_path};
 
 use cli::arg_types::AccountType;
+use locale::Locale;
 
-#[derive(Debug, RustcEncodable, RustcDecodable)]
+/// The `auth_token` and account aliases of a profile, as kept within the
+/// config file. The top level of the file is the `default` profile.
+#[derive(Debug, Clone, RustcEncodable, RustcDecodable)]
 pub struct Config {
     pub auth_token: String,
     pub current: String,
     pub savings: String,
     pub business: String,
+    pub checks: Option<Vec<String>>,
+    pub auth_token_command: Option<String>,
+    pub encrypted_auth_token: Option<EncryptedAuthToken>,
+    pub profiles: Option<BTreeMap<String, Config>>,
+    /// Any aliases besides `current`, `savings` and `business`, such as
+    /// those given to imported accounts.
+    pub aliases: Option<BTreeMap<String, String>>,
+    /// The account of the books that spending at a counterparty belongs to,
+    /// e.g. `"NANNA'S": "Expenses:Coffee"`.
+    pub categories: Option<BTreeMap<String, String>>,
+    /// How amounts and dates are displayed, e.g. `{"name": "en_GB"}`.
+    pub locale: Option<Locale>,
+    /// How long responses of the Teller API are kept on disk and reused, e.g. `"10m"`.
+    pub cache: Option<String>,
 }
 
+/// The profile at the top level of the config.
+pub const DEFAULT_PROFILE: &'static str = "default";
+
 impl Config {
+    /// A profile with an `auth_token` and the accounts of its three usual aliases.
     pub fn new<S: Into<String>>(auth_token: S, current: S, savings: S, business: S) -> Config {
         Config {
             auth_token: auth_token.into(),
             current: current.into(),
             savings: savings.into(),
             business: business.into(),
+            checks: None,
+            auth_token_command: None,
+            encrypted_auth_token: None,
+            profiles: None,
+            aliases: None,
+            categories: None,
+            locale: None,
+            cache: None,
         }
     }
 
+    /// A profile with an `auth_token` and no aliases.
     pub fn new_with_auth_token_only<S: Into<String>>(auth_token: S) -> Config {
         Config::new(auth_token.into(),
                     "".to_string(),
@@ -38,38 +79,223 @@ impl Config {
                     "".to_string())
     }
 
+    /// The top level of the config is the default profile, while any other
+    /// profiles are kept within `profiles`.
+    pub fn get_profile(&self, profile_name: &str) -> Option<Config> {
+        let profile = if profile_name == DEFAULT_PROFILE {
+            Some(self.clone())
+        } else {
+            self.profiles.as_ref().and_then(|profiles| profiles.get(profile_name)).cloned()
+        };
+        profile.map(|mut profile| {
+            profile.profiles = None;
+            profile
+        })
+    }
+
+    /// Adds or replaces a profile, keeping the other profiles.
+    pub fn set_profile(&mut self, profile_name: &str, mut profile: Config) {
+        if profile_name == DEFAULT_PROFILE {
+            profile.profiles = self.profiles.take();
+            *self = profile;
+        } else {
+            profile.profiles = None;
+            if self.profiles.is_none() {
+                self.profiles = Some(BTreeMap::new());
+            }
+            if let Some(ref mut profiles) = self.profiles {
+                profiles.insert(profile_name.to_string(), profile);
+            }
+        }
+    }
+
+    /// The account id of an alias, which is the current account for an unknown alias.
     pub fn get_account_id(&self, account: &AccountType) -> String {
         let default_account_id = self.current.to_owned();
         match *account {
             AccountType::Current => self.current.to_owned(),
             AccountType::Savings => self.savings.to_owned(),
             AccountType::Business => self.business.to_owned(),
+            AccountType::Unknown(ref alias) => {
+                self.aliases
+                    .as_ref()
+                    .and_then(|aliases| aliases.get(alias))
+                    .cloned()
+                    .unwrap_or(default_account_id)
+            }
             _ => default_account_id,
         }
     }
 
-    pub fn get_account_alias_for_id<'a>(&self, account_id: &str) -> &'a str {
-        if *account_id == self.current {
-            "(current)"
-        } else if *account_id == self.savings {
-            "(savings)"
-        } else if *account_id == self.business {
-            "(business)"
-        } else {
-            ""
+    /// Every alias which is set, alongside its account id.
+    pub fn get_account_aliases(&self) -> Vec<(String, String)> {
+        let mut account_aliases: Vec<(String, String)> =
+            vec![("current", &self.current), ("savings", &self.savings), ("business", &self.business)]
+                .into_iter()
+                .filter(|&(_, account_id)| !account_id.is_empty())
+                .map(|(alias, account_id)| (alias.to_string(), account_id.to_owned()))
+                .collect();
+        if let Some(ref aliases) = self.aliases {
+            account_aliases.extend(aliases.iter()
+                                          .map(|(alias, account_id)| {
+                                              (alias.to_owned(), account_id.to_owned())
+                                          }));
+        }
+        account_aliases
+    }
+
+    /// Points an alias at an account, adding it if it is not one of the usual three.
+    pub fn set_account_alias(&mut self, alias: &str, account_id: &str) {
+        match alias {
+            "current" => self.current = account_id.to_string(),
+            "savings" => self.savings = account_id.to_string(),
+            "business" => self.business = account_id.to_string(),
+            _ => {
+                if self.aliases.is_none() {
+                    self.aliases = Some(BTreeMap::new());
+                }
+                if let Some(ref mut aliases) = self.aliases {
+                    aliases.insert(alias.to_string(), account_id.to_string());
+                }
+            }
         }
     }
+
+    /// The alias of an account, such as `(current)`, or an empty string without one.
+    pub fn get_account_alias_for_id(&self, account_id: &str) -> String {
+        self.get_account_aliases()
+            .into_iter()
+            .find(|&(_, ref id)| id == account_id)
+            .map(|(alias, _)| format!("({})", alias))
+            .unwrap_or("".to_string())
+    }
+
+    /// A category applies to every counterparty whose name contains it, and
+    /// the longest one wins so that `TESCO PETROL` can be told from `TESCO`.
+    pub fn get_category(&self, counterparty: &str) -> Option<String> {
+        let counterparty = counterparty.to_lowercase();
+        self.categories.as_ref().and_then(|categories| {
+            categories.iter()
+                      .filter(|&(name, _)| {
+                          !name.is_empty() && counterparty.contains(&name.to_lowercase())
+                      })
+                      .max_by_key(|&(name, _)| name.len())
+                      .map(|(_, category)| category.to_owned())
+        })
+    }
+}
+
+const CONFIG_ENV_VAR: &'static str = "TELLER_CONFIG";
+const PROFILE_ENV_VAR: &'static str = "TELLER_PROFILE";
+
+/// The profile from `--profile`, then `$TELLER_PROFILE`, then `default`.
+pub fn get_profile_name(profile_flag: &str) -> String {
+    if !profile_flag.is_empty() {
+        return profile_flag.to_string();
+    }
+    match env::var(PROFILE_ENV_VAR) {
+        Ok(ref profile_name) if !profile_name.is_empty() => profile_name.to_owned(),
+        _ => DEFAULT_PROFILE.to_string(),
+    }
 }
 
-pub fn get_config_path() -> PathBuf {
+/// The config is discovered in this order:
+///   1. `--config=<path>`
+///   2. `$TELLER_CONFIG`
+///   3. `$XDG_CONFIG_HOME/teller/config` (or `~/.config/teller/config`), if it exists
+///   4. `~/.tellerrc` (or `./.tellerrc` without a home directory)
+pub fn get_config_path(config_flag: &str) -> PathBuf {
+    let get_var = |name: &str| {
+        env::var(name).ok().and_then(|value| if value.is_empty() { None } else { Some(value) })
+    };
+    find_config_path(&config_flag, get_var, env::home_dir())
+}
+
+fn find_config_path<F>(config_flag: &str, get_var: F, home_dir: Option<PathBuf>) -> PathBuf
+    where F: Fn(&str) -> Option<String> {
+    if !config_flag.is_empty() {
+        return PathBuf::from(config_flag);
+    }
+    if let Some(config_path) = get_var(CONFIG_ENV_VAR) {
+        return PathBuf::from(config_path);
+    }
+
+    let xdg_config_home = match get_var("XDG_CONFIG_HOME") {
+        Some(xdg_config_home) => Some(PathBuf::from(xdg_config_home)),
+        None => home_dir.clone().map(|mut p| {
+            p.push(".config");
+            p
+        }),
+    };
+    if let Some(mut xdg_config_path) = xdg_config_home {
+        xdg_config_path.push("teller");
+        xdg_config_path.push("config");
+        if xdg_config_path.exists() {
+            return xdg_config_path;
+        }
+    }
+
     let fallback_config_path = PathBuf::from("./.tellerrc");
     let append_config_file = |mut p: PathBuf| {
         p.push(".tellerrc");
         p
     };
-    env::home_dir().map_or(fallback_config_path, append_config_file)
+    home_dir.map_or(fallback_config_path, append_config_file)
+}
+
+// Shells only allow letters, digits and underscores within names, so an alias
+// such as `joint-savings` is overridden by `TELLER_ACCOUNT_JOINT_SAVINGS`.
+fn to_account_env_var(alias: &str) -> String {
+    let name: String = alias.to_uppercase()
+                            .chars()
+                            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+                            .collect();
+    format!("TELLER_ACCOUNT_{}", name)
+}
+
+fn apply_overrides<F>(mut config: Config, get_var: F) -> Config
+    where F: Fn(&str) -> Option<String> {
+    let mut aliases = vec!["current".to_string(), "savings".to_string(), "business".to_string()];
+    if let Some(ref config_aliases) = config.aliases {
+        aliases.extend(config_aliases.keys().cloned());
+    }
+    for alias in aliases {
+        if let Some(account_id) = get_var(&to_account_env_var(&alias)) {
+            config.set_account_alias(&alias, &account_id);
+        }
+    }
+    config
+}
+
+/// Account ids can be given by `TELLER_ACCOUNT_<ALIAS>`, e.g. in CI jobs which
+/// do not have a config file. `current`, `savings` and `business` can always be
+/// given, while any other alias must already be within the config. They are
+/// never written back to the config.
+pub fn apply_env_overrides(config: Config) -> Config {
+    apply_overrides(config, |name| env::var(name).ok().and_then(|value| {
+        if value.is_empty() {
+            None
+        } else {
+            Some(value)
+        }
+    }))
 }
 
+#[cfg(unix)]
+fn is_readable_by_others(config_file: &File) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    config_file.metadata()
+               .map(|metadata| metadata.permissions().mode() & 0o044 != 0)
+               .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_readable_by_others(_config_file: &File) -> bool {
+    false
+}
+
+/// Opens the config, warning if other users can read it, or `None` when there is no
+/// file. Panics if it cannot be opened.
 pub fn get_config_file(config_path: &PathBuf) -> Option<File> {
     let config_path_str = config_path.to_str().unwrap_or("[error: config_path#to_str fails]");
     info!("Checking whether config file within {} exists",
@@ -81,25 +307,26 @@ pub fn get_config_file(config_path: &PathBuf) -> Option<File> {
             None
         }
         Err(_) => panic!("Unable to read config!"),
-        Ok(config_file) => Some(config_file),
-    }
-}
-
-pub fn get_config_file_to_write(config_path: &PathBuf) -> Result<File, StdIoError> {
-    let config_file = File::create(&config_path);
-    match config_file {
-        Err(ref e) if ErrorKind::PermissionDenied == e.kind() => {
-            panic!("Permission to read config denied")
+        Ok(config_file) => {
+            if is_readable_by_others(&config_file) {
+                let _ = writeln!(io::stderr(),
+                                 "WARNING: {} can be read by other users. Run `chmod 600 {}` \
+                                  to protect your `auth_token`.",
+                                 config_path_str,
+                                 config_path_str);
+            }
+            Some(config_file)
         }
-        _ => config_file,
     }
 }
 
-pub fn get_config() -> Option<Config> {
-    let config_file_path = get_config_path();
+/// Reads the config at a path, or `None` when there is no file there. The
+/// `auth_token` is not resolved; see `auth_token::resolve_auth_token`.
+pub fn get_config(config_file_path: &PathBuf) -> Option<Config> {
     match get_config_file(&config_file_path) {
         None => None,
         Some(mut config_file) => {
+            upgrade_config_file(&config_file_path);
             match read_config(&mut config_file) {
                 Ok(config) => Some(config),
                 Err(e) => {
@@ -112,25 +339,155 @@ pub fn get_config() -> Option<Config> {
     }
 }
 
-pub fn read_config<R>(config_file: &mut R) -> Result<Config, ConfigError>
+// An older config is migrated and saved, after a copy of it is kept as
+// `<config>.v<version>.bak`. If it cannot be saved it is still migrated
+// whenever it is read.
+fn upgrade_config_file(config_file_path: &PathBuf) {
+    let config_json = match File::open(&config_file_path)
+                                .map_err(ConfigError::from)
+                                .and_then(|mut config_file| read_config_json(&mut config_file)) {
+        Ok(config_json) => config_json,
+        Err(_) => return,
+    };
+    let version = get_config_version(&config_json);
+    if version > CONFIG_VERSION {
+        let _ = writeln!(io::stderr(),
+                         "WARNING: {} was written by a newer version of teller (config version \
+                          {}).",
+                         config_file_path.display(),
+                         version);
+    }
+    if version >= CONFIG_VERSION {
+        return;
+    }
+
+    let backup_path = get_backup_path(&config_file_path, version);
+    let saved = migrate_config_json(config_json).and_then(|config_json| {
+        let lock = try!(lock_config(&config_file_path));
+        try!(copy_privately(&config_file_path, &backup_path));
+        let mut content = vec![];
+        try!(write_config_json(&mut content, &config_json));
+        write_atomically(&config_file_path, &lock, &content)
+    });
+    match saved {
+        Ok(_) => {
+            info!("Migrated {} to config version {} (the original is within {})",
+                  config_file_path.display(),
+                  CONFIG_VERSION,
+                  backup_path.display())
+        }
+        Err(e) => error!("Unable to save the migrated config: {}", e),
+    }
+}
+
+fn read_config_json<R>(config_file: &mut R) -> Result<Json, ConfigError>
     where R: Read {
     let mut content_str = String::new();
     try!(config_file.read_to_string(&mut content_str));
 
-    debug!("config read as: {}", content_str);
+    let config_json = try!(Json::from_str(&content_str).map_err(json::DecoderError::ParseError));
+
+    Ok(config_json)
+}
+
+/// Reads a config from JSON, migrating it from an older version.
+pub fn read_config<R>(config_file: &mut R) -> Result<Config, ConfigError>
+    where R: Read {
+    let config_json = try!(migrate_config_json(try!(read_config_json(config_file))));
 
-    let config: Config = try!(json::decode(&content_str));
+    let mut decoder = json::Decoder::new(config_json);
+    let config: Config = try!(Decodable::decode(&mut decoder));
 
     Ok(config)
 }
 
+// A token which came from a password manager or was decrypted must never be
+// written back to the config as plain text.
+fn without_resolved_auth_tokens(config: &Config) -> Config {
+    let mut config = config.clone();
+    if config.auth_token_command.is_some() || config.encrypted_auth_token.is_some() {
+        config.auth_token = "".to_string();
+    }
+    config.profiles = config.profiles.map(|profiles| {
+        profiles.iter()
+                .map(|(name, profile)| (name.to_owned(), without_resolved_auth_tokens(&profile)))
+                .collect()
+    });
+    config
+}
+
+fn to_config_json(config: &Config) -> Result<Json, ConfigError> {
+    let content_str = try!(json::encode(&without_resolved_auth_tokens(&config)));
+    let mut config_json = try!(Json::from_str(&content_str).map_err(json::DecoderError::ParseError));
+    set_config_version(&mut config_json);
+
+    Ok(config_json)
+}
+
+fn write_config_json<W>(config_file: &mut W, config_json: &Json) -> Result<(), ConfigError>
+    where W: Write {
+    try!(config_file.write_all(config_json.to_string().as_bytes()));
+
+    Ok(())
+}
+
+/// Writes a config as JSON, without any `auth_token` which came from a command or was
+/// decrypted.
 pub fn write_config<W>(config_file: &mut W, config: &Config) -> Result<(), ConfigError>
     where W: Write {
-    let content_str = try!(json::encode(&config));
+    write_config_json(config_file, &try!(to_config_json(&config)))
+}
 
-    try!(config_file.write_all(content_str.as_bytes()));
+/// Writes the config to a path. Any fields within the existing config that
+/// this version of teller does not know about are kept.
+pub fn save_config(config_file_path: &PathBuf, config: &Config) -> Result<(), ConfigError> {
+    let lock = try!(lock_config(&config_file_path));
+    let previous_config_json = File::open(&config_file_path)
+                                   .map_err(ConfigError::from)
+                                   .and_then(|mut config_file| read_config_json(&mut config_file));
+    save_config_with_lock(&config_file_path, &lock, &config, previous_config_json.ok())
+}
 
-    Ok(())
+/// Reads the config at a path (or `None` when there is no file there), changes
+/// it and writes it back while holding its lock, so that commands running at
+/// the same time cannot undo each other's changes.
+pub fn update_config<F>(config_file_path: &PathBuf, update: F) -> Result<(), ConfigError>
+    where F: FnOnce(Option<Config>) -> Result<Config, ConfigError> {
+    let lock = try!(lock_config(&config_file_path));
+    let previous_config_json = match File::open(&config_file_path) {
+        Ok(mut config_file) => Some(try!(read_config_json(&mut config_file))),
+        Err(ref e) if e.kind() == ErrorKind::NotFound => None,
+        Err(e) => return Err(ConfigError::from(e)),
+    };
+
+    let config = match previous_config_json {
+        Some(ref previous_config_json) => {
+            let config_json = try!(migrate_config_json(previous_config_json.clone()));
+            Some(try!(Decodable::decode(&mut json::Decoder::new(config_json))))
+        }
+        None => None,
+    };
+    let config = try!(update(config));
+
+    save_config_with_lock(&config_file_path, &lock, &config, previous_config_json)
+}
+
+fn save_config_with_lock(config_file_path: &PathBuf,
+                         lock: &ConfigLock,
+                         config: &Config,
+                         previous_config_json: Option<Json>)
+                         -> Result<(), ConfigError> {
+    let mut content = vec![];
+    match previous_config_json {
+        Some(previous_config_json) => {
+            let mut config_json = try!(to_config_json(&config));
+            merge_unknown_fields(&mut config_json, &previous_config_json);
+            try!(write_config_json(&mut content, &config_json));
+        }
+        None => try!(write_config(&mut content, &config)),
+    }
+
+    write_atomically(&config_file_path, &lock, &content)
 }
 
 #[cfg(test)]
@@ -138,10 +495,12 @@ mod tests {
     use super::Config;
 
     use cli::arg_types::AccountType;
+    use std::collections::BTreeMap;
 
     use std::env;
+    use std::fs;
     use std::path::PathBuf;
-    use super::get_config_path;
+    use super::{get_config, get_config_path, find_config_path, apply_overrides, update_config};
 
     use std::io::Cursor;
 
@@ -188,11 +547,155 @@ mod tests {
         assert_eq!("(savings)", config.get_account_alias_for_id(&expected_savings));
     }
 
+    #[test]
+    fn can_get_account_aliases_from_config() {
+        let config = Config::new("fake-auth-token", "current-id", "", "business-id");
+
+        assert_eq!(vec![("current".to_string(), "current-id".to_string()),
+                        ("business".to_string(), "business-id".to_string())],
+                   config.get_account_aliases());
+    }
+
+    #[test]
+    fn can_set_other_account_aliases() {
+        let mut config = Config::new("fake-auth-token", "current-id", "", "");
+        config.set_account_alias("savings", "savings-id");
+        config.set_account_alias("joint", "joint-id");
+
+        assert_eq!("joint-id",
+                   config.get_account_id(&AccountType::Unknown("joint".to_string())));
+        assert_eq!("current-id",
+                   config.get_account_id(&AccountType::Unknown("unknown".to_string())));
+        assert_eq!("(joint)", config.get_account_alias_for_id("joint-id"));
+        assert_eq!(vec![("current".to_string(), "current-id".to_string()),
+                        ("savings".to_string(), "savings-id".to_string()),
+                        ("joint".to_string(), "joint-id".to_string())],
+                   config.get_account_aliases());
+    }
+
+    #[test]
+    fn can_get_category_of_counterparty() {
+        let mut config = Config::new("fake-auth-token", "current-id", "", "");
+        assert_eq!(None, config.get_category("TESCO"));
+
+        let mut categories = BTreeMap::new();
+        categories.insert("tesco".to_string(), "Expenses:Groceries".to_string());
+        categories.insert("TESCO PETROL".to_string(), "Expenses:Car".to_string());
+        config.categories = Some(categories);
+
+        assert_eq!(Some("Expenses:Groceries".to_string()), config.get_category("TESCO STORES"));
+        assert_eq!(Some("Expenses:Car".to_string()), config.get_category("Tesco Petrol 123"));
+        assert_eq!(None, config.get_category("NANNA'S"));
+    }
+
     #[test]
     fn can_get_config_path() {
-        let config_path = get_config_path();
-        let home_dir = env::home_dir().unwrap_or(PathBuf::from("."));
-        assert_eq!(format!("{}/.tellerrc", home_dir.display()), config_path.to_str().unwrap());
+        let home_dir = env::temp_dir().join("teller-test-config-path");
+        let _ = fs::remove_dir_all(&home_dir);
+        let no_vars = |_: &str| None;
+
+        assert_eq!(home_dir.join(".tellerrc"),
+                   find_config_path("", &no_vars, Some(home_dir.clone())));
+        assert_eq!(PathBuf::from("./.tellerrc"), find_config_path("", &no_vars, None));
+        assert_eq!(PathBuf::from("/etc/teller/config"),
+                   find_config_path("",
+                                    |name| {
+                                        match name {
+                                            "TELLER_CONFIG" => Some("/etc/teller/config".to_string()),
+                                            _ => None,
+                                        }
+                                    },
+                                    Some(home_dir.clone())));
+
+        let xdg_config_path = home_dir.join(".config").join("teller").join("config");
+        fs::create_dir_all(xdg_config_path.parent().unwrap()).unwrap();
+        fs::File::create(&xdg_config_path).unwrap();
+        assert_eq!(xdg_config_path,
+                   find_config_path("", &no_vars, Some(home_dir.clone())));
+
+        let _ = fs::remove_dir_all(&home_dir);
+    }
+
+    #[test]
+    fn can_get_and_set_profiles() {
+        let mut config = Config::new("auth-token", "current-id", "savings-id", "business-id");
+        config.set_profile("company",
+                           Config::new("company-auth-token", "company-current-id", "", ""));
+        config.set_profile("default", Config::new("new-auth-token", "new-current-id", "", ""));
+
+        let default_profile = config.get_profile("default").unwrap();
+        assert_eq!("new-auth-token", default_profile.auth_token);
+        assert_eq!(true, default_profile.profiles.is_none());
+
+        let company_profile = config.get_profile("company").unwrap();
+        assert_eq!("company-auth-token", company_profile.auth_token);
+        assert_eq!("company-current-id", company_profile.current);
+
+        assert_eq!(true, config.get_profile("personal").is_none());
+    }
+
+    #[test]
+    fn can_update_config_keeping_other_profiles() {
+        let config_dir = env::temp_dir().join("teller-test-update-config");
+        let _ = fs::remove_dir_all(&config_dir);
+        fs::create_dir_all(&config_dir).unwrap();
+        let config_path = config_dir.join("config");
+
+        update_config(&config_path, |config| {
+            assert_eq!(true, config.is_none());
+            Ok(Config::new("auth-token", "current-id", "", ""))
+        })
+            .unwrap();
+        update_config(&config_path, |config| {
+            let mut config = config.unwrap();
+            config.set_profile("company", Config::new("company-auth-token", "", "", ""));
+            Ok(config)
+        })
+            .unwrap();
+
+        let config = get_config(&config_path).unwrap();
+        assert_eq!("auth-token", config.get_profile("default").unwrap().auth_token);
+        assert_eq!("company-auth-token",
+                   config.get_profile("company").unwrap().auth_token);
+
+        let _ = fs::remove_dir_all(&config_dir);
+    }
+
+    #[test]
+    fn can_get_config_path_from_flag() {
+        let config_path = get_config_path("/etc/teller/config");
+        assert_eq!("/etc/teller/config", config_path.to_str().unwrap());
+    }
+
+    #[test]
+    fn can_override_account_ids() {
+        let config = Config::new("auth-token", "current-id", "savings-id", "business-id");
+
+        let config = apply_overrides(config, |name| {
+            match name {
+                "TELLER_ACCOUNT_SAVINGS" => Some("other-savings-id".to_string()),
+                _ => None,
+            }
+        });
+
+        assert_eq!("current-id", config.current);
+        assert_eq!("other-savings-id", config.savings);
+    }
+
+    #[test]
+    fn can_override_account_ids_of_other_aliases() {
+        let mut config = Config::new("auth-token", "current-id", "savings-id", "business-id");
+        config.set_account_alias("joint-savings", "joint-savings-id");
+
+        let config = apply_overrides(config, |name| {
+            match name {
+                "TELLER_ACCOUNT_JOINT_SAVINGS" => Some("other-joint-savings-id".to_string()),
+                _ => None,
+            }
+        });
+
+        assert_eq!("other-joint-savings-id",
+                   config.get_account_id(&AccountType::Unknown("joint-savings".to_string())));
     }
 
     #[test]
@@ -211,6 +714,18 @@ mod tests {
         assert_eq!("business-id", cnf.business);
     }
 
+    #[test]
+    fn can_read_flat_config_without_some_aliases() {
+        let mut reader = Cursor::new(
+            &b"{\"auth_token\":\"auth-token\",\"current\":\"current-id\"}"[..]
+        );
+
+        let cnf = read_config(&mut reader).unwrap();
+        assert_eq!("current-id", cnf.current);
+        assert_eq!("", cnf.savings);
+        assert_eq!("", cnf.business);
+    }
+
     #[test]
     fn can_read_config_and_error() {
         let mut reader = Cursor::new(vec![]);
@@ -235,11 +750,22 @@ mod tests {
         assert_eq!(true, write_state.is_ok());
 
         assert_eq!(
-            "{\"auth_token\":\"auth-token\",\"current\":\"current-id\",\"savings\":\"savings-id\",\"business\":\"business-id\"}",
+            "{\"aliases\":null,\"auth_token\":\"auth-token\",\"auth_token_command\":null,\"business\":\"business-id\",\"cache\":null,\"categories\":null,\"checks\":null,\"current\":\"current-id\",\"encrypted_auth_token\":null,\"locale\":null,\"profiles\":null,\"savings\":\"savings-id\",\"version\":2}",
             from_utf8(writer.get_ref()).unwrap()
         );
     }
 
+    #[test]
+    fn can_write_config_without_auth_token_from_command() {
+        let mut writer = Cursor::new(vec![]);
+
+        let mut config = Config::new("auth-token", "current-id", "savings-id", "business-id");
+        config.auth_token_command = Some("pass teller/token".to_string());
+
+        assert_eq!(true, write_config(&mut writer, &config).is_ok());
+        assert_eq!(false, from_utf8(writer.get_ref()).unwrap().contains("auth-token"));
+    }
+
     /*
     NOTE: Currently unsure about how to test IO errors as Cursor always succeeds.
 
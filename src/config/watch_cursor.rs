@@ -0,0 +1,187 @@
+use rustc_serialize::json;
+use std::env;
+use std::path::PathBuf;
+use std::fs::File;
+use std::io::ErrorKind;
+
+use std::io::prelude::*; // Required for Read, Write, etc.
+
+use config::error::ConfigError;
+use config::atomic_write::replace_atomically;
+
+use api::Transaction;
+
+/// The watch cursor remembers the date of the newest transaction we have seen
+/// alongside the ids of every transaction on that date, since more than one
+/// transaction can arrive on the same day.
+#[derive(Debug, Clone, PartialEq, RustcEncodable, RustcDecodable)]
+pub struct WatchCursor {
+    pub date: String,
+    pub ids: Vec<String>,
+}
+
+impl WatchCursor {
+    /// A cursor after the transactions with `ids` on `date`.
+    pub fn new<S: Into<String>>(date: S, ids: Vec<String>) -> WatchCursor {
+        WatchCursor {
+            date: date.into(),
+            ids: ids,
+        }
+    }
+
+    /// A cursor after the newest of some transactions, or `None` without any.
+    pub fn from_transactions(transactions: &Vec<Transaction>) -> Option<WatchCursor> {
+        if transactions.is_empty() {
+            None
+        } else {
+            Some(WatchCursor::new("", vec![]).advance(&transactions))
+        }
+    }
+
+    /// Whether a transaction is at or before the cursor.
+    pub fn is_seen(&self, transaction: &Transaction) -> bool {
+        transaction.date < self.date ||
+        (transaction.date == self.date && self.ids.contains(&transaction.id))
+    }
+
+    /// Moves the cursor past some transactions which it has not seen.
+    pub fn advance(&self, transactions: &Vec<Transaction>) -> WatchCursor {
+        transactions.iter().fold(self.clone(), |mut cursor, t| {
+            if t.date > cursor.date {
+                WatchCursor::new(t.date.to_owned(), vec![t.id.to_owned()])
+            } else {
+                if t.date == cursor.date && !cursor.ids.contains(&t.id) {
+                    cursor.ids.push(t.id.to_owned());
+                }
+                cursor
+            }
+        })
+    }
+}
+
+/// `~/.tellerwatch_<account id>` (or `./.tellerwatch_<account id>` without a home
+/// directory).
+pub fn get_watch_cursor_path(account_id: &str) -> PathBuf {
+    let file_name = format!(".tellerwatch_{}", account_id);
+    let fallback_watch_cursor_path = PathBuf::from(format!("./{}", file_name));
+    let append_watch_cursor_file = |mut p: PathBuf| {
+        p.push(&file_name);
+        p
+    };
+    env::home_dir().map_or(fallback_watch_cursor_path, append_watch_cursor_file)
+}
+
+/// Reads a cursor from JSON.
+pub fn read_watch_cursor<R>(watch_cursor_file: &mut R) -> Result<WatchCursor, ConfigError>
+    where R: Read {
+    let mut content_str = String::new();
+    try!(watch_cursor_file.read_to_string(&mut content_str));
+
+    let watch_cursor: WatchCursor = try!(json::decode(&content_str));
+
+    Ok(watch_cursor)
+}
+
+/// Writes a cursor as JSON.
+pub fn write_watch_cursor<W>(watch_cursor_file: &mut W,
+                             watch_cursor: &WatchCursor)
+                             -> Result<(), ConfigError>
+    where W: Write {
+    let content_str = try!(json::encode(&watch_cursor));
+
+    try!(watch_cursor_file.write_all(content_str.as_bytes()));
+
+    Ok(())
+}
+
+/// The cursor of an account, or `None` when it has not been watched (or its cursor
+/// cannot be read).
+pub fn get_watch_cursor(account_id: &str) -> Option<WatchCursor> {
+    let watch_cursor_path = get_watch_cursor_path(&account_id);
+    match File::open(&watch_cursor_path) {
+        Err(ref e) if ErrorKind::NotFound == e.kind() => {
+            debug!("No watch cursor found");
+            None
+        }
+        Err(e) => {
+            error!("Unable to open watch cursor {}: {}", watch_cursor_path.display(), e);
+            None
+        }
+        Ok(mut watch_cursor_file) => {
+            match read_watch_cursor(&mut watch_cursor_file) {
+                Ok(watch_cursor) => Some(watch_cursor),
+                Err(e) => {
+                    error!("Unable to read watch cursor {}: {}", watch_cursor_path.display(), e);
+                    None
+                }
+            }
+        }
+    }
+}
+
+/// A cursor lost to a crash would replay every transaction to the hook, so it
+/// is replaced atomically rather than truncated and rewritten.
+pub fn save_watch_cursor(account_id: &str, watch_cursor: &WatchCursor) -> Result<(), ConfigError> {
+    let watch_cursor_path = get_watch_cursor_path(&account_id);
+    let mut content = vec![];
+    try!(write_watch_cursor(&mut content, &watch_cursor));
+    try!(replace_atomically(&watch_cursor_path, &content));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WatchCursor;
+
+    use api::Transaction;
+
+    use std::io::Cursor;
+    use std::str::from_utf8;
+    use super::{read_watch_cursor, write_watch_cursor};
+
+    fn to_transaction(id: &str, date: &str) -> Transaction {
+        Transaction {
+            description: "".to_string(),
+            date: date.to_string(),
+            counterparty: "NANNA'S".to_string(),
+            amount: "-2.70".to_string(),
+            id: id.to_string(),
+        }
+    }
+
+    #[test]
+    fn can_create_watch_cursor_from_transactions() {
+        let transactions = vec![to_transaction("3", "2016-01-02"),
+                                to_transaction("2", "2016-01-02"),
+                                to_transaction("1", "2016-01-01")];
+
+        let watch_cursor = WatchCursor::from_transactions(&transactions).unwrap();
+
+        assert_eq!(WatchCursor::new("2016-01-02", vec!["3".to_string(), "2".to_string()]),
+                   watch_cursor);
+    }
+
+    #[test]
+    fn can_tell_whether_transaction_is_seen() {
+        let watch_cursor = WatchCursor::new("2016-01-02", vec!["2".to_string()]);
+
+        assert_eq!(true, watch_cursor.is_seen(&to_transaction("1", "2016-01-01")));
+        assert_eq!(true, watch_cursor.is_seen(&to_transaction("2", "2016-01-02")));
+        assert_eq!(false, watch_cursor.is_seen(&to_transaction("3", "2016-01-02")));
+        assert_eq!(false, watch_cursor.is_seen(&to_transaction("4", "2016-01-03")));
+    }
+
+    #[test]
+    fn can_read_and_write_watch_cursor() {
+        let mut writer = Cursor::new(vec![]);
+
+        let watch_cursor = WatchCursor::new("2016-01-02", vec!["2".to_string()]);
+        assert_eq!(true, write_watch_cursor(&mut writer, &watch_cursor).is_ok());
+        assert_eq!("{\"date\":\"2016-01-02\",\"ids\":[\"2\"]}",
+                   from_utf8(writer.get_ref()).unwrap());
+
+        let mut reader = Cursor::new(writer.into_inner());
+        assert_eq!(watch_cursor, read_watch_cursor(&mut reader).unwrap());
+    }
+
+}
@@ -0,0 +1,308 @@
+use api::Transaction;
+use api::cents::{from_cents, to_transaction_cents};
+use api::client::ApiServiceResult;
+use config::Config;
+use export::to_single_line;
+
+const UNKNOWN_EXPENSES_ACCOUNT: &'static str = "Expenses:Unknown";
+const UNKNOWN_INCOME_ACCOUNT: &'static str = "Income:Unknown";
+
+/// The plain text accounting tools which a journal can be written for.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JournalFormat {
+    Ledger,
+    Hledger,
+    Beancount,
+}
+
+/// Reads a journal format name such as `ledger`.
+pub fn to_journal_format(format: &str) -> Option<JournalFormat> {
+    match &format.to_lowercase()[..] {
+        "ledger" => Some(JournalFormat::Ledger),
+        "hledger" => Some(JournalFormat::Hledger),
+        "beancount" => Some(JournalFormat::Beancount),
+        _ => None,
+    }
+}
+
+/// The account of the books which holds a bank account, e.g. `Assets:Joint`
+/// for the alias `joint`.
+///
+/// Beancount only allows letters, numbers and dashes within the components of
+/// an account and wants them to start with a capital letter.
+pub fn to_asset_account(alias: &str) -> String {
+    let component: String = alias.trim()
+                                 .chars()
+                                 .map(|c| if c.is_alphanumeric() { c } else { '-' })
+                                 .collect();
+    let mut chars = component.chars();
+    match chars.next() {
+        Some(first) if first.is_alphabetic() => {
+            format!("Assets:{}{}", first.to_uppercase().collect::<String>(), chars.as_str())
+        }
+        Some(_) => format!("Assets:A{}", component),
+        None => "Assets:Current".to_string(),
+    }
+}
+
+fn escape_beancount_string(text: &str) -> String {
+    to_single_line(&text).replace("\\", "\\\\").replace("\"", "\\\"")
+}
+
+fn represent_posting(format: &JournalFormat, account: &str, cents: i64, currency: &str) -> String {
+    let indent = match *format {
+        JournalFormat::Beancount => "  ",
+        JournalFormat::Ledger | JournalFormat::Hledger => "    ",
+    };
+    // At least two spaces must separate an account from its amount.
+    format!("{}{:<40}  {:>10} {}\n",
+            indent,
+            account,
+            from_cents(cents),
+            currency)
+}
+
+fn represent_entry(format: &JournalFormat,
+                   asset_account: &str,
+                   counter_account: &str,
+                   currency: &str,
+                   transaction: &Transaction,
+                   cents: i64)
+                   -> String {
+    let counterparty = to_single_line(&transaction.counterparty);
+    let description = to_single_line(&transaction.description);
+    let payee = if counterparty.is_empty() {
+        description.to_owned()
+    } else {
+        counterparty.to_owned()
+    };
+    let has_note = !counterparty.is_empty() && !description.is_empty();
+
+    let mut entry = match *format {
+        JournalFormat::Ledger => {
+            let mut entry = format!("{} {}\n", transaction.date.replace("-", "/"), payee);
+            if !transaction.id.is_empty() {
+                entry.push_str(&format!("    ; id: {}\n", transaction.id));
+            }
+            if has_note {
+                entry.push_str(&format!("    ; description: {}\n", description));
+            }
+            entry
+        }
+        JournalFormat::Hledger => {
+            let mut entry = if has_note {
+                format!("{} {} | {}\n", transaction.date, payee, description)
+            } else {
+                format!("{} {}\n", transaction.date, payee)
+            };
+            if !transaction.id.is_empty() {
+                entry.push_str(&format!("    ; id:{}\n", transaction.id));
+            }
+            entry
+        }
+        JournalFormat::Beancount => {
+            let (payee, narration) = if has_note {
+                (counterparty.to_owned(), description.to_owned())
+            } else {
+                (payee, "".to_string())
+            };
+            let mut entry = format!("{} * \"{}\" \"{}\"\n",
+                                    transaction.date,
+                                    escape_beancount_string(&payee),
+                                    escape_beancount_string(&narration));
+            if !transaction.id.is_empty() {
+                entry.push_str(&format!("  id: \"{}\"\n", escape_beancount_string(&transaction.id)));
+            }
+            entry
+        }
+    };
+
+    // Both postings are written out in full, so that the entry balances
+    // without relying upon the tool to infer the missing amount.
+    entry.push_str(&represent_posting(&format, &asset_account, cents, &currency));
+    entry.push_str(&represent_posting(&format, &counter_account, -cents, &currency));
+    entry
+}
+
+/// Writes each transaction as an entry which moves money between the bank
+/// account and a counter-account.
+///
+/// The counter-account is the category of the counterparty within the config,
+/// otherwise `Expenses:Unknown` for money going out and `Income:Unknown` for
+/// money coming in. Transaction ids are kept as metadata so that entries which
+/// were exported before can be recognised. Beancount refuses postings to
+/// accounts which were never opened, so its journal starts by opening each of
+/// them on the date of the earliest transaction.
+pub fn represent_journal(format: &JournalFormat,
+                         asset_account: &str,
+                         currency: &str,
+                         transactions: &Vec<Transaction>,
+                         config: &Config)
+                         -> ApiServiceResult<String> {
+    let amounts = try!(to_transaction_cents(&transactions));
+    let mut accounts = vec![asset_account.to_string()];
+    let entries = transactions.iter()
+                              .zip(amounts.into_iter())
+                              .map(|(transaction, cents)| {
+                                  let counter_account =
+                                      config.get_category(&transaction.counterparty)
+                                            .unwrap_or_else(|| {
+                                                if cents < 0 {
+                                                    UNKNOWN_EXPENSES_ACCOUNT.to_string()
+                                                } else {
+                                                    UNKNOWN_INCOME_ACCOUNT.to_string()
+                                                }
+                                            });
+                                  if !accounts.contains(&counter_account) {
+                                      accounts.push(counter_account.to_owned());
+                                  }
+                                  represent_entry(&format,
+                                                  &asset_account,
+                                                  &counter_account,
+                                                  &currency,
+                                                  &transaction,
+                                                  cents)
+                              })
+                              .collect::<Vec<String>>()
+                              .join("\n");
+
+    let earliest_date = transactions.iter().map(|t| &t.date).min();
+    match (format, earliest_date) {
+        (&JournalFormat::Beancount, Some(earliest_date)) => {
+            let opens = accounts.iter()
+                                .map(|account| format!("{} open {}\n", earliest_date, account))
+                                .collect::<String>();
+            Ok(format!("{}\n{}", opens, entries))
+        }
+        _ => Ok(entries),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use api::Transaction;
+    use config::Config;
+    use super::{JournalFormat, to_asset_account, represent_journal};
+
+    fn get_transactions() -> Vec<Transaction> {
+        vec![Transaction {
+                 id: "abc1".to_string(),
+                 date: "2016-01-21".to_string(),
+                 counterparty: "NANNA'S".to_string(),
+                 amount: "-2.70".to_string(),
+                 description: "COFFEE \"TO GO\"".to_string(),
+             },
+             Transaction {
+                 id: "abc2".to_string(),
+                 date: "2016-01-22".to_string(),
+                 counterparty: "ACME".to_string(),
+                 amount: "1500.00".to_string(),
+                 description: "".to_string(),
+             }]
+    }
+
+    #[test]
+    fn can_get_asset_account() {
+        assert_eq!("Assets:Current", to_asset_account("current"));
+        assert_eq!("Assets:Joint-savings", to_asset_account("joint savings"));
+        assert_eq!("Assets:Current", to_asset_account(""));
+    }
+
+    #[test]
+    fn can_represent_ledger_journal() {
+        let mut config = Config::new_with_auth_token_only("");
+        let mut categories = BTreeMap::new();
+        categories.insert("nanna".to_string(), "Expenses:Coffee".to_string());
+        config.categories = Some(categories);
+
+        let journal = represent_journal(&JournalFormat::Ledger,
+                                        "Assets:Current",
+                                        "GBP",
+                                        &get_transactions(),
+                                        &config)
+                          .unwrap();
+
+        assert_eq!("2016/01/21 NANNA'S
+    ; id: abc1
+    ; description: COFFEE \"TO GO\"
+    Assets:Current                                 -2.70 GBP
+    Expenses:Coffee                                 2.70 GBP
+
+2016/01/22 ACME
+    ; id: abc2
+    Assets:Current                               1500.00 GBP
+    Income:Unknown                              -1500.00 GBP
+",
+                   journal);
+    }
+
+    #[test]
+    fn can_represent_hledger_and_beancount_journals() {
+        let config = Config::new_with_auth_token_only("");
+        let transactions = get_transactions();
+
+        let hledger = represent_journal(&JournalFormat::Hledger,
+                                        "Assets:Current",
+                                        "GBP",
+                                        &transactions,
+                                        &config)
+                          .unwrap();
+        assert!(hledger.starts_with("2016-01-21 NANNA'S | COFFEE \"TO GO\"\n    ; id:abc1\n"));
+        assert!(hledger.contains("    Expenses:Unknown                                2.70 GBP\n"));
+
+        let beancount = represent_journal(&JournalFormat::Beancount,
+                                          "Assets:Current",
+                                          "GBP",
+                                          &transactions,
+                                          &config)
+                          .unwrap();
+        assert!(beancount.contains("\n2016-01-21 * \"NANNA'S\" \"COFFEE \\\"TO GO\\\"\"\n  \
+                                    id: \"abc1\"\n  Assets:Current"));
+        assert!(beancount.contains("2016-01-22 * \"ACME\" \"\"\n  id: \"abc2\"\n"));
+    }
+
+    #[test]
+    fn can_open_beancount_accounts_at_the_earliest_transaction() {
+        let mut config = Config::new_with_auth_token_only("");
+        let mut categories = BTreeMap::new();
+        categories.insert("nanna".to_string(), "Expenses:Coffee".to_string());
+        config.categories = Some(categories);
+        let mut transactions = get_transactions();
+        transactions.reverse();
+
+        let beancount = represent_journal(&JournalFormat::Beancount,
+                                          "Assets:Current",
+                                          "GBP",
+                                          &transactions,
+                                          &config)
+                            .unwrap();
+        let empty = represent_journal(&JournalFormat::Beancount,
+                                      "Assets:Current",
+                                      "GBP",
+                                      &vec![],
+                                      &config)
+                        .unwrap();
+
+        assert!(beancount.starts_with("2016-01-21 open Assets:Current\n2016-01-21 open \
+                                       Income:Unknown\n2016-01-21 open Expenses:Coffee\n\n\
+                                       2016-01-22 * \"ACME\""));
+        assert_eq!("", empty);
+    }
+
+    #[test]
+    fn can_reject_invalid_amounts() {
+        let config = Config::new_with_auth_token_only("");
+        let mut transactions = get_transactions();
+        transactions[1].amount = "1,500.00".to_string();
+
+        let journal = represent_journal(&JournalFormat::Ledger,
+                                        "Assets:Current",
+                                        "GBP",
+                                        &transactions,
+                                        &config);
+
+        assert!(journal.is_err());
+    }
+}
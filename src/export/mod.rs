@@ -0,0 +1,45 @@
+pub mod journal;
+pub mod ofx;
+pub mod qif;
+pub mod sqlite;
+
+pub use self::journal::{JournalFormat, to_journal_format, to_asset_account, represent_journal};
+pub use self::ofx::represent_ofx;
+pub use self::qif::represent_qif;
+pub use self::sqlite::{ExportedAccount, write_sqlite};
+
+/// Collapses every run of whitespace, newlines included, into a single space,
+/// since each format keeps a field upon one line.
+pub fn to_single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// The formats which write transactions out as text, to stdout or a file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TextFormat {
+    Journal(JournalFormat),
+    Ofx,
+    Qif,
+}
+
+/// The formats which transactions can be exported in. A database is updated
+/// in place rather than written out as text.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExportFormat {
+    Text(TextFormat),
+    Sqlite,
+}
+
+/// Reads an export format name such as `ofx` or `beancount`.
+pub fn to_export_format(format: &str) -> Option<ExportFormat> {
+    match &format.to_lowercase()[..] {
+        "ofx" => Some(ExportFormat::Text(TextFormat::Ofx)),
+        "qif" => Some(ExportFormat::Text(TextFormat::Qif)),
+        "sqlite" => Some(ExportFormat::Sqlite),
+        format => {
+            to_journal_format(&format).map(|journal_format| {
+                ExportFormat::Text(TextFormat::Journal(journal_format))
+            })
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use std::str::FromStr; // Use of #from_str.
+
+use chrono::{Date, UTC};
+
+use api::{Account, Transaction};
+use cli::arg_types::AccountType;
+use export::to_single_line;
+
+const OFX_DATE_FORMAT: &'static str = "%Y%m%d";
+
+// OFX 1.02 allows a payee of up to 32 characters.
+const MAX_NAME_LENGTH: usize = 32;
+
+fn escape_ofx(text: &str) -> String {
+    to_single_line(&text)
+        .replace("&", "&amp;")
+        .replace("<", "&lt;")
+        .replace(">", "&gt;")
+}
+
+// OFX only tells checking and savings accounts apart, and the alias of an
+// account is the only thing which says which it is.
+fn to_ofx_account_type(account_type: &AccountType) -> &'static str {
+    match *account_type {
+        AccountType::Savings => "SAVINGS",
+        AccountType::Unknown(ref alias) if alias.to_lowercase().contains("saving") => "SAVINGS",
+        _ => "CHECKING",
+    }
+}
+
+fn to_ofx_date(date: &str) -> String {
+    date.replace("-", "")
+}
+
+fn represent_transaction(transaction: &Transaction) -> String {
+    let is_debit = f64::from_str(&transaction.amount).map(|a| a < 0f64).unwrap_or(false);
+    let name: String = if transaction.counterparty.trim().is_empty() {
+        transaction.description.chars().take(MAX_NAME_LENGTH).collect()
+    } else {
+        transaction.counterparty.chars().take(MAX_NAME_LENGTH).collect()
+    };
+
+    let mut stmttrn = String::new();
+    stmttrn.push_str("<STMTTRN>\n");
+    stmttrn.push_str(&format!("<TRNTYPE>{}\n", if is_debit { "DEBIT" } else { "CREDIT" }));
+    stmttrn.push_str(&format!("<DTPOSTED>{}\n", to_ofx_date(&transaction.date)));
+    stmttrn.push_str(&format!("<TRNAMT>{}\n", transaction.amount));
+    // Finance software skips a transaction whose FITID it has seen before, so
+    // this must not change between exports.
+    stmttrn.push_str(&format!("<FITID>{}\n", escape_ofx(&transaction.id)));
+    stmttrn.push_str(&format!("<NAME>{}\n", escape_ofx(&name)));
+    if !transaction.description.trim().is_empty() {
+        stmttrn.push_str(&format!("<MEMO>{}\n", escape_ofx(&transaction.description)));
+    }
+    stmttrn.push_str("</STMTTRN>\n");
+    stmttrn
+}
+
+/// Writes an OFX 1.02 bank statement, which GnuCash, Moneydance and Quicken
+/// can all import.
+///
+/// Each transaction needs an id to become its FITID, which is how finance
+/// software recognises transactions within overlapping statements. The
+/// `account_type` it was chosen by decides whether it is a savings account.
+pub fn represent_ofx(account: &Account,
+                     account_type: &AccountType,
+                     transactions: &Vec<Transaction>,
+                     from: &Date<UTC>,
+                     to: &Date<UTC>)
+                     -> String {
+    let dtend = to.format(OFX_DATE_FORMAT).to_string();
+    // A bank id is required, even for the accounts of banks without one.
+    let bank_id = if account.bank_code.is_empty() {
+        "000000"
+    } else {
+        &account.bank_code
+    };
+    let account_id = if account.account_number.is_empty() {
+        &account.id
+    } else {
+        &account.account_number
+    };
+
+    let mut ofx = String::new();
+    // Payees such as `CAFÉ` are written as they are, so the header has to
+    // declare UTF-8 rather than an ASCII code page.
+    ofx.push_str("OFXHEADER:100\nDATA:OFXSGML\nVERSION:102\nSECURITY:NONE\nENCODING:UTF-8\n\
+                  CHARSET:NONE\nCOMPRESSION:NONE\nOLDFILEUID:NONE\nNEWFILEUID:NONE\n\n");
+    ofx.push_str("<OFX>\n<SIGNONMSGSRSV1>\n<SONRS>\n\
+                  <STATUS>\n<CODE>0\n<SEVERITY>INFO\n</STATUS>\n");
+    ofx.push_str(&format!("<DTSERVER>{}\n", dtend));
+    ofx.push_str("<LANGUAGE>ENG\n</SONRS>\n</SIGNONMSGSRSV1>\n");
+    ofx.push_str("<BANKMSGSRSV1>\n<STMTTRNRS>\n<TRNUID>0\n\
+                  <STATUS>\n<CODE>0\n<SEVERITY>INFO\n</STATUS>\n<STMTRS>\n");
+    ofx.push_str(&format!("<CURDEF>{}\n", escape_ofx(&account.currency)));
+    ofx.push_str(&format!("<BANKACCTFROM>\n<BANKID>{}\n<ACCTID>{}\n<ACCTTYPE>{}\n\
+                           </BANKACCTFROM>\n",
+                          escape_ofx(&bank_id),
+                          escape_ofx(&account_id),
+                          to_ofx_account_type(&account_type)));
+    ofx.push_str("<BANKTRANLIST>\n");
+    ofx.push_str(&format!("<DTSTART>{}\n<DTEND>{}\n", from.format(OFX_DATE_FORMAT), dtend));
+    for transaction in transactions.iter() {
+        ofx.push_str(&represent_transaction(&transaction));
+    }
+    ofx.push_str("</BANKTRANLIST>\n");
+    ofx.push_str(&format!("<LEDGERBAL>\n<BALAMT>{}\n<DTASOF>{}\n</LEDGERBAL>\n",
+                          account.balance,
+                          dtend));
+    ofx.push_str("</STMTRS>\n</STMTTRNRS>\n</BANKMSGSRSV1>\n</OFX>\n");
+    ofx
+}
+
+#[cfg(test)]
+mod tests {
+    use api::{Account, Transaction};
+    use api::client::generate_utc_date_from_date_str;
+    use api::source::ofx::parse_ofx;
+    use cli::arg_types::AccountType;
+    use super::{represent_ofx, to_ofx_account_type};
+
+    #[test]
+    fn can_represent_ofx_which_can_be_read_back() {
+        let account = Account {
+            account_number: "12345678".to_string(),
+            balance: "1497.30".to_string(),
+            bank_code: "000000".to_string(),
+            currency: "GBP".to_string(),
+            id: "123".to_string(),
+            institution: "natwest".to_string(),
+            name: "Current".to_string(),
+        };
+        let counterparty = "NANNA'S & <CO> OF CAMDEN TOWN LONDON";
+        let transactions = vec![Transaction {
+                                    id: "abc1".to_string(),
+                                    date: "2016-01-21".to_string(),
+                                    counterparty: counterparty.to_string(),
+                                    amount: "-2.70".to_string(),
+                                    description: "COFFEE".to_string(),
+                                },
+                                Transaction {
+                                    id: "abc2".to_string(),
+                                    date: "2016-01-22".to_string(),
+                                    counterparty: "ACME".to_string(),
+                                    amount: "1500.00".to_string(),
+                                    description: "".to_string(),
+                                }];
+        let ofx = represent_ofx(&account,
+                                &AccountType::None,
+                                &transactions,
+                                &generate_utc_date_from_date_str("2016-01-01"),
+                                &generate_utc_date_from_date_str("2016-01-31"));
+
+        assert!(ofx.starts_with("OFXHEADER:100\nDATA:OFXSGML\nVERSION:102\n"));
+        assert!(ofx.contains("<TRNTYPE>DEBIT\n<DTPOSTED>20160121\n<TRNAMT>-2.70\n<FITID>abc1\n"));
+        assert!(ofx.contains("<NAME>NANNA'S &amp; &lt;CO&gt; OF CAMDEN TOWN LO\n"));
+
+        let statement = parse_ofx(&ofx, None).unwrap();
+        assert_eq!(Some("GBP".to_string()), statement.currency);
+        assert_eq!(Some("12345678".to_string()), statement.account_number);
+        assert_eq!(Some("1497.30".to_string()), statement.balance);
+        assert_eq!(2, statement.transactions.len());
+        assert_eq!("COFFEE", statement.transactions[0].description);
+        assert_eq!("abc2", statement.transactions[1].id);
+        assert_eq!("1500.00", statement.transactions[1].amount);
+    }
+
+    #[test]
+    fn can_represent_ofx_declaring_utf8() {
+        let account = Account {
+            account_number: "".to_string(),
+            balance: "-3.20".to_string(),
+            bank_code: "".to_string(),
+            currency: "GBP".to_string(),
+            id: "123".to_string(),
+            institution: "".to_string(),
+            name: "Current".to_string(),
+        };
+        let transactions = vec![Transaction {
+                                    id: "abc1".to_string(),
+                                    date: "2016-01-21".to_string(),
+                                    counterparty: "CAFÉ NÉRO".to_string(),
+                                    amount: "-3.20".to_string(),
+                                    description: "£3.20 FLAT WHITE".to_string(),
+                                }];
+        let ofx = represent_ofx(&account,
+                                &AccountType::None,
+                                &transactions,
+                                &generate_utc_date_from_date_str("2016-01-01"),
+                                &generate_utc_date_from_date_str("2016-01-31"));
+
+        assert!(ofx.contains("\nENCODING:UTF-8\nCHARSET:NONE\n"));
+        assert!(ofx.contains("<NAME>CAFÉ NÉRO\n<MEMO>£3.20 FLAT WHITE\n"));
+
+        let statement = parse_ofx(&ofx, None).unwrap();
+        assert_eq!("CAFÉ NÉRO", statement.transactions[0].counterparty);
+        assert_eq!("£3.20 FLAT WHITE", statement.transactions[0].description);
+    }
+
+    #[test]
+    fn can_choose_account_type_by_alias() {
+        assert_eq!("SAVINGS", to_ofx_account_type(&AccountType::Savings));
+        assert_eq!("SAVINGS",
+                   to_ofx_account_type(&AccountType::Unknown("joint-savings".to_string())));
+        assert_eq!("CHECKING", to_ofx_account_type(&AccountType::Current));
+        assert_eq!("CHECKING", to_ofx_account_type(&AccountType::None));
+    }
+}
@@ -0,0 +1,57 @@
+use api::Transaction;
+use export::to_single_line;
+
+// Quicken expects dates to be month first.
+fn to_qif_date(date: &str) -> String {
+    let parts: Vec<&str> = date.split('-').collect();
+    match parts.len() {
+        3 => format!("{}/{}/{}", parts[1], parts[2], parts[0]),
+        _ => date.to_string(),
+    }
+}
+
+/// Writes a QIF bank statement.
+///
+/// QIF has nowhere to keep the id of a transaction, so finance software can
+/// only match the transactions of overlapping statements by their details.
+pub fn represent_qif(transactions: &Vec<Transaction>) -> String {
+    let mut qif = String::from("!Type:Bank\n");
+    for transaction in transactions.iter() {
+        qif.push_str(&format!("D{}\n", to_qif_date(&transaction.date)));
+        qif.push_str(&format!("T{}\n", transaction.amount));
+        if !transaction.counterparty.trim().is_empty() {
+            qif.push_str(&format!("P{}\n", to_single_line(&transaction.counterparty)));
+        }
+        if !transaction.description.trim().is_empty() {
+            qif.push_str(&format!("M{}\n", to_single_line(&transaction.description)));
+        }
+        qif.push_str("^\n");
+    }
+    qif
+}
+
+#[cfg(test)]
+mod tests {
+    use api::Transaction;
+    use api::source::qif::to_qif_transactions;
+    use super::represent_qif;
+
+    #[test]
+    fn can_represent_qif_which_can_be_read_back() {
+        let transactions = vec![Transaction {
+                                    id: "abc1".to_string(),
+                                    date: "2016-01-21".to_string(),
+                                    counterparty: "NANNA'S".to_string(),
+                                    amount: "-2.70".to_string(),
+                                    description: "COFFEE\nTO GO".to_string(),
+                                }];
+        let qif = represent_qif(&transactions);
+
+        assert_eq!("!Type:Bank\nD01/21/2016\nT-2.70\nPNANNA'S\nMCOFFEE TO GO\n^\n", qif);
+
+        let read_back = to_qif_transactions(&qif, None, None).unwrap();
+        assert_eq!("2016-01-21", read_back[0].date);
+        assert_eq!("-2.70", read_back[0].amount);
+        assert_eq!("NANNA'S", read_back[0].counterparty);
+    }
+}
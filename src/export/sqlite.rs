@@ -0,0 +1,259 @@
+use std::path::Path;
+
+use rusqlite::{Connection, Result as SqliteResult};
+use rusqlite::types::ToSql;
+
+use api::{Account, Transaction};
+use config::Config;
+
+/// An account alongside the alias it has within the config and the
+/// transactions to export.
+pub struct ExportedAccount {
+    pub alias: String,
+    pub account: Account,
+    pub transactions: Vec<Transaction>,
+}
+
+const SCHEMA: &'static str = "
+CREATE TABLE IF NOT EXISTS accounts (
+    id TEXT PRIMARY KEY,
+    alias TEXT,
+    name TEXT NOT NULL,
+    institution TEXT NOT NULL,
+    account_number TEXT NOT NULL,
+    bank_code TEXT NOT NULL,
+    currency TEXT NOT NULL,
+    balance NUMERIC NOT NULL
+);
+CREATE TABLE IF NOT EXISTS categories (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE
+);
+CREATE TABLE IF NOT EXISTS counterparties (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    category_id INTEGER REFERENCES categories (id)
+);
+CREATE TABLE IF NOT EXISTS transactions (
+    account_id TEXT NOT NULL REFERENCES accounts (id),
+    id TEXT NOT NULL,
+    date TEXT NOT NULL,
+    amount NUMERIC NOT NULL,
+    counterparty_id INTEGER REFERENCES counterparties (id),
+    description TEXT NOT NULL,
+    PRIMARY KEY (account_id, id)
+);
+CREATE INDEX IF NOT EXISTS transactions_date ON transactions (date);
+CREATE INDEX IF NOT EXISTS transactions_counterparty_id ON transactions (counterparty_id);
+";
+
+// Counterparties and categories are only ever added to, so that their ids
+// stay the same between exports. Accounts and transactions are replaced, as
+// their balances and descriptions can change.
+fn upsert_category(conn: &Connection, category: &str) -> SqliteResult<()> {
+    conn.execute("INSERT OR IGNORE INTO categories (name) VALUES (?)",
+                 &[&category as &ToSql])
+        .map(|_| ())
+}
+
+fn upsert_counterparty(conn: &Connection,
+                       config: &Config,
+                       counterparty: &str,
+                       redact: &Fn(&str) -> String)
+                       -> SqliteResult<()> {
+    // The category is found by the name before it is redacted.
+    let category = config.get_category(&counterparty);
+    let counterparty = redact(&counterparty);
+    try!(conn.execute("INSERT OR IGNORE INTO counterparties (name) VALUES (?)",
+                      &[&counterparty as &ToSql]));
+    if let Some(ref category) = category {
+        try!(upsert_category(&conn, &category));
+    }
+    conn.execute("UPDATE counterparties SET category_id = (SELECT id FROM categories WHERE name \
+                  = ?) WHERE name = ?",
+                 &[&category as &ToSql, &counterparty])
+        .map(|_| ())
+}
+
+fn upsert_account(conn: &Connection,
+                  config: &Config,
+                  exported_account: &ExportedAccount,
+                  redact: &Fn(&str) -> String)
+                  -> SqliteResult<()> {
+    let ExportedAccount { ref alias, ref account, ref transactions } = *exported_account;
+    let account_id = redact(&account.id);
+    try!(conn.execute("INSERT OR REPLACE INTO accounts (id, alias, name, institution, \
+                       account_number, bank_code, currency, balance) VALUES (?, ?, ?, ?, ?, ?, \
+                       ?, ?)",
+                      &[&account_id as &ToSql,
+                        &redact(&alias),
+                        &redact(&account.name),
+                        &redact(&account.institution),
+                        &redact(&account.account_number),
+                        &redact(&account.bank_code),
+                        &account.currency,
+                        &account.balance]));
+
+    for transaction in transactions.iter() {
+        let counterparty = transaction.counterparty.trim();
+        if !counterparty.is_empty() {
+            try!(upsert_counterparty(&conn, &config, &counterparty, redact));
+        }
+        try!(conn.execute("INSERT OR REPLACE INTO transactions (account_id, id, date, amount, \
+                           counterparty_id, description) VALUES (?, ?, ?, ?, (SELECT id FROM \
+                           counterparties WHERE name = ?), ?)",
+                          &[&account_id as &ToSql,
+                            &redact(&transaction.id),
+                            &transaction.date,
+                            &transaction.amount,
+                            &redact(&counterparty),
+                            &redact(&transaction.description)]));
+    }
+    Ok(())
+}
+
+/// Writes accounts and their transactions into normalised tables, updating
+/// whatever an earlier export wrote. Each name, number and description is
+/// passed through `redact` before it is inserted.
+pub fn upsert_sqlite(conn: &Connection,
+                     config: &Config,
+                     exported_accounts: &Vec<ExportedAccount>,
+                     redact: &Fn(&str) -> String)
+                     -> SqliteResult<()> {
+    try!(conn.execute_batch(SCHEMA));
+
+    // A single transaction is much faster than one for each row, and means
+    // a failed export leaves the database as it was.
+    try!(conn.execute_batch("BEGIN"));
+    let upserted = (|| -> SqliteResult<()> {
+        if let Some(ref categories) = config.categories {
+            for category in categories.values() {
+                try!(upsert_category(&conn, &category));
+            }
+        }
+        for exported_account in exported_accounts.iter() {
+            try!(upsert_account(&conn, &config, &exported_account, redact));
+        }
+        Ok(())
+    })();
+    match upserted {
+        Ok(_) => conn.execute_batch("COMMIT"),
+        Err(e) => {
+            let _ = conn.execute_batch("ROLLBACK");
+            Err(e)
+        }
+    }
+}
+
+/// Opens (or creates) the database at `path` and upserts the accounts into it.
+pub fn write_sqlite<P: AsRef<Path>>(path: P,
+                                    config: &Config,
+                                    exported_accounts: &Vec<ExportedAccount>,
+                                    redact: &Fn(&str) -> String)
+                                    -> SqliteResult<()> {
+    let conn = try!(Connection::open(path));
+    upsert_sqlite(&conn, &config, &exported_accounts, redact)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use rusqlite::Connection;
+    use rusqlite::types::ToSql;
+
+    use api::{Account, Transaction};
+    use config::Config;
+    use super::{ExportedAccount, upsert_sqlite};
+
+    fn to_transaction(id: &str, counterparty: &str, amount: &str) -> Transaction {
+        Transaction {
+            id: id.to_string(),
+            date: "2016-01-21".to_string(),
+            counterparty: counterparty.to_string(),
+            amount: amount.to_string(),
+            description: "".to_string(),
+        }
+    }
+
+    fn get_exported_accounts(balance: &str, transactions: Vec<Transaction>) -> Vec<ExportedAccount> {
+        vec![ExportedAccount {
+                 alias: "current".to_string(),
+                 account: Account {
+                     account_number: "12345678".to_string(),
+                     balance: balance.to_string(),
+                     bank_code: "000000".to_string(),
+                     currency: "GBP".to_string(),
+                     id: "123".to_string(),
+                     institution: "natwest".to_string(),
+                     name: "Current".to_string(),
+                 },
+                 transactions: transactions,
+             }]
+    }
+
+    fn query_count(conn: &Connection, sql: &str) -> i64 {
+        conn.query_row(sql, &[] as &[&ToSql], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn can_upsert_into_sqlite() {
+        let conn = Connection::open_in_memory().unwrap();
+        let mut config = Config::new_with_auth_token_only("");
+        let mut categories = BTreeMap::new();
+        categories.insert("NANNA'S".to_string(), "Expenses:Coffee".to_string());
+        config.categories = Some(categories);
+
+        let first = get_exported_accounts("100.00",
+                                          vec![to_transaction("abc1", "NANNA'S", "-2.70"),
+                                               to_transaction("abc2", "ACME", "1500.00")]);
+        upsert_sqlite(&conn, &config, &first, &|text| text.to_string()).unwrap();
+
+        let second = get_exported_accounts("90.00",
+                                           vec![to_transaction("abc2", "ACME", "1500.00"),
+                                                to_transaction("abc3", "NANNA'S", "-10.00")]);
+        upsert_sqlite(&conn, &config, &second, &|text| text.to_string()).unwrap();
+
+        assert_eq!(1, query_count(&conn, "SELECT COUNT(*) FROM accounts"));
+        assert_eq!(3, query_count(&conn, "SELECT COUNT(*) FROM transactions"));
+        assert_eq!(2, query_count(&conn, "SELECT COUNT(*) FROM counterparties"));
+        assert_eq!(90, query_count(&conn, "SELECT CAST(balance AS INTEGER) FROM accounts"));
+        assert_eq!(-1270,
+                   query_count(&conn,
+                               "SELECT CAST(ROUND(SUM(t.amount) * 100) AS INTEGER) FROM \
+                                transactions t JOIN counterparties c ON c.id = t.counterparty_id \
+                                JOIN categories g ON g.id = c.category_id WHERE g.name = \
+                                'Expenses:Coffee'"));
+    }
+
+    #[test]
+    fn can_redact_before_upserting_into_sqlite() {
+        let conn = Connection::open_in_memory().unwrap();
+        let mut config = Config::new_with_auth_token_only("");
+        let mut categories = BTreeMap::new();
+        categories.insert("NANNA'S".to_string(), "Expenses:Coffee".to_string());
+        config.categories = Some(categories);
+        let redact = |text: &str| {
+            text.replace("NANNA'S", "[counterparty 1]").replace("12345678", "[account number 1]")
+        };
+
+        let exported_accounts = get_exported_accounts("100.00",
+                                                      vec![to_transaction("abc1",
+                                                                          "NANNA'S",
+                                                                          "-2.70")]);
+        upsert_sqlite(&conn, &config, &exported_accounts, &redact).unwrap();
+
+        let name: String = conn.query_row("SELECT name FROM counterparties",
+                                          &[] as &[&ToSql],
+                                          |row| row.get(0))
+                               .unwrap();
+        assert_eq!("[counterparty 1]", name);
+        assert_eq!(0,
+                   query_count(&conn,
+                               "SELECT COUNT(*) FROM accounts WHERE account_number = '12345678'"));
+        assert_eq!(1,
+                   query_count(&conn,
+                               "SELECT COUNT(*) FROM counterparties c JOIN categories g ON g.id \
+                                = c.category_id WHERE g.name = 'Expenses:Coffee'"));
+    }
+}
@@ -1,22 +1,86 @@
+use std::fmt;
 use std::io::{self, BufRead, Write};
 
-#[derive(Debug)]
+use rpassword;
+
+// An answer which fails validation is asked for again, but only a few times so
+// that a closed stdin cannot loop forever.
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Checks an answer, returning the message to show if it is invalid.
+pub type Validator = Box<Fn(&str) -> Result<(), String>>;
+
+/// A question to ask on the terminal, of the `_type` `input`, `select`, `confirm` or
+/// `password`.
 pub struct Question {
     pub _type: String,
     pub name: String,
     pub message: String,
+    pub choices: Vec<String>,
+    pub default: Option<String>,
+    pub validate: Option<Validator>,
+}
+
+impl fmt::Debug for Question {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Question")
+         .field("_type", &self._type)
+         .field("name", &self.name)
+         .field("message", &self.message)
+         .field("choices", &self.choices)
+         .field("default", &self.default)
+         .field("validate", &self.validate.is_some())
+         .finish()
+    }
 }
 
 impl Question {
+    /// A question answered by typing anything.
     pub fn new<S: Into<String>>(name: S, message: S) -> Question {
         Question {
             _type: "input".to_string(),
             name: name.into(),
             message: message.into(),
+            choices: vec![],
+            default: None,
+            validate: None,
         }
     }
+
+    /// Each choice is listed against a number, and either the number or the
+    /// choice itself can be typed.
+    pub fn select<S: Into<String>>(name: S, message: S, choices: Vec<String>) -> Question {
+        let mut question = Question::new(name, message);
+        question._type = "select".to_string();
+        question.choices = choices;
+        question
+    }
+
+    /// Answered with `yes` or `no`, or the default when nothing is typed.
+    pub fn confirm<S: Into<String>>(name: S, message: S, default: bool) -> Question {
+        let mut question = Question::new(name, message);
+        question._type = "confirm".to_string();
+        question.default = Some(to_confirm_value(default).to_string());
+        question
+    }
+
+    /// Input which is not echoed to the terminal.
+    pub fn password<S: Into<String>>(name: S, message: S) -> Question {
+        let mut question = Question::new(name, message);
+        question._type = "password".to_string();
+        question
+    }
+
+    /// The validator is given the answer (or an empty string without one) and
+    /// returns the message to show before the question is asked again.
+    pub fn with_validator<F>(mut self, validate: F) -> Question
+        where F: Fn(&str) -> Result<(), String> + 'static {
+        self.validate = Some(Box::new(validate));
+        self
+    }
 }
 
+/// The answer to a question, named after it.
 #[derive(Debug)]
 pub struct Answer {
     pub _type: String,
@@ -25,6 +89,7 @@ pub struct Answer {
 }
 
 impl Answer {
+    /// An answer of `value` to the question `name`.
     pub fn new<S: Into<String>>(name: S, value: S) -> Answer {
         Answer {
             _type: "input".to_string(),
@@ -32,27 +97,124 @@ impl Answer {
             value: value.into(),
         }
     }
+
+    /// Whether a `confirm` question was answered with yes.
+    pub fn is_yes(&self) -> bool {
+        self.value == to_confirm_value(true)
+    }
 }
 
-pub fn raw_ask_question<R, W>(reader: &mut R, mut writer: &mut W, question: &Question) -> Option<Answer>
-    where R: BufRead,
-          W: Write {
-    write!(&mut writer, "{}\n", question.message).unwrap();
-
-    let mut input = String::new();
-    match reader.read_line(&mut input) {
-        Ok(_) => {
-            let answer_value = input.trim().to_string();
-            if !answer_value.is_empty() {
-                Some(Answer::new(question.name.to_owned(), answer_value))
+fn to_confirm_value(confirmed: bool) -> &'static str {
+    if confirmed {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+fn write_question<W>(writer: &mut W, question: &Question)
+    where W: Write {
+    match &question._type[..] {
+        "select" => {
+            write!(writer, "{}\n", question.message).unwrap();
+            for (idx, choice) in question.choices.iter().enumerate() {
+                write!(writer, "  {}) {}\n", idx + 1, choice).unwrap();
+            }
+        }
+        "confirm" => {
+            let hint = if question.default == Some(to_confirm_value(true).to_string()) {
+                "[Y/n]"
             } else {
-                None
+                "[y/N]"
+            };
+            write!(writer, "{} {}\n", question.message, hint).unwrap();
+        }
+        _ => write!(writer, "{}\n", question.message).unwrap(),
+    }
+}
+
+fn to_answer_value(question: &Question, input: &str) -> Result<Option<String>, String> {
+    let value = if input.is_empty() {
+        question.default.clone()
+    } else {
+        match &question._type[..] {
+            "select" => {
+                let choice = match input.parse::<usize>() {
+                    Ok(row) if row >= 1 && row <= question.choices.len() => {
+                        Some(question.choices[row - 1].to_owned())
+                    }
+                    _ => question.choices.iter().find(|choice| *choice == input).cloned(),
+                };
+                match choice {
+                    Some(choice) => Some(choice),
+                    None => {
+                        return Err(format!("Please choose a number between 1 and {}",
+                                           question.choices.len()))
+                    }
+                }
             }
-        },
-        Err(error) => panic!("Unable to read line for {}: {}", question.name, error),
+            "confirm" => {
+                match &input.to_lowercase()[..] {
+                    "y" | "yes" => Some(to_confirm_value(true).to_string()),
+                    "n" | "no" => Some(to_confirm_value(false).to_string()),
+                    _ => return Err("Please answer y or n".to_string()),
+                }
+            }
+            _ => Some(input.to_string()),
+        }
+    };
+
+    if let Some(ref validate) = question.validate {
+        try!(validate(value.as_ref().map_or("", |value| &value[..])));
     }
+
+    Ok(value)
+}
+
+// Asks a question using `read_line` to read each attempt at an answer, which
+// returns `None` once there is nothing left to read.
+fn ask_with<F, W>(mut read_line: F, writer: &mut W, question: &Question) -> Option<Answer>
+    where F: FnMut(&Question) -> Option<String>,
+          W: Write {
+    for _ in 0..MAX_ATTEMPTS {
+        write_question(writer, &question);
+        let input = match read_line(&question) {
+            Some(input) => input,
+            None => return None,
+        };
+        match to_answer_value(&question, input.trim()) {
+            Ok(value) => {
+                return value.map(|value| {
+                    Answer {
+                        _type: question._type.to_owned(),
+                        ..Answer::new(question.name.to_owned(), value)
+                    }
+                })
+            }
+            Err(message) => write!(writer, "{}\n", message).unwrap(),
+        }
+    }
+    None
+}
+
+/// Asks a question through `writer` and reads its answer from `reader`. `None` when it
+/// is left empty without a default, or is not answered validly within a few attempts.
+pub fn raw_ask_question<R, W>(reader: &mut R, writer: &mut W, question: &Question) -> Option<Answer>
+    where R: BufRead,
+          W: Write {
+    ask_with(|question| {
+                 let mut input = String::new();
+                 match reader.read_line(&mut input) {
+                     Ok(0) => None,
+                     Ok(_) => Some(input),
+                     Err(error) => panic!("Unable to read line for {}: {}", question.name, error),
+                 }
+             },
+             writer,
+             &question)
 }
 
+/// Asks each question in turn, keeping only the answers which were given.
 pub fn raw_ask_questions<R, W>(reader: &mut R, writer: &mut W, questions: &Vec<Question>) -> Vec<Answer>
     where R: BufRead,
           W: Write {
@@ -63,17 +225,37 @@ pub fn raw_ask_questions<R, W>(reader: &mut R, writer: &mut W, questions: &Vec<Q
     non_empty_answers
 }
 
+fn read_password_line(question: &Question) -> Option<String> {
+    match rpassword::read_password() {
+        Ok(input) => Some(input),
+        Err(error) => panic!("Unable to read line for {}: {}", question.name, error),
+    }
+}
+
+/// Asks a question on the terminal. See `raw_ask_question`.
 pub fn ask_question(question: &Question) -> Option<Answer> {
-    let stdin = io::stdin();
-    let mut reader = stdin.lock(); // A locked stdin implements BufRead.
     let mut writer = io::stdout();
-    raw_ask_question(&mut reader, &mut writer, &question)
+    if question._type == "password" {
+        // The password is read from stdin by rpassword, which stops the
+        // terminal from echoing it, so stdin must not be locked here.
+        ask_with(read_password_line, &mut writer, &question)
+    } else {
+        let stdin = io::stdin();
+        let mut reader = stdin.lock(); // A locked stdin implements BufRead.
+        raw_ask_question(&mut reader, &mut writer, &question)
+    }
 }
 
+/// Asks each question on the terminal. See `raw_ask_questions`.
 pub fn ask_questions(questions: &Vec<Question>) -> Vec<Answer> {
+    if questions.iter().any(|question| question._type == "password") {
+        return questions.iter().filter_map(ask_question).collect();
+    }
+
     let stdin = io::stdin();
-    let mut reader = stdin.lock(); // A locked stdin implements BufRead.
-    let mut writer = io::stdout();
+    let stdout = io::stdout();
+    let mut reader = stdin.lock();
+    let mut writer = stdout.lock();
     raw_ask_questions(&mut reader, &mut writer, &questions)
 }
 
@@ -170,4 +352,55 @@ mod tests {
 
       assert_eq!("Tell me your first answer?\nTell me your second answer?\nTell me your third answer?\n", from_utf8(writer.get_ref()).unwrap());
   }
+
+    #[test]
+    fn can_ask_select_question() {
+        let mut reader = Cursor::new(&b"3\nSavings\n"[..]);
+        let mut writer = Cursor::new(Vec::new());
+
+        let question = Question::select("account",
+                                        "Which account?",
+                                        vec!["Current".to_string(), "Savings".to_string()]);
+
+        let answer = raw_ask_question(&mut reader, &mut writer, &question).unwrap();
+
+        assert_eq!("select", answer._type);
+        assert_eq!("Savings", answer.value);
+        assert_eq!("Which account?\n  1) Current\n  2) Savings\nPlease choose a number between 1 \
+                    and 2\nWhich account?\n  1) Current\n  2) Savings\n",
+                   from_utf8(writer.get_ref()).unwrap());
+    }
+
+    #[test]
+    fn can_ask_confirm_question() {
+        let mut writer = Cursor::new(Vec::new());
+        let question = Question::confirm("save", "Save?", true);
+
+        let mut reader = Cursor::new(&b"\n"[..]);
+        assert_eq!(true, raw_ask_question(&mut reader, &mut writer, &question).unwrap().is_yes());
+        let mut reader = Cursor::new(&b"maybe\nN\n"[..]);
+        assert_eq!(false, raw_ask_question(&mut reader, &mut writer, &question).unwrap().is_yes());
+        assert_eq!("Save? [Y/n]\nSave? [Y/n]\nPlease answer y or n\nSave? [Y/n]\n",
+                   from_utf8(writer.get_ref()).unwrap());
+    }
+
+    #[test]
+    fn can_ask_validated_question_until_valid_or_out_of_attempts() {
+        let mut writer = Cursor::new(Vec::new());
+        let question = Question::password("auth_token", "What is your token?")
+                           .with_validator(|value| {
+                               if value.is_empty() {
+                                   Err("A token is needed".to_string())
+                               } else {
+                                   Ok(())
+                               }
+                           });
+
+        let mut reader = Cursor::new(&b"\nfake-auth-token\n"[..]);
+        let answer = raw_ask_question(&mut reader, &mut writer, &question).unwrap();
+        assert_eq!("fake-auth-token", answer.value);
+
+        let mut reader = Cursor::new(&b"\n\n\nfake-auth-token\n"[..]);
+        assert_eq!(true, raw_ask_question(&mut reader, &mut writer, &question).is_none());
+    }
 }
@@ -1,3 +1,3 @@
 pub mod ask;
 
-pub use self::ask::{Question, Answer, ask_question, ask_questions};
+pub use self::ask::{Question, ask_question, ask_questions};
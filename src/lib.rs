@@ -0,0 +1,53 @@
+//! Banking for the command line, as a library.
+//!
+//! The `teller` binary is built upon this crate, which other tools can use to
+//! talk to the [Teller API](https://teller.io):
+//!
+//! - `api` contains the `TellerClient` and its `Account` and `Transaction`
+//!   models, with aggregations such as balances, outgoings and counterparties
+//!   within `api::inform`.
+//! - `config` reads and writes the `.tellerrc` config and its profiles.
+//! - `check` parses and evaluates alerting rules.
+//! - `export` writes transactions in the formats of other finance tools.
+//! - `color` colours tables when they are written to a terminal.
+//! - `locale` displays amounts and dates in the formats of a locale.
+//!
+//! ```no_run
+//! use teller::api::TellerClient;
+//! use teller::api::inform::GetAccountBalance;
+//! use teller::config::{get_config, get_config_path};
+//! use teller::config::auth_token::resolve_auth_token;
+//!
+//! let config = resolve_auth_token(get_config(&get_config_path("")).unwrap()).unwrap();
+//! let teller = TellerClient::new(config.auth_token.to_owned());
+//! let balance = teller.get_account_balance(&config.current).unwrap();
+//! println!("{} {}", balance.amount, balance.currency);
+//! ```
+
+#[cfg(test)] #[macro_use]
+extern crate yup_hyper_mock;
+
+#[macro_use]
+extern crate log;
+#[macro_use]
+extern crate lazy_static;
+
+extern crate rustc_serialize;
+extern crate chrono;
+extern crate hyper;
+extern crate itertools;
+extern crate crypto;
+extern crate rand;
+extern crate rpassword;
+extern crate rusqlite;
+extern crate libc;
+
+pub mod api;
+pub mod check;
+pub mod cli;
+pub mod color;
+pub mod config;
+pub mod export;
+pub mod inquirer;
+pub mod locale;
+pub mod redact;
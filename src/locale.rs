@@ -0,0 +1,339 @@
+use std::sync::Mutex;
+
+use chrono::NaiveDate;
+use chrono::format::{Item, StrftimeItems};
+
+const DATE_FORMAT: &'static str = "%Y-%m-%d";
+const MONTH_FORMAT: &'static str = "%m-%Y";
+
+const CURRENCY_SYMBOLS: [(&'static str, &'static str); 6] = [("GBP", "£"),
+                                                             ("EUR", "€"),
+                                                             ("USD", "$"),
+                                                             ("JPY", "¥"),
+                                                             ("INR", "₹"),
+                                                             ("KRW", "₩")];
+
+fn get_currency_symbol(currency: &str) -> &str {
+    CURRENCY_SYMBOLS.iter()
+                    .find(|&&(code, _)| code == currency)
+                    .map(|&(_, symbol)| symbol)
+                    .unwrap_or(currency)
+}
+
+fn is_currency_symbol(c: char) -> bool {
+    CURRENCY_SYMBOLS.iter().any(|&(_, symbol)| symbol.contains(c))
+}
+
+// chrono fails to display a date with an unknown specifier, so these are
+// caught when the config is read rather than when a table is printed.
+fn is_valid_date_format(format: &str) -> bool {
+    !StrftimeItems::new(&format).any(|item| item == Item::Error)
+}
+
+/// How amounts and dates are displayed, as kept within the `locale` of a
+/// profile. A `name` such as `de_DE` gives the formats of that locale, and
+/// any other field takes precedence over these.
+#[derive(Debug, Clone, PartialEq, RustcEncodable, RustcDecodable)]
+pub struct Locale {
+    pub name: Option<String>,
+    pub thousands_separator: Option<String>,
+    pub decimal_mark: Option<String>,
+    /// Where the currency goes, e.g. `{symbol}{amount}` or `{amount} {code}`.
+    pub currency_format: Option<String>,
+    pub date_format: Option<String>,
+    /// The format of the months which head the columns of `list balances`,
+    /// `list outgoings` and `list incomings`.
+    pub month_format: Option<String>,
+    pub hide_currency: Option<bool>,
+}
+
+/// How amounts and dates are displayed, once a `Locale` has been resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DisplayFormat {
+    pub thousands_separator: String,
+    pub decimal_mark: String,
+    pub currency_format: String,
+    pub date_format: String,
+    pub month_format: String,
+    pub hide_currency: bool,
+}
+
+impl DisplayFormat {
+    /// Amounts and dates as the Teller API gives them, e.g. `1234.56 GBP`.
+    pub fn new() -> DisplayFormat {
+        DisplayFormat {
+            thousands_separator: "".to_string(),
+            decimal_mark: ".".to_string(),
+            currency_format: "{amount} {code}".to_string(),
+            date_format: DATE_FORMAT.to_string(),
+            month_format: MONTH_FORMAT.to_string(),
+            hide_currency: false,
+        }
+    }
+
+    /// The formats of a locale such as `en_GB`, or `None` if it is not known.
+    pub fn for_locale_name(name: &str) -> Option<DisplayFormat> {
+        let formats = match &name.replace("-", "_")[..] {
+            "en_GB" => Some((",", ".", "{symbol}{amount}", "%d/%m/%Y", "%b %Y")),
+            "en_US" => Some((",", ".", "{symbol}{amount}", "%m/%d/%Y", "%b %Y")),
+            "de_DE" => Some((".", ",", "{amount} {symbol}", "%d.%m.%Y", "%m.%Y")),
+            _ => None,
+        };
+        formats.map(|(thousands_separator, decimal_mark, currency_format, date_format, month_format)| {
+            DisplayFormat {
+                thousands_separator: thousands_separator.to_string(),
+                decimal_mark: decimal_mark.to_string(),
+                currency_format: currency_format.to_string(),
+                date_format: date_format.to_string(),
+                month_format: month_format.to_string(),
+                hide_currency: false,
+            }
+        })
+    }
+
+    /// The formats of a locale from the config, where any field overrides its named
+    /// locale.
+    pub fn from_locale(locale: &Locale) -> DisplayFormat {
+        let mut display_format = match locale.name {
+            Some(ref name) => {
+                DisplayFormat::for_locale_name(&name).unwrap_or_else(|| {
+                    warn!("There is no locale named {} so amounts and dates will be displayed \
+                           as they are given",
+                          name);
+                    DisplayFormat::new()
+                })
+            }
+            None => DisplayFormat::new(),
+        };
+
+        if let Some(ref thousands_separator) = locale.thousands_separator {
+            display_format.thousands_separator = thousands_separator.to_owned();
+        }
+        match locale.decimal_mark {
+            Some(ref decimal_mark) if !decimal_mark.is_empty() => {
+                display_format.decimal_mark = decimal_mark.to_owned()
+            }
+            _ => (),
+        }
+        if let Some(ref currency_format) = locale.currency_format {
+            display_format.currency_format = currency_format.to_owned();
+        }
+        let date_formats = vec![(&locale.date_format, &mut display_format.date_format),
+                                (&locale.month_format, &mut display_format.month_format)];
+        for (format, display_date_format) in date_formats {
+            match *format {
+                Some(ref format) if is_valid_date_format(&format) => {
+                    *display_date_format = format.to_owned()
+                }
+                Some(ref format) => warn!("The date format {} is not valid so will be ignored", format),
+                None => (),
+            }
+        }
+        if let Some(hide_currency) = locale.hide_currency {
+            display_format.hide_currency = hide_currency;
+        }
+        display_format
+    }
+
+    // The sign is kept apart so that it can be put before a currency symbol.
+    fn to_signed_parts(&self, amount: &str) -> Option<(bool, String)> {
+        let amount = amount.trim();
+        let is_negative = amount.starts_with('-');
+        let unsigned = amount.trim_left_matches(|c| c == '-' || c == '+');
+        let mut parts = unsigned.splitn(2, '.');
+        let whole = parts.next().unwrap_or("");
+        let fraction = parts.next();
+        let is_number = !whole.is_empty() && whole.chars().all(|c| c.is_digit(10)) &&
+                        fraction.map_or(true, |f| f.chars().all(|c| c.is_digit(10)));
+        if !is_number {
+            return None;
+        }
+
+        let mut grouped_whole = String::new();
+        for (idx, c) in whole.chars().enumerate() {
+            if idx > 0 && (whole.len() - idx) % 3 == 0 {
+                grouped_whole.push_str(&self.thousands_separator);
+            }
+            grouped_whole.push(c);
+        }
+        let formatted = match fraction {
+            Some(fraction) => format!("{}{}{}", grouped_whole, self.decimal_mark, fraction),
+            None => grouped_whole,
+        };
+        Some((is_negative, formatted))
+    }
+
+    /// Groups the thousands of an amount and uses the decimal mark, e.g.
+    /// `-1234.56` becomes `-1.234,56`. Anything else is left as it is.
+    pub fn format_amount(&self, amount: &str) -> String {
+        match self.to_signed_parts(&amount) {
+            Some((true, formatted)) => format!("-{}", formatted),
+            Some((false, formatted)) => formatted,
+            None => amount.to_string(),
+        }
+    }
+
+    /// Displays an amount with its currency, e.g. `£1,234.56`, unless the currency is hidden.
+    pub fn format_money(&self, amount: &str, currency: &str, hide_currency: &bool) -> String {
+        if *hide_currency || self.hide_currency {
+            return self.format_amount(&amount);
+        }
+        match self.to_signed_parts(&amount) {
+            Some((is_negative, formatted)) => {
+                let money = self.currency_format
+                                .replace("{amount}", &formatted)
+                                .replace("{symbol}", get_currency_symbol(&currency))
+                                .replace("{code}", &currency);
+                if is_negative {
+                    format!("-{}", money)
+                } else {
+                    money
+                }
+            }
+            None => format!("{} {}", amount, currency),
+        }
+    }
+
+    /// Displays a `YYYY-MM-DD` date.
+    pub fn format_date(&self, date: &str) -> String {
+        NaiveDate::parse_from_str(&date, DATE_FORMAT)
+            .map(|date| date.format(&self.date_format).to_string())
+            .unwrap_or(date.to_string())
+    }
+
+    /// Displays an `MM-YYYY` month.
+    pub fn format_month(&self, month: &str) -> String {
+        NaiveDate::parse_from_str(&format!("01-{}", month), &format!("%d-{}", MONTH_FORMAT))
+            .map(|date| date.format(&self.month_format).to_string())
+            .unwrap_or(month.to_string())
+    }
+
+    /// Reads back an amount which `format_amount` or `format_money` wrote,
+    /// without mistaking dates or counts for one.
+    pub fn parse_amount(&self, text: &str) -> Option<f64> {
+        let is_negative = text.starts_with('-');
+        let unsigned = text.trim_left_matches('-').trim_matches(is_currency_symbol);
+        let mut parts = unsigned.splitn(2, &self.decimal_mark[..]);
+        let (whole, fraction) = match (parts.next(), parts.next()) {
+            (Some(whole), Some(fraction)) if !fraction.is_empty() => (whole, fraction),
+            _ => return None,
+        };
+        let groups: Vec<&str> = if self.thousands_separator.is_empty() {
+            vec![whole]
+        } else {
+            whole.split(&self.thousands_separator[..]).collect()
+        };
+        let is_grouped = groups.iter().enumerate().all(|(idx, group)| {
+            let is_sized = if idx == 0 {
+                !group.is_empty() && (groups.len() == 1 || group.len() <= 3)
+            } else {
+                group.len() == 3
+            };
+            is_sized && group.chars().all(|c| c.is_digit(10))
+        });
+        if !is_grouped || !fraction.chars().all(|c| c.is_digit(10)) {
+            return None;
+        }
+
+        format!("{}{}.{}", if is_negative { "-" } else { "" }, groups.concat(), fraction)
+            .parse::<f64>()
+            .ok()
+    }
+}
+
+lazy_static! {
+    static ref DISPLAY_FORMAT: Mutex<DisplayFormat> = Mutex::new(DisplayFormat::new());
+}
+
+/// Displays every amount and date from now on as `locale` does.
+pub fn set_locale(locale: &Locale) {
+    *DISPLAY_FORMAT.lock().unwrap() = DisplayFormat::from_locale(&locale);
+}
+
+/// The display format set by `set_locale`, for code which is given one
+/// rather than reading it.
+pub fn get_display_format() -> DisplayFormat {
+    DISPLAY_FORMAT.lock().unwrap().clone()
+}
+
+/// Displays an amount as the locale does. See `DisplayFormat::format_amount`.
+pub fn format_amount(amount: &str) -> String {
+    DISPLAY_FORMAT.lock().unwrap().format_amount(&amount)
+}
+
+/// Displays an amount with its currency as the locale does.
+pub fn format_money(amount: &str, currency: &str, hide_currency: &bool) -> String {
+    DISPLAY_FORMAT.lock().unwrap().format_money(&amount, &currency, &hide_currency)
+}
+
+/// Displays a `YYYY-MM-DD` date as the locale does.
+pub fn format_date(date: &str) -> String {
+    DISPLAY_FORMAT.lock().unwrap().format_date(&date)
+}
+
+/// Displays an `MM-YYYY` month as the locale does.
+pub fn format_month(month: &str) -> String {
+    DISPLAY_FORMAT.lock().unwrap().format_month(&month)
+}
+
+/// Reads back an amount which the locale displayed.
+pub fn parse_amount(text: &str) -> Option<f64> {
+    DISPLAY_FORMAT.lock().unwrap().parse_amount(&text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Locale, DisplayFormat};
+
+    fn to_locale(name: &str) -> Locale {
+        Locale {
+            name: Some(name.to_string()),
+            thousands_separator: None,
+            decimal_mark: None,
+            currency_format: None,
+            date_format: None,
+            month_format: None,
+            hide_currency: None,
+        }
+    }
+
+    #[test]
+    fn can_display_amounts_and_dates_as_given() {
+        let display_format = DisplayFormat::new();
+
+        assert_eq!("-1234.56", display_format.format_amount("-1234.56"));
+        assert_eq!("1234.56 GBP", display_format.format_money("1234.56", "GBP", &false));
+        assert_eq!("1234.56", display_format.format_money("1234.56", "GBP", &true));
+        assert_eq!("2016-01-21", display_format.format_date("2016-01-21"));
+        assert_eq!("01-2016", display_format.format_month("01-2016"));
+    }
+
+    #[test]
+    fn can_display_amounts_and_dates_for_locale() {
+        let en_gb = DisplayFormat::from_locale(&to_locale("en_GB"));
+        assert_eq!("£1,234.56", en_gb.format_money("1234.56", "GBP", &false));
+        assert_eq!("-£2.70", en_gb.format_money("-2.70", "GBP", &false));
+        assert_eq!("-1,234,567.00", en_gb.format_amount("-1234567.00"));
+        assert_eq!("21/01/2016", en_gb.format_date("2016-01-21"));
+        assert_eq!("Jan 2016", en_gb.format_month("01-2016"));
+
+        let mut de_de = to_locale("de-DE");
+        de_de.currency_format = Some("{amount} {code}".to_string());
+        de_de.date_format = Some("%Q".to_string());
+        let de_de = DisplayFormat::from_locale(&de_de);
+        assert_eq!("1.234,56 EUR", de_de.format_money("1234.56", "EUR", &false));
+        assert_eq!("21.01.2016", de_de.format_date("2016-01-21"));
+        assert_eq!("01.2016", de_de.format_month("01-2016"));
+    }
+
+    #[test]
+    fn can_parse_displayed_amounts() {
+        let de_de = DisplayFormat::from_locale(&to_locale("de_DE"));
+
+        assert_eq!(Some(-1234.56), de_de.parse_amount("-1.234,56"));
+        assert_eq!(Some(2.7), de_de.parse_amount("2,70€"));
+        assert_eq!(None, de_de.parse_amount("21.01.2016"));
+        assert_eq!(None, de_de.parse_amount("12,5%"));
+        assert_eq!(Some(1500f64), DisplayFormat::new().parse_amount("1500.00"));
+    }
+}
@@ -1,6 +1,3 @@
-#[cfg(test)] #[macro_use]
-extern crate yup_hyper_mock;
-
 #[macro_use]
 extern crate log;
 extern crate env_logger;
@@ -10,31 +7,44 @@ extern crate rustc_serialize;
 extern crate chrono;
 extern crate hyper;
 extern crate tabwriter;
-extern crate itertools;
+extern crate libc;
+
+extern crate teller;
 
-mod cli;
 mod command;
-mod config;
-mod inquirer;
-mod api;
+
+// The commands are written against the library as if its modules were our own.
+use teller::{api, check, cli, color, config, export, inquirer, locale, redact};
 
 use docopt::Docopt;
-use cli::get_command_type;
+use env_logger::LogBuilder;
+use log::LogRecord;
+use cli::{CliArgs, get_command_type};
 use command::execute;
+use std::env;
 use std::process;
 
 const VERSION: Option<&'static str> = option_env!("CARGO_PKG_VERSION");
 const USAGE: &'static str = "Banking for the command line.
 
 Usage:
-    teller init
-    teller [list] accounts
-    teller [list] transactions [<account> --timeframe=<tf> --show-description]
-    teller [list] counterparties [<account> --timeframe=<tf> --count=<n>]
-    teller [list] (balances|outgoings|incomings) [<account> --interval=<itv> --timeframe=<tf> --output=<of>]
-    teller [show] balance [<account> --hide-currency]
-    teller [show] outgoing [<account> --hide-currency]
-    teller [show] incoming [<account> --hide-currency]
+    teller init [--auth-token=<token> --alias=<alias>... --from-file=<path> --yes --config=<path> --profile=<name>]
+    teller [list] accounts [--redact --color=<when> --config=<path> --profile=<name>]
+    teller [list] transactions [<account> --timeframe=<tf> --show-description --input=<path> --redact --color=<when> --config=<path> --profile=<name>]
+    teller [list] counterparties [<account> --timeframe=<tf> --count=<n> --output=<of> --input=<path> --redact --color=<when> --config=<path> --profile=<name>]
+    teller [list] (balances|outgoings|incomings) [<account> --interval=<itv> --timeframe=<tf> --output=<of> --input=<path> --redact --color=<when> --config=<path> --profile=<name>]
+    teller [list] anomalies [<account> --timeframe=<tf> --threshold=<amt> --within=<days> --input=<path> --redact --color=<when> --config=<path> --profile=<name>]
+    teller [show] balance [<account> --hide-currency --input=<path> --redact --color=<when> --config=<path> --profile=<name>]
+    teller [show] outgoing [<account> --hide-currency --input=<path> --redact --color=<when> --config=<path> --profile=<name>]
+    teller [show] incoming [<account> --hide-currency --input=<path> --redact --color=<when> --config=<path> --profile=<name>]
+    teller [show] counterparty <counterparty> [<account> --interval=<itv> --timeframe=<tf> --input=<path> --redact --color=<when> --config=<path> --profile=<name>]
+    teller stats [<account> --timeframe=<tf> --input=<path> --redact --color=<when> --config=<path> --profile=<name>]
+    teller watch [<account> --every=<dur> --hook=<cmd> --redact --config=<path> --profile=<name>]
+    teller check [<rule>... --perfdata --redact --config=<path> --profile=<name>]
+    teller metrics [--timeframe=<tf> --count=<n> --textfile=<path> --redact --config=<path> --profile=<name>]
+    teller serve [--listen=<addr> --refresh=<dur> --input=<path> --redact --config=<path> --profile=<name>]
+    teller export [<account> --format=<fmt> --timeframe=<tf> --out=<path> --input=<path> --redact --config=<path> --profile=<name>]
+    teller import <file> --account=<alias> [--format=<fmt> --columns=<mapping> --date-format=<fmt> --decimal-mark=<mark> --config=<path> --profile=<name>]
     teller [--help | --version]
 
 Commands:
@@ -45,12 +55,29 @@ Commands:
     list balances           List balances during a timeframe.
     list outgoings          List outgoings during a timeframe.
     list incomings          List incomings during a timeframe.
+    list anomalies          List unusual transactions and months during a timeframe.
     show balance            Show the current balance.
     show outgoing           Show the current outgoing.
     show incoming           Show the current incoming.
+    show counterparty       Show the transactions and spending habits for a counterparty.
+    stats                   Show descriptive statistics for outgoings and incomings.
+    watch                   Print new transactions as they arrive.
+    check                   Check rules and exit with a Nagios-style status.
+    metrics                 Show balances, incomings, outgoings and counterparties as OpenMetrics.
+    serve                   Serve accounts, transactions and aggregates as read-only JSON.
+    export                  Export transactions as a ledger, hledger or beancount journal, OFX, QIF or SQLite.
+    import                  Import an OFX, QIF or CSV statement into an account Teller does not cover.
 
     NOTE: By default commands are applied to the 'current' <account>.
 
+    NOTE: A <rule> describes when to alert (e.g. 'balance of savings < 500',
+          'warning: outgoings this month > 2000' or 'no salary received by the 3rd').
+          Without any <rule> the `checks` in the config are used.
+
+    NOTE: The config is read from --config, $TELLER_CONFIG, $XDG_CONFIG_HOME/teller/config
+          or ~/.tellerrc, in that order. $TELLER_AUTH_TOKEN and $TELLER_ACCOUNT_<ALIAS>
+          (e.g. $TELLER_ACCOUNT_CURRENT) override the config.
+
 Options:
     -h --help               Show this screen.
     -V --version            Show version.
@@ -58,19 +85,68 @@ Options:
     -t --timeframe=<tf>     Operate upon a named period of time [default: 6-months].
     -c --count=<n>          Only the top N elements [default: 10].
     -d --show-description   Show descriptions against transactions.
+    --threshold=<amt>       Flag first payments to new counterparties above an amount [default: 100].
+    --within=<days>         Flag repeated charges within a number of days [default: 3].
     -c --hide-currency      Show money without currency codes.
-    -o --output=<of>        Output in a particular format (e.g. spark).
+    -o --output=<of>        Output in a particular format (spark, sparkline, bars or line).
+    -e --every=<dur>        Poll for new transactions this often (e.g. 30s, 5m, 1h) [default: 5m].
+    --hook=<cmd>            Run a command with each new transaction as JSON on its stdin.
+    --perfdata              Show performance data after the check summary.
+    --textfile=<path>       Write metrics to a file for the node_exporter textfile collector.
+    --listen=<addr>         Listen for HTTP requests on an address [default: 127.0.0.1:8080].
+    --refresh=<dur>         Fetch from the API again once responses are this old [default: 5m].
+    --input=<path>          Read transactions from a JSON, CSV, OFX or QIF statement (or - for stdin) instead of Teller.
+    --out=<path>            Write an export to a file, which is needed by --format=sqlite.
+    --account=<alias>       Import into the account with this alias, which is created if it is new.
+    --format=<fmt>          Export as ledger, hledger, beancount, ofx, qif or sqlite, or import a statement as
+                            ofx, qif, csv or json rather than guessing.
+    --columns=<mapping>     Map fields to CSV headers (e.g. date=Posted,counterparty=Payee).
+    --date-format=<fmt>     Read statement dates with a strftime format (e.g. %d/%m/%Y).
+    --decimal-mark=<mark>   Read statement amounts with . or , as their decimal mark.
+    --redact                Mask account numbers, bank codes and counterparties in output and logs.
+    --color=<when>          Colour output as auto, always or never, where auto colours it for a terminal
+                            unless $NO_COLOR is set [default: auto].
+    --config=<path>         Read the config from a path instead of discovering it.
+    --profile=<name>        Use a named profile of the config (or $TELLER_PROFILE).
+    --auth-token=<token>    Initialise without any questions using this `auth_token`.
+    --alias=<alias>         Point an alias at an account id or row (e.g. current=2).
+    --from-file=<path>      Initialise without any questions using the answers within a JSON file.
+    -y --yes                Replace an existing profile when initialising without any questions.
 ";
 
+fn init_logger() {
+    // Every log line is passed through the redactor so that `RUST_LOG=debug`
+    // output is safe to paste into a bug report.
+    let mut builder = LogBuilder::new();
+    builder.format(|record: &LogRecord| {
+        redact::redact(&format!("{}:{}: {}",
+                                record.level(),
+                                record.location().module_path(),
+                                record.args()))
+    });
+    if let Ok(rust_log) = env::var("RUST_LOG") {
+        builder.parse(&rust_log);
+    }
+    builder.init().unwrap();
+}
+
 fn main() {
-    env_logger::init().unwrap();
-
-    let arguments = Docopt::new(USAGE)
-                        .and_then(|d| {
-                            d.version(VERSION.map(|v| v.to_string()))
-                             .decode()
-                        })
-                        .unwrap_or_else(|e| e.exit());
+    init_logger();
+
+    let arguments: CliArgs = Docopt::new(USAGE)
+                                 .and_then(|d| {
+                                     d.version(VERSION.map(|v| v.to_string()))
+                                      .decode()
+                                 })
+                                 .unwrap_or_else(|e| e.exit());
+
+    if arguments.flag_redact {
+        redact::enable_redaction();
+    }
+
+    if color::should_color_stdout(&arguments.flag_color) {
+        color::enable_color();
+    }
 
     let command_type = get_command_type(&arguments);
 
@@ -0,0 +1,203 @@
+use std::sync::Mutex;
+
+/// The kinds of value which `--redact` masks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SecretKind {
+    AuthToken,
+    AccountId,
+    AccountNumber,
+    BankCode,
+    Counterparty,
+}
+
+impl SecretKind {
+    fn get_label(&self) -> &'static str {
+        match *self {
+            SecretKind::AuthToken => "auth_token",
+            SecretKind::AccountId => "account",
+            SecretKind::AccountNumber => "account number",
+            SecretKind::BankCode => "bank code",
+            SecretKind::Counterparty => "counterparty",
+        }
+    }
+
+    fn get_json_key(&self) -> Option<&'static str> {
+        match *self {
+            SecretKind::AuthToken => Some("auth_token"),
+            SecretKind::AccountId => None,
+            SecretKind::AccountNumber => Some("account_number"),
+            SecretKind::BankCode => Some("bank_code"),
+            SecretKind::Counterparty => Some("counterparty"),
+        }
+    }
+
+    // Short values could be found within amounts, dates or currency codes, so
+    // they are not treated as secrets.
+    fn get_min_length(&self) -> usize {
+        match *self {
+            SecretKind::Counterparty => 3,
+            _ => 4,
+        }
+    }
+}
+
+const JSON_SECRET_KINDS: [SecretKind; 4] = [SecretKind::AuthToken,
+                                            SecretKind::AccountNumber,
+                                            SecretKind::BankCode,
+                                            SecretKind::Counterparty];
+
+#[derive(Debug)]
+struct Secret {
+    kind: SecretKind,
+    value: String,
+    mask: String,
+}
+
+/// Masks every secret it has been told about, replacing each with a label such
+/// as `[account number 1]` so that different accounts can still be told apart.
+#[derive(Debug)]
+pub struct Redactor {
+    secrets: Vec<Secret>,
+    redact_counterparties: bool,
+    redact_output: bool,
+}
+
+impl Redactor {
+    /// A redactor without any secrets, which only masks output once enabled.
+    pub fn new() -> Redactor {
+        Redactor {
+            secrets: vec![],
+            redact_counterparties: false,
+            redact_output: false,
+        }
+    }
+
+    /// Masks `value` from now on, unless it is too short to tell from other text.
+    pub fn add_secret(&mut self, kind: SecretKind, value: &str) {
+        if (kind == SecretKind::Counterparty && !self.redact_counterparties) ||
+           value.len() < kind.get_min_length() ||
+           self.secrets.iter().any(|secret| secret.value == value) {
+            return;
+        }
+
+        let mask = match kind {
+            SecretKind::AuthToken => format!("[{}]", kind.get_label()),
+            _ => {
+                let count = self.secrets.iter().filter(|secret| secret.kind == kind).count();
+                format!("[{} {}]", kind.get_label(), count + 1)
+            }
+        };
+        self.secrets.push(Secret {
+            kind: kind,
+            value: value.to_string(),
+            mask: mask,
+        });
+        // The longest secrets are replaced first in case one contains another.
+        self.secrets.sort_by(|a, b| b.value.len().cmp(&a.value.len()));
+    }
+
+    // API responses are logged before they are decoded, so any secrets in them
+    // are found by their keys.
+    fn add_secrets_from_json(&mut self, text: &str) {
+        for kind in JSON_SECRET_KINDS.iter() {
+            let json_key = match kind.get_json_key() {
+                Some(json_key) => format!("\"{}\"", json_key),
+                None => continue,
+            };
+            let mut values = vec![];
+            for (idx, _) in text.match_indices(&json_key[..]) {
+                let rest = text[idx + json_key.len()..].trim_left();
+                if !rest.starts_with(':') {
+                    continue;
+                }
+                let rest = rest[1..].trim_left();
+                if !rest.starts_with('"') {
+                    continue;
+                }
+                let rest = &rest[1..];
+                let mut is_escaped = false;
+                for (value_end, c) in rest.char_indices() {
+                    if c == '"' && !is_escaped {
+                        values.push(rest[..value_end].to_string());
+                        break;
+                    }
+                    is_escaped = c == '\\' && !is_escaped;
+                }
+            }
+            for value in values {
+                self.add_secret(*kind, &value);
+            }
+        }
+    }
+
+    /// Masks every secret within some text, including any found within API responses.
+    pub fn redact(&mut self, text: &str) -> String {
+        self.add_secrets_from_json(&text);
+        self.secrets
+            .iter()
+            .fold(text.to_string(), |text, secret| text.replace(&secret.value[..], &secret.mask))
+    }
+}
+
+lazy_static! {
+    static ref REDACTOR: Mutex<Redactor> = Mutex::new(Redactor::new());
+}
+
+/// Masks `value` within logs, and within output with `--redact`.
+pub fn add_secret(kind: SecretKind, value: &str) {
+    REDACTOR.lock().unwrap().add_secret(kind, &value)
+}
+
+/// `--redact` masks counterparty names as well and applies the masking to the
+/// output of commands.
+pub fn enable_redaction() {
+    let mut redactor = REDACTOR.lock().unwrap();
+    redactor.redact_counterparties = true;
+    redactor.redact_output = true;
+}
+
+/// Masks every secret within a log message.
+pub fn redact(text: &str) -> String {
+    REDACTOR.lock().unwrap().redact(&text)
+}
+
+/// Masks every secret within the output of a command if `--redact` was given.
+pub fn redact_output(text: &str) -> String {
+    let mut redactor = REDACTOR.lock().unwrap();
+    if redactor.redact_output {
+        redactor.redact(&text)
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Redactor, SecretKind};
+
+    #[test]
+    fn can_redact_secrets() {
+        let mut redactor = Redactor::new();
+        redactor.add_secret(SecretKind::AuthToken, "fake-auth-token");
+        redactor.add_secret(SecretKind::AccountId, "account-id-1");
+        redactor.add_secret(SecretKind::AccountId, "account-id-2");
+        redactor.add_secret(SecretKind::Counterparty, "NANNA'S");
+
+        assert_eq!("Bearer [auth_token] GET /accounts/[account 2]/transactions NANNA'S",
+                   redactor.redact("Bearer fake-auth-token GET /accounts/account-id-2/transactions \
+                                    NANNA'S"));
+    }
+
+    #[test]
+    fn can_redact_secrets_found_in_json() {
+        let mut redactor = Redactor::new();
+        redactor.redact_counterparties = true;
+
+        assert_eq!("[{\"account_number\":\"[account number 1]\",\"bank_code\":\"[bank code 1]\"}] \
+                    [account number 1]",
+                   redactor.redact("[{\"account_number\":\"12345678\",\"bank_code\":\"601613\"}] \
+                                    12345678"));
+        assert_eq!("{\"counterparty\": \"[counterparty 1]\", \"amount\": \"-2.70\"}",
+                   redactor.redact("{\"counterparty\": \"NANNA'S\", \"amount\": \"-2.70\"}"));
+    }
+}